@@ -50,6 +50,18 @@ pub struct Args {
     /// The checkpoint sync URL to use.
     #[clap(long)]
     pub checkpoint_sync_url: Option<String>,
+
+    /// Record every L1 provider call to this fixture file.
+    #[clap(long, conflicts_with = "replay")]
+    pub record: Option<String>,
+
+    /// Replay L1 provider calls offline from this fixture file.
+    #[clap(long)]
+    pub replay: Option<String>,
+
+    /// Ingest from an auto-advancing mock chain instead of the L1 RPC.
+    #[clap(long, conflicts_with_all = ["record", "replay"])]
+    pub mock: bool,
 }
 
 /// Non-optional arguments.
@@ -75,6 +87,12 @@ pub struct BuiltArgs {
     pub checkpoint_hash: String,
     /// The checkpoint sync URL to use.
     pub checkpoint_sync_url: String,
+    /// The fixture file to record provider calls to.
+    pub record: Option<String>,
+    /// The fixture file to replay provider calls from.
+    pub replay: Option<String>,
+    /// Whether to ingest from a mock chain.
+    pub mock: bool,
 }
 
 /// A helper macro that takes an Option<T> and returns the value in anyhow::Result<T>
@@ -115,6 +133,9 @@ impl BuiltArgs {
             rpc_port: args.rpc_port,
             checkpoint_hash,
             checkpoint_sync_url,
+            record: args.record,
+            replay: args.replay,
+            mock: args.mock,
         })
     }
 }
@@ -1,11 +1,40 @@
 //! Driver Configuration
 
-use axos::stages::driver::DriverConfig;
+use axos::stages::driver::{Driver, DriverConfig};
+use axos_providers::http::HttpProvider;
+use axos_providers::replay::{RecordingProvider, ReplayProvider};
 
 /// Build the driver configuration from the CLI arguments.
 pub fn build_driver_config(args: &crate::cli::BuiltArgs) -> DriverConfig {
     DriverConfig {
+        l1_rpc_url: args.l1_rpc_url.clone(),
         l2_rpc_url: args.l2_rpc_url.clone(),
+        mock: args.mock,
         chain_config: axos_primitives::ChainConfig::base(),
     }
 }
+
+/// Build the driver, using an offline or recording provider if requested.
+///
+/// L1 calls are recorded to the fixture file and the L2 head info
+/// to a sibling fixture with an `.l2` suffix.
+pub fn build_driver(args: &crate::cli::BuiltArgs, config: DriverConfig) -> anyhow::Result<Driver> {
+    if let Some(path) = &args.replay {
+        tracing::info!(target: "axos", "Replaying provider calls from {}", path);
+        let l1 = ReplayProvider::open(path)?;
+        let l2 = ReplayProvider::open(l2_fixture(path))?;
+        return Ok(Driver::with_providers(l1, &l2, &config)?);
+    }
+    if let Some(path) = &args.record {
+        tracing::info!(target: "axos", "Recording provider calls to {}", path);
+        let l1 = RecordingProvider::new(HttpProvider::new(&args.l1_rpc_url), path);
+        let l2 = RecordingProvider::new(HttpProvider::new(&args.l2_rpc_url), l2_fixture(path));
+        return Ok(Driver::with_providers(l1, &l2, &config)?);
+    }
+    Ok(Driver::try_from(config)?)
+}
+
+/// Returns the path of the L2 fixture recorded next to the given one.
+fn l2_fixture(path: &str) -> String {
+    format!("{}.l2", path)
+}
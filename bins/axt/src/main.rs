@@ -2,7 +2,7 @@
 fn sync_pipe(args: axt::cli::BuiltArgs) -> anyhow::Result<()> {
     // First Stage: driver
     let config = axt::driver::build_driver_config(&args);
-    let first_stage = axos::stages::driver::Driver::from(config);
+    let first_stage = axt::driver::build_driver(&args, config)?;
     tracing::info!("Built first stage: {:?}", first_stage);
 
     Ok(())
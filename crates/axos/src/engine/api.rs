@@ -16,12 +16,15 @@ use alloc::format;
 
 use super::config::EngineConfig;
 
+/// The url scheme of an IPC engine api endpoint.
+pub const IPC_SCHEME: &str = "ipc://";
+
 /// The Engine Api
 #[derive(Debug, Clone)]
 pub struct EngineApi {
-    /// Base request url
+    /// Base request url, or `ipc://<path>` for a Unix socket
     pub base_url: GenericString,
-    /// The url port
+    /// The url port, `0` for a Unix socket
     pub port: u16,
     /// Internal [JwtSecret][axos_primitives::jwt::JwtSecret] used to authenticate with the engine api.
     #[allow(dead_code)]
@@ -47,7 +50,7 @@ impl EngineApi {
         let (base_url, port) = Self::split_base_url(base_url).unwrap_or_else(|_| {
             panic!(
                 "Invalid base url. \
-                Must be of the form \"http://<addr>:<port>\""
+                Must be of the form \"http://<addr>:<port>\" or \"ipc://<path>\""
             )
         });
         Self {
@@ -57,9 +60,25 @@ impl EngineApi {
         }
     }
 
-    /// Splits the base url into the address and port
+    /// Returns the path of the Unix socket, if the engine api is
+    /// served over IPC.
+    pub fn ipc_path(&self) -> Option<&str> {
+        self.base_url.strip_prefix(IPC_SCHEME)
+    }
+
+    /// Splits the base url into the address and port.
+    /// IPC endpoints, given as `ipc://<path>` or an absolute socket path,
+    /// are normalized to `ipc://<path>` with port `0`.
     fn split_base_url(base_url: impl Into<GenericString>) -> Result<(GenericString, u16)> {
         let binding = base_url.into();
+        if let Some(path) = Self::ipc_socket(&binding) {
+            if path.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Empty socket path. Must be of the form \"ipc://<path>\""
+                ));
+            }
+            return Ok((format!("{}{}", IPC_SCHEME, path), 0));
+        }
         let prefix = binding.split_once("http://").map(|_| "http://").unwrap_or(
             binding
                 .split_once("https://")
@@ -89,8 +108,18 @@ impl EngineApi {
         Ok((addr, port))
     }
 
-    /// Constructs the base engine api url for the given address
+    /// Returns the socket path of an IPC endpoint.
+    fn ipc_socket(addr: &str) -> Option<&str> {
+        addr.strip_prefix(IPC_SCHEME)
+            .or_else(|| addr.starts_with('/').then_some(addr))
+    }
+
+    /// Constructs the base engine api url for the given address.
+    /// IPC endpoints are returned as `ipc://<path>`.
     pub fn auth_url_from_addr(addr: &str, port: Option<u16>) -> GenericString {
+        if let Some(path) = Self::ipc_socket(addr) {
+            return GenericString::from(format!("{}{}", IPC_SCHEME, path));
+        }
         let stripped = addr.strip_prefix("http://").unwrap_or(addr);
         let stripped = addr.strip_prefix("https://").unwrap_or(stripped);
         let port = port.unwrap_or(DEFAULT_AUTH_PORT);
@@ -120,6 +149,16 @@ impl EngineApi {
     }
 }
 
+#[cfg(all(feature = "std", unix))]
+impl EngineApi {
+    /// Returns a transport to the engine api, if it is served over IPC.
+    /// IPC connections are trusted and not authenticated with the
+    /// [JwtSecret].
+    pub fn ipc_transport(&self) -> Option<axos_providers::ipc::IpcTransport> {
+        self.ipc_path().map(axos_providers::ipc::IpcTransport::new)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,6 +213,39 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_engine_api_split_ipc() {
+        let (addr, port) = EngineApi::split_base_url("ipc:///tmp/reth.ipc").unwrap();
+        assert_eq!(addr, "ipc:///tmp/reth.ipc");
+        assert_eq!(port, 0);
+        let (addr, _) = EngineApi::split_base_url("/tmp/reth.ipc").unwrap();
+        assert_eq!(addr, "ipc:///tmp/reth.ipc");
+        assert!(EngineApi::split_base_url("ipc://").is_err());
+    }
+
+    #[test]
+    fn test_engine_api_ipc_path() {
+        let base_url = EngineApi::auth_url_from_addr("/tmp/reth.ipc", None);
+        let engine_api = EngineApi::new(base_url, SECRET);
+        assert_eq!(engine_api.ipc_path(), Some("/tmp/reth.ipc"));
+        let engine_api = EngineApi::new("http://localhost:8551".into(), SECRET);
+        assert_eq!(engine_api.ipc_path(), None);
+    }
+
+    #[cfg(all(feature = "std", unix))]
+    #[test]
+    fn test_engine_api_ipc_transport() {
+        use axos_providers::test_utils::MockIpcServer;
+        use axos_providers::transport::Transport;
+
+        let server = MockIpcServer::spawn(|request| request.to_vec());
+        let base_url = EngineApi::auth_url_from_addr(&server.path().to_string_lossy(), None);
+        let transport = EngineApi::new(base_url, SECRET).ipc_transport().unwrap();
+        let request =
+            br#"{"jsonrpc":"2.0","id":0,"method":"engine_exchangeCapabilities","params":[[]]}"#;
+        assert_eq!(transport.send(request).unwrap(), request.to_vec());
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn test_engine_api_from_env() {
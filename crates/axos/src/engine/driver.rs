@@ -8,25 +8,22 @@ use crate::engine::api::EngineApi;
 
 use anyhow::Result;
 
-use axos_providers::mock::MockProvider;
 // #[cfg(feature = "serde")]
 // use serde::{Deserialize, Serialize};
 
 use axos_primitives::{BlockInfo, Epoch};
+use axos_providers::provider::Provider;
 
 use super::config::EngineConfig;
 
-// todo: replace the hardcoded MockProvider below
-// with the generic provider trait.
-
 /// The engine driver
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
-pub struct EngineDriver {
+pub struct EngineDriver<P> {
     /// The L2 execution engine
     engine: EngineApi,
     /// Provider for the local L2 execution RPC
-    provider: MockProvider,
+    provider: P,
     /// Blocktime of the L2 chain
     blocktime: u64,
     /// Most recent block found on the p2p network
@@ -41,12 +38,12 @@ pub struct EngineDriver {
     pub finalized_epoch: Epoch,
 }
 
-impl EngineDriver {
+impl<P: Provider> EngineDriver<P> {
     /// Create a new [EngineDriver][crate::engine::EngineDriver] instance.
     pub fn new(
         finalized_head: BlockInfo,
         finalized_epoch: Epoch,
-        provider: MockProvider,
+        provider: P,
         config: impl Into<EngineConfig>,
     ) -> Result<Self> {
         let engine_config: EngineConfig = config.into();
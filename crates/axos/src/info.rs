@@ -3,7 +3,7 @@
 //! Contains utilities for querying chain information.
 
 use axos_primitives::{BlockId, BlockKind, ChainConfig, HeadInfo};
-use axos_providers::provider::Provider;
+use axos_providers::provider::{Error, Provider};
 
 /// A query for the head info.
 #[derive(Debug, Clone)]
@@ -11,19 +11,63 @@ pub struct HeadInfoQuery {}
 
 impl HeadInfoQuery {
     /// Get the head info.
-    pub fn get_head_info<P: Provider>(p: &P, config: &ChainConfig) -> HeadInfo {
-        let b = p
-            .get_block_with_txs(BlockId::Kind(BlockKind::Finalized))
-            .ok()
-            .flatten()
-            .and_then(|block| HeadInfo::try_from(block).ok());
-        b.unwrap_or_else(|| {
-            tracing::warn!("could not get head info. Falling back to the genesis head.");
-            HeadInfo {
-                l2_block_info: config.l2_genesis,
-                l1_epoch: config.l1_start_epoch,
-                sequence_number: 0,
+    ///
+    /// Falls back to the genesis head if the provider has no finalized
+    /// block or the block cannot be converted into a [HeadInfo]. Any other
+    /// provider error is returned so the caller can decide whether to retry
+    /// with [Error::is_retryable].
+    pub fn get_head_info<P: Provider>(p: &P, config: &ChainConfig) -> Result<HeadInfo, Error> {
+        let block = match p.get_block_with_txs(BlockId::Kind(BlockKind::Finalized)) {
+            Ok(block) => block,
+            Err(Error::BlockNotFound) => None,
+            Err(e) => {
+                tracing::warn!("could not get head info: {}", e);
+                return Err(e);
             }
-        })
+        };
+        Ok(block
+            .and_then(|block| HeadInfo::try_from(block).ok())
+            .unwrap_or_else(|| {
+                tracing::warn!("no finalized head. Falling back to the genesis head.");
+                Self::genesis(config)
+            }))
+    }
+
+    /// Returns the genesis head info for the chain.
+    pub fn genesis(config: &ChainConfig) -> HeadInfo {
+        HeadInfo {
+            l2_block_info: config.l2_genesis,
+            l1_epoch: config.l1_start_epoch,
+            sequence_number: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_primitives::BlockWithTransactions;
+
+    /// A provider that always fails with the given error.
+    struct FailingProvider(Error);
+
+    impl Provider for FailingProvider {
+        fn get_block_with_txs(&self, _: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+            Err(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_head_info_not_found_falls_back_to_genesis() {
+        let config = ChainConfig::default();
+        let head = HeadInfoQuery::get_head_info(&FailingProvider(Error::BlockNotFound), &config);
+        assert_eq!(head, Ok(HeadInfoQuery::genesis(&config)));
+    }
+
+    #[test]
+    fn test_head_info_propagates_provider_errors() {
+        let config = ChainConfig::default();
+        let head = HeadInfoQuery::get_head_info(&FailingProvider(Error::Timeout), &config);
+        assert_eq!(head, Err(Error::Timeout));
     }
 }
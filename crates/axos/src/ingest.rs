@@ -4,17 +4,81 @@
 //! consensus layer and buffering them for subsequent processing by the
 //! axos derivation pipeline.
 
+pub mod cursor;
+#[cfg(feature = "alloc")]
+pub mod filter;
+#[cfg(feature = "alloc")]
+pub mod observer;
 pub mod poll_queue;
+#[cfg(feature = "std")]
+pub mod prefetch;
 pub mod provider;
 pub mod queue;
+pub mod tracker;
 pub mod types;
+#[cfg(feature = "ws")]
+pub mod ws;
 
 // Re-export common ingestor types.
 #[doc(inline)]
 pub use types::BlockUpdate;
 
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use axos_providers::async_provider::{AsyncAdapter, BlockingRunner, BoxFuture};
+
 /// Block Ingestor Trait
 pub trait BlockIngestor {
     /// Attempt to ingest a block.
     fn try_ingest(&mut self) -> anyhow::Result<Option<BlockUpdate>>;
+
+    /// Takes the last error the ingestor recovered from on its own since
+    /// this was last called, such as a retryable provider error.
+    fn take_recovered_error(&mut self) -> Option<anyhow::Error> {
+        None
+    }
+
+    /// Records the latest L2 block derived from the ingested blocks, so
+    /// that ingestors with a cursor resume derivation from it.
+    fn set_l2_head(&mut self, _number: u64) {}
+}
+
+/// Async Block Ingestor Trait
+///
+/// The asynchronous counterpart of [BlockIngestor]. Like the
+/// [AsyncProvider][axos_providers::async_provider::AsyncProvider], it
+/// returns boxed futures and does not depend on any executor.
+#[cfg(feature = "alloc")]
+pub trait AsyncBlockIngestor {
+    /// Attempt to ingest a block.
+    fn try_ingest(&mut self) -> BoxFuture<'_, anyhow::Result<Option<BlockUpdate>>>;
+}
+
+/// Exposes a [BlockIngestor] as an [AsyncBlockIngestor].
+/// The block is ingested through the [BlockingRunner] when
+/// [AsyncBlockIngestor::try_ingest] is called.
+#[cfg(feature = "alloc")]
+impl<I: BlockIngestor, R: BlockingRunner> AsyncBlockIngestor for AsyncAdapter<I, R> {
+    fn try_ingest(&mut self) -> BoxFuture<'_, anyhow::Result<Option<BlockUpdate>>> {
+        let update = self.1.run(|| self.0.try_ingest());
+        Box::pin(core::future::ready(update))
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use axos_providers::executor::block_on;
+    use axos_providers::mock::MockChain;
+    use axos_providers::provider::Provider;
+
+    #[test]
+    fn test_async_poll_queue() {
+        let provider = MockChain::builder().blocks(2).build();
+        let poll_queue = poll_queue::PollQueue::from(Box::new(provider) as Box<dyn Provider>);
+        let mut ingestor = AsyncAdapter::inline(poll_queue);
+        let update = block_on(ingestor.try_ingest()).unwrap();
+        assert!(matches!(update, Some(BlockUpdate::NewBlock(_))));
+    }
 }
@@ -0,0 +1,165 @@
+//! Ingestion Cursor
+//!
+//! A [Cursor] records how far ingestion got: the last L1 block handed to
+//! derivation and the latest L2 block derived. Ingestors save it to a
+//! [CursorStore] as they go, and resume from it after a restart instead of
+//! starting over from the finalized head.
+//!
+//! A stored cursor is only trusted if its L1 block is still canonical.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use axos_primitives::B256;
+
+/// The position of an ingestor.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Cursor {
+    /// The number of the last processed L1 block
+    pub l1_number: u64,
+    /// The hash of the last processed L1 block
+    pub l1_hash: B256,
+    /// The latest L2 block number
+    pub l2_number: u64,
+}
+
+/// Persists an ingestion [Cursor].
+pub trait CursorStore: core::fmt::Debug {
+    /// Loads the stored cursor, if any.
+    fn load(&self) -> anyhow::Result<Option<Cursor>>;
+
+    /// Replaces the stored cursor.
+    fn store(&mut self, cursor: Cursor) -> anyhow::Result<()>;
+}
+
+#[cfg(feature = "alloc")]
+impl<S: CursorStore + ?Sized> CursorStore for alloc::boxed::Box<S> {
+    fn load(&self) -> anyhow::Result<Option<Cursor>> {
+        (**self).load()
+    }
+
+    fn store(&mut self, cursor: Cursor) -> anyhow::Result<()> {
+        (**self).store(cursor)
+    }
+}
+
+/// A [CursorStore] that keeps the cursor in memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryCursorStore(pub Option<Cursor>);
+
+impl CursorStore for MemoryCursorStore {
+    fn load(&self) -> anyhow::Result<Option<Cursor>> {
+        Ok(self.0)
+    }
+
+    fn store(&mut self, cursor: Cursor) -> anyhow::Result<()> {
+        self.0 = Some(cursor);
+        Ok(())
+    }
+}
+
+/// A [CursorStore] backed by a file.
+///
+/// The cursor is written as three lines: the L1 block number, the L1
+/// block hash, and the L2 block number. Writes go to a temporary file that
+/// is renamed over the cursor file, so a crash never leaves it torn.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCursorStore {
+    /// The path of the cursor file.
+    path: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl FileCursorStore {
+    /// Instantiates a new [FileCursorStore] at the given path. The file is
+    /// created on the first store.
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the cursor file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+#[cfg(feature = "std")]
+impl CursorStore for FileCursorStore {
+    fn load(&self) -> anyhow::Result<Option<Cursor>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut lines = contents.lines().map(str::trim);
+        let mut next = |field: &str| {
+            lines
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("cursor file is missing the {}", field))
+        };
+        Ok(Some(Cursor {
+            l1_number: next("l1 number")?.parse()?,
+            l1_hash: next("l1 hash")?.parse()?,
+            l2_number: next("l2 number")?.parse()?,
+        }))
+    }
+
+    fn store(&mut self, cursor: Cursor) -> anyhow::Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(
+            &tmp,
+            std::format!(
+                "{}\n{}\n{}\n",
+                cursor.l1_number,
+                cursor.l1_hash,
+                cursor.l2_number
+            ),
+        )?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_cursor_store() {
+        let mut store = MemoryCursorStore::default();
+        assert_eq!(store.load().unwrap(), None);
+        let cursor = Cursor {
+            l1_number: 10,
+            l1_hash: B256::with_last_byte(1),
+            l2_number: 20,
+        };
+        store.store(cursor).unwrap();
+        assert_eq!(store.load().unwrap(), Some(cursor));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_file_cursor_store() {
+        let dir = std::env::temp_dir().join(std::format!("axos-cursor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut store = FileCursorStore::new(dir.join("cursor"));
+        assert_eq!(store.load().unwrap(), None);
+
+        let cursor = Cursor {
+            l1_number: 10,
+            l1_hash: B256::with_last_byte(1),
+            l2_number: 20,
+        };
+        store.store(cursor).unwrap();
+        assert_eq!(
+            FileCursorStore::new(dir.join("cursor")).load().unwrap(),
+            Some(cursor)
+        );
+
+        std::fs::write(store.path(), "10\n").unwrap();
+        assert!(store.load().is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+//! L1 Data Filter
+//!
+//! Derivation only reads a small part of each L1 block: the header, the
+//! batcher transactions sent to the batch inbox, and the logs of the
+//! deposit and system config contracts. An [L1Filter] strips everything
+//! else, so ingestors can buffer compact [L1BlockData] instead of full
+//! blocks.
+
+use alloc::vec::Vec;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    Address, BlockWithTransactions, ChainConfig, Filter, L1BlockData, Log, B256,
+};
+
+/// Selects the derivation-relevant data of L1 blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct L1Filter {
+    /// The batch inbox address
+    pub batch_inbox: Address,
+    /// The batch sender address. Batcher transactions from any other
+    /// sender are dropped, so this must follow system config updates.
+    pub batcher: Address,
+    /// The deposit contract address
+    pub deposit_contract: Address,
+    /// The L1 system config contract
+    pub system_config_contract: Address,
+}
+
+impl From<&ChainConfig> for L1Filter {
+    fn from(config: &ChainConfig) -> Self {
+        Self {
+            batch_inbox: config.batch_inbox,
+            batcher: config.system_config.batch_sender,
+            deposit_contract: config.deposit_contract,
+            system_config_contract: config.system_config_contract,
+        }
+    }
+}
+
+impl L1Filter {
+    /// Returns true if the transaction is a batcher transaction.
+    pub fn is_batcher_transaction(&self, tx: &Transaction) -> bool {
+        tx.to == Some(self.batch_inbox) && tx.from == self.batcher
+    }
+
+    /// Returns true if the log was emitted by the deposit or system
+    /// config contract.
+    pub fn is_relevant_log(&self, log: &Log) -> bool {
+        log.address == self.deposit_contract || log.address == self.system_config_contract
+    }
+
+    /// Drops every transaction of the block but the batcher transactions.
+    pub fn strip(&self, block: &mut BlockWithTransactions) {
+        block
+            .transactions
+            .retain(|tx| self.is_batcher_transaction(tx));
+    }
+
+    /// Returns the log filter for the deposit and system config contract
+    /// logs of a block.
+    pub fn log_filter(&self, block_hash: B256) -> Filter {
+        Filter::new()
+            .at_block_hash(block_hash)
+            .address(self.deposit_contract)
+            .address(self.system_config_contract)
+    }
+
+    /// Strips a block and its logs down to an [L1BlockData].
+    pub fn block_data(&self, mut block: BlockWithTransactions, logs: Vec<Log>) -> L1BlockData {
+        self.strip(&mut block);
+        let logs = logs
+            .into_iter()
+            .filter(|log| self.is_relevant_log(log))
+            .collect();
+        L1BlockData::new(block, logs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_providers::mock::txs::batcher_tx;
+
+    #[test]
+    fn test_strip_block() {
+        let config = ChainConfig::optimism();
+        let filter = L1Filter::from(&config);
+        let batcher = config.system_config.batch_sender;
+        let mut block = BlockWithTransactions {
+            transactions: alloc::vec![
+                batcher_tx(batcher, config.batch_inbox, [1u8]),
+                batcher_tx(Address::with_last_byte(1), config.batch_inbox, [2u8]),
+                batcher_tx(batcher, Address::with_last_byte(2), [3u8]),
+            ],
+            ..Default::default()
+        };
+        filter.strip(&mut block);
+        assert_eq!(
+            block.transactions,
+            [batcher_tx(batcher, config.batch_inbox, [1u8])]
+        );
+    }
+
+    #[test]
+    fn test_relevant_logs() {
+        let config = ChainConfig::optimism();
+        let filter = L1Filter::from(&config);
+        let log = |address| Log {
+            address,
+            ..Default::default()
+        };
+        assert!(filter.is_relevant_log(&log(config.deposit_contract)));
+        assert!(filter.is_relevant_log(&log(config.system_config_contract)));
+        assert!(!filter.is_relevant_log(&log(config.batch_inbox)));
+    }
+}
@@ -0,0 +1,254 @@
+//! Ingestion Observers
+//!
+//! An [ObservedIngestor] wraps any [BlockIngestor] and reports what it
+//! ingests to registered [IngestObserver]s, along with how long each
+//! ingest call took. Embedders can feed their own metrics and alerting
+//! from the observers instead of parsing logs.
+//!
+//! Errors an ingestor recovers from on its own, such as retryable
+//! provider errors, are reported through
+//! [BlockIngestor::take_recovered_error], which only keeps the last one
+//! per ingest call.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use axos_primitives::BlockInfo;
+use axos_providers::clock::Clock;
+
+use crate::ingest::{tracker, BlockIngestor, BlockUpdate};
+
+/// The timing of the ingest call that produced an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Timing {
+    /// When the call started, by the ingestor's clock.
+    pub started: Duration,
+    /// How long the call took.
+    pub elapsed: Duration,
+}
+
+/// Receives ingestion events. Every method does nothing by default.
+pub trait IngestObserver {
+    /// Called when a new block is ingested.
+    fn on_new_block(&mut self, _block: &BlockInfo, _timing: Timing) {}
+
+    /// Called when ingested blocks were replaced.
+    fn on_reorg(&mut self, _common_ancestor: &BlockInfo, _depth: u64, _timing: Timing) {}
+
+    /// Called when the finalized block advances.
+    fn on_finality_update(&mut self, _number: u64, _timing: Timing) {}
+
+    /// Called when the safe block advances.
+    fn on_safe_update(&mut self, _number: u64, _timing: Timing) {}
+
+    /// Called when an ingest call fails, and with the last error the
+    /// ingestor recovered from during the call, if any. Earlier errors
+    /// recovered from during the same call are not reported.
+    fn on_fetch_error(&mut self, _error: &anyhow::Error, _timing: Timing) {}
+}
+
+/// A [BlockIngestor] that reports to [IngestObserver]s.
+pub struct ObservedIngestor<I, C> {
+    /// The wrapped ingestor.
+    ingestor: I,
+    /// The clock timing each ingest call.
+    clock: C,
+    /// The registered observers.
+    observers: Vec<Box<dyn IngestObserver>>,
+}
+
+impl<I: core::fmt::Debug, C: core::fmt::Debug> core::fmt::Debug for ObservedIngestor<I, C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ObservedIngestor")
+            .field("ingestor", &self.ingestor)
+            .field("clock", &self.clock)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
+
+impl<I: BlockIngestor, C: Clock> ObservedIngestor<I, C> {
+    /// Instantiates a new [ObservedIngestor] without observers.
+    pub fn new(ingestor: I, clock: C) -> Self {
+        Self {
+            ingestor,
+            clock,
+            observers: Vec::new(),
+        }
+    }
+
+    /// Registers an observer.
+    pub fn with_observer(mut self, observer: impl IngestObserver + 'static) -> Self {
+        self.add_observer(observer);
+        self
+    }
+
+    /// Registers an observer.
+    pub fn add_observer(&mut self, observer: impl IngestObserver + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Returns the wrapped ingestor.
+    pub fn inner(&self) -> &I {
+        &self.ingestor
+    }
+
+    /// Returns the wrapped ingestor.
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.ingestor
+    }
+
+    /// Consumes the [ObservedIngestor], returning the wrapped ingestor.
+    pub fn into_inner(self) -> I {
+        self.ingestor
+    }
+
+    /// Reports an ingested update to every observer.
+    fn notify(&mut self, update: &BlockUpdate, timing: Timing) {
+        for observer in &mut self.observers {
+            match update {
+                BlockUpdate::NewBlock(block) => {
+                    observer.on_new_block(&tracker::block_info(block), timing)
+                }
+                BlockUpdate::NewBlockData(data) => observer.on_new_block(&data.info, timing),
+                BlockUpdate::FinalityUpdate(number) => observer.on_finality_update(*number, timing),
+                BlockUpdate::SafeUpdate(number) => observer.on_safe_update(*number, timing),
+                BlockUpdate::Reorg {
+                    common_ancestor,
+                    depth,
+                } => observer.on_reorg(common_ancestor, *depth, timing),
+            }
+        }
+    }
+
+    /// Reports a fetch error to every observer.
+    fn notify_error(&mut self, error: &anyhow::Error, timing: Timing) {
+        for observer in &mut self.observers {
+            observer.on_fetch_error(error, timing);
+        }
+    }
+}
+
+impl<I: BlockIngestor, C: Clock> BlockIngestor for ObservedIngestor<I, C> {
+    fn try_ingest(&mut self) -> anyhow::Result<Option<BlockUpdate>> {
+        let started = self.clock.now();
+        let result = self.ingestor.try_ingest();
+        let timing = Timing {
+            started,
+            elapsed: self.clock.now().saturating_sub(started),
+        };
+        if let Some(error) = self.ingestor.take_recovered_error() {
+            self.notify_error(&error, timing);
+        }
+        match &result {
+            Ok(Some(update)) => self.notify(update, timing),
+            Ok(None) => {}
+            Err(error) => self.notify_error(error, timing),
+        }
+        result
+    }
+
+    fn set_l2_head(&mut self, number: u64) {
+        self.ingestor.set_l2_head(number);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::VecDeque;
+    use alloc::rc::Rc;
+    use alloc::string::{String, ToString};
+    use axos_primitives::BlockWithTransactions;
+    use axos_providers::clock::ManualClock;
+    use core::cell::RefCell;
+
+    /// An ingestor that replays a script, taking 5ms per call.
+    struct Script<'a> {
+        updates: VecDeque<anyhow::Result<Option<BlockUpdate>>>,
+        recovered: Option<anyhow::Error>,
+        clock: &'a ManualClock,
+    }
+
+    impl BlockIngestor for Script<'_> {
+        fn try_ingest(&mut self) -> anyhow::Result<Option<BlockUpdate>> {
+            self.clock.advance(Duration::from_millis(5));
+            self.updates.pop_front().unwrap_or(Ok(None))
+        }
+
+        fn take_recovered_error(&mut self) -> Option<anyhow::Error> {
+            self.recovered.take()
+        }
+    }
+
+    /// Records every event as a string.
+    #[derive(Default, Clone)]
+    struct Recorder(Rc<RefCell<Vec<String>>>);
+
+    impl IngestObserver for Recorder {
+        fn on_new_block(&mut self, block: &BlockInfo, timing: Timing) {
+            self.0.borrow_mut().push(alloc::format!(
+                "block {} in {:?}",
+                block.number,
+                timing.elapsed
+            ));
+        }
+
+        fn on_reorg(&mut self, common_ancestor: &BlockInfo, depth: u64, _: Timing) {
+            self.0.borrow_mut().push(alloc::format!(
+                "reorg to {} depth {}",
+                common_ancestor.number,
+                depth
+            ));
+        }
+
+        fn on_finality_update(&mut self, number: u64, _: Timing) {
+            self.0
+                .borrow_mut()
+                .push(alloc::format!("finalized {}", number));
+        }
+
+        fn on_fetch_error(&mut self, error: &anyhow::Error, _: Timing) {
+            self.0.borrow_mut().push(error.to_string());
+        }
+    }
+
+    #[test]
+    fn test_observed_ingestor() {
+        let clock = ManualClock::new();
+        let block = BlockWithTransactions {
+            number: Some(axos_primitives::U64::from(7)),
+            ..Default::default()
+        };
+        let script = Script {
+            updates: VecDeque::from([
+                Ok(Some(BlockUpdate::NewBlock(block))),
+                Ok(None),
+                Ok(Some(BlockUpdate::Reorg {
+                    common_ancestor: BlockInfo::new(Default::default(), 5, Default::default(), 0),
+                    depth: 2,
+                })),
+                Ok(Some(BlockUpdate::FinalityUpdate(4))),
+                Err(anyhow::anyhow!("fatal")),
+            ]),
+            recovered: Some(anyhow::anyhow!("timeout")),
+            clock: &clock,
+        };
+        let recorder = Recorder::default();
+        let mut ingestor = ObservedIngestor::new(script, &clock).with_observer(recorder.clone());
+        for _ in 0..5 {
+            let _ = ingestor.try_ingest();
+        }
+        assert_eq!(
+            *recorder.0.borrow(),
+            [
+                "timeout",
+                "block 7 in 5ms",
+                "reorg to 5 depth 2",
+                "finalized 4",
+                "fatal",
+            ]
+        );
+    }
+}
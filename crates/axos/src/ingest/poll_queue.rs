@@ -4,6 +4,36 @@
 //! that polls the provider for new blocks and ingests them into an internal
 //! queue. Seen blocks are not re-ingested.
 //!
+//! Recent blocks are tracked by hash. When a fetched block does not build
+//! on them, the queue walks back to the common ancestor, drops the queued
+//! blocks above it, and emits a [BlockUpdate::Reorg] before the
+//! replacement blocks if any ingested block was replaced.
+//!
+//! The finalized and safe blocks are polled alongside the latest block.
+//! Once the queue has ingested past them, a [BlockUpdate::FinalityUpdate]
+//! or [BlockUpdate::SafeUpdate] is emitted, and tracked blocks behind
+//! finality are pruned.
+//!
+//! With an [L1Filter][filter::L1Filter], queued blocks are stripped down to
+//! their batcher transactions, and emitted as [BlockUpdate::NewBlockData]
+//! along with their deposit and system config contract logs.
+//!
+//! With a [CursorStore][cursor::CursorStore], the queue saves a cursor
+//! whenever it hands out a block or a reorg, and whenever derivation
+//! reports a new L2 head through [BlockIngestor::set_l2_head].
+//! [PollQueue::resume] picks up from the stored cursor after a restart, if
+//! its L1 block is still canonical.
+//!
+//! With a [PollQueue::confirmation_depth] of `n`, only blocks at least `n`
+//! below the latest block are ingested. This trades latency for fewer
+//! reorgs, like the verifier confirmation depth of op-node. Without a
+//! confirmation depth, the latest block is left to the next poll.
+//!
+//! Blocks are buffered in a [BlockQueue][queue::BlockQueue], which grows
+//! without bound by default. With a bounded queue, such as a
+//! `PollQueue<RingQueue<N>>`, fetching pauses while the queue is full and
+//! resumes once blocks have been ingested.
+//!
 //! ## Example
 //!
 //! The [PollQueue] implements the [Iterator] trait, so it can be used as an
@@ -15,13 +45,12 @@
 ```rust
 use axos_providers::provider::Provider;
 use axos::ingest::poll_queue::PollQueue;
-use axos_providers::mock::MockProvider;
-use axos_primitives::U64;
+use axos_providers::mock::MockChain;
 
-let provider = MockProvider::new("http://localhost:8080".to_string());
-let mut poll_queue = PollQueue::from(Box::new(provider) as Box<dyn Provider>);
+let chain = MockChain::builder().blocks(3).build();
+let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>);
 let block = poll_queue.next().unwrap();
-assert_eq!(block.number, Some(U64::from(2)));
+assert_eq!(Some(block), chain.block(0));
 ```
 "#
 )]
@@ -29,20 +58,69 @@ assert_eq!(block.number, Some(U64::from(2)));
 #[cfg(feature = "alloc")]
 use alloc::boxed::Box;
 
-use axos_primitives::{BlockId, BlockKind, BlockWithTransactions};
-use axos_providers::provider::Provider;
+use axos_primitives::{BlockId, BlockKind, BlockWithTransactions, B256};
+use axos_providers::provider::{Error, Provider};
 
 use crate::ingest::*;
 
+/// A block number the provider reports, such as the finalized block,
+/// and the last value of it that was emitted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Checkpoint {
+    /// The number last reported by the provider.
+    reported: Option<u64>,
+    /// The number last emitted.
+    emitted: Option<u64>,
+}
+
+impl Checkpoint {
+    /// Records a newly reported number. Returns true if it advanced.
+    fn report(&mut self, number: u64) -> bool {
+        let advanced = self.reported.is_none_or(|n| number > n);
+        if advanced {
+            self.reported = Some(number);
+        }
+        advanced
+    }
+
+    /// Takes the reported number if it advanced past the last emitted
+    /// number and has been ingested.
+    fn take(&mut self, ingested: Option<u64>) -> Option<u64> {
+        let reported = self.reported?;
+        if self.emitted.is_some_and(|n| n >= reported) || ingested.is_none_or(|n| n < reported) {
+            return None;
+        }
+        self.emitted = Some(reported);
+        Some(reported)
+    }
+}
+
 /// Poll Queue
 #[derive(Debug, Default)]
-pub struct PollQueue {
+pub struct PollQueue<Q = queue::DefaultQueue> {
     /// The block queue.
-    queue: queue::InnerQueue,
+    queue: Q,
+    /// The most blocks the queue has held at once.
+    high_water: usize,
 
     /// An internal reference to the dynamic [axos_providers::provider::Provider] trait.
     provider: provider::InnerProvider,
 
+    /// Recent canonical blocks, fetched or ingested.
+    tracker: tracker::ChainTracker,
+    /// The number of the last block taken from the queue.
+    ingested: Option<u64>,
+    /// The cursor last saved.
+    position: Option<cursor::Cursor>,
+    /// A reorg to emit before the next block.
+    reorg: Option<BlockUpdate>,
+    /// The last retryable error, until it is taken.
+    recovered: Option<Error>,
+    /// The finalized block number, as reported and as emitted.
+    finalized: Checkpoint,
+    /// The safe block number, as reported and as emitted.
+    safe: Checkpoint,
+
     /// The L1 starting block
     pub l1_start_block: u64,
     /// The L2 starting block
@@ -51,6 +129,15 @@ pub struct PollQueue {
     pub l1_latest_block: u64,
     /// The L2 latest block
     pub l2_latest_block: u64,
+    /// The number of blocks a block must be below the latest block
+    /// before it is ingested.
+    pub confirmation_depth: u64,
+    /// Strips blocks down to the data derivation reads.
+    #[cfg(feature = "alloc")]
+    pub filter: Option<filter::L1Filter>,
+    /// Persists the ingestion cursor.
+    #[cfg(feature = "alloc")]
+    cursor: Option<Box<dyn cursor::CursorStore>>,
 }
 
 #[cfg(feature = "alloc")]
@@ -74,94 +161,425 @@ impl From<&'static mut dyn Provider> for PollQueue {
 }
 
 impl PollQueue {
-    /// Instantiates a new [PollQueue].
-    #[cfg(feature = "alloc")]
+    /// Instantiates a new [PollQueue] with the [DefaultQueue][queue::DefaultQueue].
     pub fn new() -> Self {
         Self {
             ..Default::default()
         }
     }
+}
 
-    /// Instantiates a new [PollQueue].
-    #[cfg(not(feature = "alloc"))]
-    pub fn new(queue: &'static mut VecDeque<BlockWithTransactions>) -> Self {
+impl<Q: queue::BlockQueue + Default> PollQueue<Q> {
+    /// Instantiates a [PollQueue] with an empty queue of type `Q`, such as
+    /// a fixed-capacity [RingQueue][queue::RingQueue].
+    pub fn with_provider(provider: impl Into<provider::InnerProvider>) -> Self {
         Self {
-            queue,
+            provider: provider.into(),
             ..Default::default()
         }
     }
+}
+
+impl<Q: queue::BlockQueue> PollQueue<Q> {
+    /// Sets the number of blocks a block must be below the latest block
+    /// before it is ingested.
+    pub fn with_confirmation_depth(mut self, confirmation_depth: u64) -> Self {
+        self.confirmation_depth = confirmation_depth;
+        self
+    }
+
+    /// Strips blocks down to the data derivation reads, emitting
+    /// [BlockUpdate::NewBlockData] instead of [BlockUpdate::NewBlock].
+    #[cfg(feature = "alloc")]
+    pub fn with_filter(mut self, filter: filter::L1Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Saves the ingestion cursor to the given store.
+    #[cfg(feature = "alloc")]
+    pub fn with_cursor_store(mut self, store: impl cursor::CursorStore + 'static) -> Self {
+        self.cursor = Some(Box::new(store));
+        self
+    }
+
+    /// Returns the cursor store, if any.
+    #[cfg(feature = "alloc")]
+    pub fn cursor_store(&self) -> Option<&dyn cursor::CursorStore> {
+        self.cursor.as_deref()
+    }
+
+    /// Resumes from the stored cursor, ingesting after its L1 block and
+    /// starting L2 at its L2 block. The cursor is ignored if its L1
+    /// block is no longer canonical or is before
+    /// [PollQueue::l1_start_block]. Returns the cursor resumed from.
+    #[cfg(feature = "alloc")]
+    pub fn resume(&mut self) -> Result<Option<cursor::Cursor>, Error> {
+        let Some(store) = &self.cursor else {
+            return Ok(None);
+        };
+        let stored = match store.load() {
+            Ok(Some(stored)) => stored,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                tracing::warn!("[poll] failed to load the cursor, starting over: {}", e);
+                return Ok(None);
+            }
+        };
+        if stored.l1_number < self.l1_start_block {
+            tracing::info!(
+                "[poll] cursor at L1 block {} is before the start block {}",
+                stored.l1_number,
+                self.l1_start_block
+            );
+            return Ok(None);
+        }
+        let block = match self
+            .provider
+            .get_block_with_txs(BlockId::Number(stored.l1_number))
+        {
+            Ok(Some(block)) if block.hash == Some(stored.l1_hash) => block,
+            Ok(_) | Err(Error::BlockNotFound) => {
+                tracing::warn!(
+                    "[poll] cursor at L1 block {} is no longer canonical, starting over",
+                    stored.l1_number
+                );
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        tracing::info!("[poll] resuming from L1 block {}", stored.l1_number);
+        self.tracker.clear();
+        self.tracker.push(tracker::block_info(&block));
+        self.queue.retain(|_| false);
+        self.ingested = Some(stored.l1_number);
+        self.position = Some(stored);
+        self.l2_start_block = stored.l2_number;
+        self.l2_latest_block = stored.l2_number;
+        Ok(Some(stored))
+    }
+
+    /// Saves the cursor after the given L1 block was handed out.
+    fn save_cursor(&mut self, number: u64, hash: B256) {
+        self.ingested = Some(number);
+        self.store_cursor(cursor::Cursor {
+            l1_number: number,
+            l1_hash: hash,
+            l2_number: self.l2_latest_block,
+        });
+    }
+
+    /// Saves the cursor to the store. Store errors are logged, since
+    /// ingestion can go on without a cursor.
+    fn store_cursor(&mut self, cursor: cursor::Cursor) {
+        self.position = Some(cursor);
+        #[cfg(feature = "alloc")]
+        if let Some(store) = &mut self.cursor {
+            if let Err(e) = store.store(cursor) {
+                tracing::warn!("[poll] failed to save the cursor: {}", e);
+            }
+        }
+    }
 
     /// Load a specific block into the queue by block number.
     /// The block number must not already be seen by the ingestor
     /// or be in the queue.
-    pub fn load_block(&mut self, block_number: u64) {
+    pub fn load_block(&mut self, block_number: u64) -> Result<(), Error> {
         // Check if the block is already seen
         if block_number < self.l1_start_block {
-            return;
+            return Ok(());
         }
 
-        // Check if the block is already in the queue
-        if self.queue.iter().any(|b| {
-            b.number
-                .map(|n| n.to::<u64>() == block_number)
-                .unwrap_or(false)
-        }) {
-            return;
+        // Check if the block is already in the queue, or there is no room
+        if self.queue.contains(block_number) || self.queue.is_full() {
+            return Ok(());
         }
 
         match self
             .provider
             .get_block_with_txs(BlockId::Number(block_number))
         {
-            Ok(Some(block)) => self.queue.push_back(block),
-            Ok(None) => {
-                tracing::warn!(
-                    "[load_block] block {} not found. Falling back to the genesis block.",
-                    block_number
-                );
+            Ok(Some(block)) => self.push_block(block)?,
+            Ok(None) | Err(Error::BlockNotFound) => {
+                tracing::warn!("[load_block] block {} not found.", block_number);
             }
             Err(e) => {
-                tracing::error!("[load_block] error fetching block: {:?}", e);
+                tracing::error!("[load_block] error fetching block {}: {}", block_number, e);
+                return Err(e);
             }
         };
+        Ok(())
+    }
+
+    /// Queues a fetched block, unless it is already tracked.
+    ///
+    /// A block that conflicts with the tracked blocks is not queued.
+    /// Instead, the queue is rewound to the common ancestor, so the
+    /// replacement blocks are fetched next.
+    #[cfg_attr(not(feature = "alloc"), allow(unused_mut))]
+    fn push_block(&mut self, mut block: BlockWithTransactions) -> Result<(), Error> {
+        let info = tracker::block_info(&block);
+        if self.tracker.contains(&info) {
+            return Ok(());
+        }
+        if !self.tracker.conflicts(&info) {
+            #[cfg(feature = "alloc")]
+            if let Some(filter) = &self.filter {
+                filter.strip(&mut block);
+            }
+            if self.queue.push_back(block).is_err() {
+                tracing::debug!("[poll] queue is full, block {} not queued", info.number);
+                return Ok(());
+            }
+            self.tracker.push(info);
+            self.high_water = self.high_water.max(self.queue.len());
+            return Ok(());
+        }
+
+        let ancestor = self.tracker.find_common_ancestor(&info, &self.provider)?;
+        if self
+            .tracker
+            .head()
+            .is_some_and(|head| head.hash == ancestor.hash)
+        {
+            tracing::debug!("[poll] block {} is not canonical, skipping", info.number);
+            return Ok(());
+        }
+        tracing::warn!(
+            "[poll] reorg detected at block {}, common ancestor {}",
+            info.number,
+            ancestor.number
+        );
+        self.tracker.rewind(ancestor);
+        self.queue.retain(|b| {
+            b.number
+                .map(|n| n.to::<u64>() <= ancestor.number)
+                .unwrap_or(false)
+        });
+        if let Some(ingested) = self.ingested.filter(|n| *n > ancestor.number) {
+            // A reorg that was not taken yet already rewound `ingested`,
+            // so its depth carries over to the deeper reorg.
+            let pending = match self.reorg {
+                Some(BlockUpdate::Reorg { depth, .. }) => depth,
+                _ => 0,
+            };
+            self.reorg = Some(BlockUpdate::Reorg {
+                common_ancestor: ancestor,
+                depth: pending + ingested - ancestor.number,
+            });
+            self.ingested = Some(ancestor.number);
+        }
+        // Safe blocks can be reorged, and must be emitted again once replaced.
+        for checkpoint in [&mut self.safe.reported, &mut self.safe.emitted] {
+            if checkpoint.is_some_and(|n| n > ancestor.number) {
+                *checkpoint = Some(ancestor.number);
+            }
+        }
+        Ok(())
     }
 
     /// Loads blocks from the provider into the queue.
-    pub fn load_blocks(&mut self) {
+    /// Stops at the first provider error and returns it.
+    pub fn load_blocks(&mut self) -> Result<(), Error> {
         // Get the latest known block
         let latest_block = match self
             .provider
             .get_block_with_txs(BlockId::Kind(BlockKind::Latest))
         {
             Ok(Some(block)) => block,
-            Ok(None) => {
+            Ok(None) | Err(Error::BlockNotFound) => {
                 tracing::info!("[poll] no new blocks");
-                return;
+                return Ok(());
             }
             Err(e) => {
-                tracing::error!("[poll] error fetching latest block: {:?}", e);
-                return;
+                tracing::error!("[poll] error fetching latest block: {}", e);
+                return Err(e);
             }
         };
+        let latest = tracker::block_info(&latest_block);
 
-        let blocks = self.l2_latest_block
-            ..(latest_block
-                .number
-                .map(|n| n.to::<u64>())
-                .unwrap_or_default());
-        blocks.for_each(|block_number| {
-            self.load_block(block_number);
-        });
+        // The tip may have been replaced without the chain growing.
+        if self.tracker.conflicts(&latest) {
+            self.push_block(latest_block)?;
+        }
+
+        // Like op-node, a block is confirmed once `number + depth <= latest`.
+        let confirmed = match self.confirmation_depth {
+            0 => latest.number.checked_sub(1),
+            depth => latest.number.checked_sub(depth),
+        };
+        let Some(confirmed) = confirmed else {
+            return self.poll_finality();
+        };
+        let mut head = self.tracker.head();
+        while self.next_block_number() <= confirmed {
+            if self.queue.is_full() {
+                tracing::debug!(
+                    "[poll] queue is full with {} blocks, pausing fetches",
+                    self.queue.len()
+                );
+                break;
+            }
+            self.load_block(self.next_block_number())?;
+            // Stop if the block was not found or was skipped.
+            if self.tracker.head() == head {
+                break;
+            }
+            head = self.tracker.head();
+        }
+        self.poll_finality()
+    }
+
+    /// Polls the finalized and safe blocks, pruning tracked blocks behind
+    /// finality. Providers that do not know either block are ignored.
+    fn poll_finality(&mut self) -> Result<(), Error> {
+        if let Some(finalized) = self.poll_number(BlockKind::Finalized)? {
+            if self.finalized.report(finalized) {
+                tracing::debug!("[poll] finalized block advanced to {}", finalized);
+                self.tracker.prune(finalized);
+            }
+        }
+        if let Some(safe) = self.poll_number(BlockKind::Safe)? {
+            self.safe.report(safe);
+        }
+        Ok(())
+    }
+
+    /// Fetches the number of the block of the given kind.
+    fn poll_number(&self, kind: BlockKind) -> Result<Option<u64>, Error> {
+        match self.provider.get_block_with_txs(BlockId::Kind(kind)) {
+            Ok(block) => Ok(block.and_then(|b| b.number).map(|n| n.to::<u64>())),
+            Err(e) if e.is_retryable() => Err(e),
+            Err(e) => {
+                tracing::debug!("[poll] failed to fetch the {} block: {}", kind.as_tag(), e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the last finalized block number emitted.
+    pub fn finalized(&self) -> Option<u64> {
+        self.finalized.emitted
+    }
+
+    /// Returns the last safe block number emitted.
+    pub fn safe(&self) -> Option<u64> {
+        self.safe.emitted
+    }
+
+    /// Returns the number of blocks waiting in the queue.
+    pub fn queue_depth(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns the most blocks the queue has held at once.
+    pub fn queue_high_water(&self) -> usize {
+        self.high_water
+    }
+
+    /// Returns the number of the next block to fetch.
+    fn next_block_number(&self) -> u64 {
+        self.tracker
+            .head()
+            .map(|head| head.number + 1)
+            .unwrap_or(self.l1_start_block)
+    }
+
+    /// Retrieves the next update from the queue.
+    ///
+    /// A pending reorg is returned first, then finality and safe updates,
+    /// then the next block. Retryable provider
+    /// errors are logged and leave the queue as is, so the next call picks
+    /// up where this one stopped. Fatal errors are returned.
+    pub fn try_next_update(&mut self) -> Result<Option<BlockUpdate>, Error> {
+        match self.load_blocks() {
+            Err(e) if e.is_retryable() => {
+                tracing::warn!("[poll] retryable provider error, will retry: {}", e);
+                self.recovered = Some(e);
+            }
+            Err(e) => return Err(e),
+            Ok(()) => {}
+        }
+        if let Some(reorg) = self.reorg.take() {
+            if let BlockUpdate::Reorg {
+                common_ancestor, ..
+            } = reorg
+            {
+                self.save_cursor(common_ancestor.number, common_ancestor.hash);
+            }
+            return Ok(Some(reorg));
+        }
+        if let Some(finalized) = self.finalized.take(self.ingested) {
+            return Ok(Some(BlockUpdate::FinalityUpdate(finalized)));
+        }
+        if let Some(safe) = self.safe.take(self.ingested) {
+            return Ok(Some(BlockUpdate::SafeUpdate(safe)));
+        }
+        #[cfg(feature = "alloc")]
+        if let Some(filter) = self.filter {
+            return self.next_block_data(&filter);
+        }
+        let block = self.queue.pop_front();
+        if let Some(block) = &block {
+            let info = tracker::block_info(block);
+            self.save_cursor(info.number, info.hash);
+        }
+        Ok(block.map(BlockUpdate::NewBlock))
+    }
+
+    /// Takes the next block from the queue along with its logs. The block
+    /// stays queued if its logs cannot be fetched.
+    #[cfg(feature = "alloc")]
+    fn next_block_data(&mut self, filter: &filter::L1Filter) -> Result<Option<BlockUpdate>, Error> {
+        let Some(hash) = self.queue.front().map(|b| b.hash.unwrap_or_default()) else {
+            return Ok(None);
+        };
+        let logs = match self.provider.get_logs(&filter.log_filter(hash)) {
+            Ok(logs) => logs,
+            Err(e) if e.is_retryable() => {
+                tracing::warn!("[poll] retryable error fetching logs, will retry: {}", e);
+                self.recovered = Some(e);
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        let Some(block) = self.queue.pop_front() else {
+            return Ok(None);
+        };
+        let info = tracker::block_info(&block);
+        self.save_cursor(info.number, info.hash);
+        Ok(Some(BlockUpdate::NewBlockData(
+            filter.block_data(block, logs),
+        )))
     }
 
     /// Retrieves the next block from the queue.
+    ///
+    /// Reorgs are skipped, so callers that need to reset on a reorg
+    /// should use [PollQueue::try_next_update]. With a filter, blocks are
+    /// only emitted by [PollQueue::try_next_update]. Retryable provider errors
+    /// are logged and leave the queue as is. Fatal errors are returned.
+    pub fn try_next_block(&mut self) -> Result<Option<BlockWithTransactions>, Error> {
+        loop {
+            match self.try_next_update()? {
+                Some(BlockUpdate::NewBlock(block)) => return Ok(Some(block)),
+                Some(update) => tracing::debug!("[poll] skipping {:?}", update),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Retrieves the next block from the queue.
+    /// Provider errors are logged and yield [None].
     pub fn next_block(&mut self) -> Option<BlockWithTransactions> {
-        self.load_blocks();
-        self.queue.pop_front()
+        self.try_next_block().unwrap_or_else(|e| {
+            tracing::error!("[poll] fatal provider error: {}", e);
+            None
+        })
     }
 }
 
-impl Iterator for PollQueue {
+impl<Q: queue::BlockQueue> Iterator for PollQueue<Q> {
     type Item = BlockWithTransactions;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -169,9 +587,23 @@ impl Iterator for PollQueue {
     }
 }
 
-impl BlockIngestor for PollQueue {
+impl<Q: queue::BlockQueue> BlockIngestor for PollQueue<Q> {
     fn try_ingest(&mut self) -> anyhow::Result<Option<BlockUpdate>> {
-        Ok(self.next_block().map(BlockUpdate::NewBlock))
+        self.try_next_update().map_err(anyhow::Error::msg)
+    }
+
+    fn take_recovered_error(&mut self) -> Option<anyhow::Error> {
+        self.recovered.take().map(anyhow::Error::msg)
+    }
+
+    fn set_l2_head(&mut self, number: u64) {
+        self.l2_latest_block = number;
+        if let Some(position) = self.position {
+            self.store_cursor(cursor::Cursor {
+                l2_number: number,
+                ..position
+            });
+        }
     }
 }
 
@@ -179,55 +611,317 @@ impl BlockIngestor for PollQueue {
 mod tests {
     use super::*;
     #[cfg(feature = "alloc")]
-    use alloc::string::ToString;
     use axos_primitives::BlockWithTransactions;
-    use axos_providers::mock::MockProvider;
 
-    fn build_mock_provider() -> MockProvider {
-        #[cfg(feature = "alloc")]
-        let url = "http://localhost:8080".to_string();
-        #[cfg(not(feature = "alloc"))]
-        let url = "http://localhost:8080";
-        MockProvider::new(url)
+    #[test]
+    #[cfg(not(feature = "alloc"))]
+    fn test_default_ring_queue() {
+        use queue::BlockQueue;
+
+        let mut poll_queue = PollQueue::new();
+        assert_eq!(
+            poll_queue.queue.capacity(),
+            Some(queue::DEFAULT_RING_CAPACITY)
+        );
+        assert_eq!(poll_queue.try_next_update(), Ok(None));
     }
 
     #[test]
     #[cfg(feature = "alloc")]
-    fn test_ingest_mock_provider() {
-        use axos_primitives::{B256, U256, U64};
+    fn test_ingest_mock_chain() {
+        use axos_providers::mock::MockChain;
 
-        let provider = build_mock_provider();
-        let mut poll_queue = PollQueue::from(Box::new(provider) as Box<dyn Provider>);
+        let chain = MockChain::builder().blocks(3).build();
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>);
         let block = poll_queue.try_ingest().unwrap();
-        let mut expected_hash = [0; 32];
-        expected_hash[31] = 2;
-        let mut expected_parent_hash = [0; 32];
-        expected_parent_hash[31] = 1;
-        let expected_block = BlockWithTransactions {
-            number: Some(U64::from(2)),
-            hash: Some(B256::from(expected_hash)),
-            parent_hash: B256::from(expected_parent_hash),
-            timestamp: U256::from(2),
-            transactions: Default::default(),
-            ..Default::default()
-        };
-        assert_eq!(block, Some(BlockUpdate::NewBlock(expected_block)));
+        assert_eq!(block, Some(BlockUpdate::NewBlock(chain.block(0).unwrap())));
+    }
+
+    /// A provider that always fails with the given error.
+    #[cfg(feature = "alloc")]
+    struct FailingProvider(Error);
+
+    #[cfg(feature = "alloc")]
+    impl Provider for FailingProvider {
+        fn get_block_with_txs(&self, _: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+            Err(self.0.clone())
+        }
     }
 
     #[test]
-    #[cfg(not(feature = "alloc"))]
-    fn test_ingest_mock_provider() {
-        let provider = build_mock_provider();
-        let mut poll_queue = PollQueue::from(&mut provider as &mut dyn Provider);
-        let block = poll_queue.try_ingest().unwrap();
-        let expected_block = BlockWithTransactions {
-            number: Some(U64::from(2)),
-            hash: [0; 32],
-            parent_hash: [0; 32],
-            timestamp: U256::from(2),
-            transactions: Default::default(),
-            ..Default::default()
+    #[cfg(feature = "alloc")]
+    fn test_ingest_retryable_error() {
+        let provider = FailingProvider(Error::Timeout);
+        let mut poll_queue = PollQueue::from(Box::new(provider) as Box<dyn Provider>);
+        assert_eq!(poll_queue.try_ingest().unwrap(), None);
+        assert!(poll_queue.take_recovered_error().is_some());
+        assert!(poll_queue.take_recovered_error().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ingest_fatal_error() {
+        let provider = FailingProvider(Error::Decode("invalid block".into()));
+        let mut poll_queue = PollQueue::from(Box::new(provider) as Box<dyn Provider>);
+        assert!(poll_queue.try_ingest().is_err());
+        assert_eq!(poll_queue.next(), None);
+    }
+
+    /// Ingests updates until the queue runs dry.
+    #[cfg(feature = "alloc")]
+    fn drain<Q: queue::BlockQueue>(poll_queue: &mut PollQueue<Q>) -> alloc::vec::Vec<BlockUpdate> {
+        core::iter::from_fn(|| poll_queue.try_ingest().unwrap()).collect()
+    }
+
+    /// Ingests updates until the queue runs dry, skipping finality and
+    /// safe updates.
+    #[cfg(feature = "alloc")]
+    fn drain_blocks<Q: queue::BlockQueue>(
+        poll_queue: &mut PollQueue<Q>,
+    ) -> alloc::vec::Vec<BlockUpdate> {
+        drain(poll_queue)
+            .into_iter()
+            .filter(|u| {
+                !matches!(
+                    u,
+                    BlockUpdate::FinalityUpdate(_) | BlockUpdate::SafeUpdate(_)
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ingest_reorg() {
+        use axos_primitives::BlockInfo;
+        use axos_providers::mock::MockChain;
+
+        let chain = MockChain::builder().blocks(5).build();
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>);
+        assert_eq!(drain_blocks(&mut poll_queue).len(), 5);
+
+        chain.reorg(2);
+        chain.advance();
+        let updates = drain_blocks(&mut poll_queue);
+        let expected = [
+            BlockUpdate::Reorg {
+                common_ancestor: BlockInfo::try_from(chain.block(3).unwrap()).unwrap(),
+                depth: 1,
+            },
+            BlockUpdate::NewBlock(chain.block(4).unwrap()),
+            BlockUpdate::NewBlock(chain.block(5).unwrap()),
+        ];
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_reorgs_before_taken() {
+        use axos_primitives::BlockInfo;
+        use axos_providers::mock::MockChain;
+
+        let chain = MockChain::builder().blocks(5).build();
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>);
+        assert_eq!(drain_blocks(&mut poll_queue).len(), 5);
+
+        chain.reorg(2);
+        chain.advance();
+        poll_queue.load_blocks().unwrap();
+        chain.reorg(4);
+        let updates = drain_blocks(&mut poll_queue);
+        let expected = [
+            BlockUpdate::Reorg {
+                common_ancestor: BlockInfo::try_from(chain.block(2).unwrap()).unwrap(),
+                depth: 2,
+            },
+            BlockUpdate::NewBlock(chain.block(3).unwrap()),
+            BlockUpdate::NewBlock(chain.block(4).unwrap()),
+            BlockUpdate::NewBlock(chain.block(5).unwrap()),
+        ];
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_reorg_too_deep() {
+        use axos_providers::mock::MockChain;
+
+        // Blocks behind finality are no longer tracked, so a reorg past
+        // the finalized block can't be traced back.
+        let chain = MockChain::builder().blocks(10).build();
+        chain.set_finalized(6);
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>);
+        assert_eq!(drain_blocks(&mut poll_queue).len(), 10);
+
+        chain.set_finalized(0);
+        chain.reorg(5);
+        let err = poll_queue.try_next_update().unwrap_err();
+        assert!(err.downcast_ref::<tracker::ReorgTooDeepError>().is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_reorg_of_queued_blocks() {
+        use axos_providers::mock::MockChain;
+
+        let chain = MockChain::builder().blocks(5).build();
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>);
+        assert!(poll_queue.try_ingest().unwrap().is_some());
+
+        chain.reorg(3);
+        chain.advance();
+        let updates = drain_blocks(&mut poll_queue);
+        let expected = (1..=5)
+            .map(|n| BlockUpdate::NewBlock(chain.block(n).unwrap()))
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ingest_finality() {
+        use axos_providers::mock::MockChain;
+
+        let chain = MockChain::builder().blocks(10).build();
+        chain.set_finalized(4);
+        chain.set_safe(6);
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>);
+        let updates = drain(&mut poll_queue);
+        let position = |update: BlockUpdate| updates.iter().position(|u| *u == update).unwrap();
+        let block = |n: u64| BlockUpdate::NewBlock(chain.block(n).unwrap());
+        assert_eq!(updates.len(), 12);
+        assert_eq!(
+            position(BlockUpdate::FinalityUpdate(4)),
+            position(block(4)) + 1
+        );
+        assert_eq!(position(BlockUpdate::SafeUpdate(6)), position(block(6)) + 1);
+        assert_eq!(poll_queue.finalized(), Some(4));
+
+        chain.set_finalized(8);
+        assert_eq!(
+            drain(&mut poll_queue),
+            [BlockUpdate::FinalityUpdate(8), BlockUpdate::SafeUpdate(8)]
+        );
+        chain.set_finalized(7);
+        assert_eq!(drain(&mut poll_queue), []);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_confirmation_depth() {
+        use axos_providers::mock::MockChain;
+
+        let chain = MockChain::builder().blocks(10).build();
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>)
+            .with_confirmation_depth(3);
+        // Block 7 is three below the head, so it is confirmed.
+        let updates = drain_blocks(&mut poll_queue);
+        assert_eq!(
+            updates.last(),
+            Some(&BlockUpdate::NewBlock(chain.block(7).unwrap()))
+        );
+
+        // A reorg above the confirmed blocks goes unnoticed.
+        chain.reorg(2);
+        chain.advance();
+        assert_eq!(
+            drain_blocks(&mut poll_queue),
+            [BlockUpdate::NewBlock(chain.block(8).unwrap())]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_filtered_blocks() {
+        use axos_primitives::{Address, ChainConfig};
+        use axos_providers::mock::{txs::batcher_tx, MockChain};
+
+        let config = ChainConfig::optimism();
+        let batcher = config.system_config.batch_sender;
+        let batch = batcher_tx(batcher, config.batch_inbox, [1u8]);
+        let chain = MockChain::builder()
+            .blocks(3)
+            .transaction(2, batch.clone())
+            .transaction(2, batcher_tx(Address::ZERO, config.batch_inbox, [2u8]))
+            .build();
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>)
+            .with_filter(filter::L1Filter::from(&config));
+        let data = drain_blocks(&mut poll_queue)
+            .into_iter()
+            .map(|update| match update {
+                BlockUpdate::NewBlockData(data) => data,
+                update => panic!("unexpected update {:?}", update),
+            })
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[2].info, tracker::block_info(&chain.block(2).unwrap()));
+        assert_eq!(data[2].batcher_transactions.len(), 1);
+        assert_eq!(data[2].batcher_transactions[0].input, batch.input);
+        assert!(data[1].batcher_transactions.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_resume_from_cursor() {
+        use axos_providers::mock::MockChain;
+        use cursor::{Cursor, MemoryCursorStore};
+
+        let chain = MockChain::builder().blocks(10).build();
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>)
+            .with_cursor_store(MemoryCursorStore::default());
+        assert_eq!(poll_queue.resume(), Ok(None));
+        drain(&mut poll_queue);
+        poll_queue.set_l2_head(7);
+        let cursor = poll_queue.cursor_store().unwrap().load().unwrap().unwrap();
+        assert_eq!(cursor.l1_number, 9);
+        assert_eq!(Some(cursor.l1_hash), chain.hash(9));
+        assert_eq!(cursor.l2_number, 7);
+
+        chain.advance_to(12);
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>)
+            .with_cursor_store(MemoryCursorStore(Some(cursor)));
+        assert_eq!(poll_queue.resume(), Ok(Some(cursor)));
+        assert_eq!(poll_queue.l2_start_block, 7);
+        assert_eq!(
+            drain_blocks(&mut poll_queue),
+            [
+                BlockUpdate::NewBlock(chain.block(10).unwrap()),
+                BlockUpdate::NewBlock(chain.block(11).unwrap()),
+            ]
+        );
+
+        // A cursor on a replaced block is ignored.
+        let stale = Cursor {
+            l1_number: 11,
+            l1_hash: chain.hash(11).unwrap(),
+            ..cursor
         };
-        assert_eq!(block, Some(BlockUpdate::NewBlock(expected_block)));
+        chain.reorg(3);
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>)
+            .with_cursor_store(MemoryCursorStore(Some(stale)));
+        assert_eq!(poll_queue.resume(), Ok(None));
+        assert_eq!(drain_blocks(&mut poll_queue).len(), 12);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_bounded_queue() {
+        use axos_providers::mock::MockChain;
+
+        let chain = MockChain::builder().blocks(10).build();
+        let mut poll_queue = PollQueue::<queue::RingQueue<4>>::with_provider(
+            Box::new(chain.clone()) as Box<dyn Provider>,
+        );
+        poll_queue.load_blocks().unwrap();
+        assert_eq!(poll_queue.queue_depth(), 4);
+
+        // Fetching resumes as blocks are ingested.
+        let updates = drain_blocks(&mut poll_queue);
+        let expected = (0..10)
+            .map(|n| BlockUpdate::NewBlock(chain.block(n).unwrap()))
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(updates, expected);
+        assert_eq!(poll_queue.queue_depth(), 0);
+        assert_eq!(poll_queue.queue_high_water(), 4);
     }
 }
@@ -0,0 +1,506 @@
+//! Prefetching Provider
+//!
+//! The [PollQueue][crate::ingest::poll_queue::PollQueue] fetches blocks one
+//! at a time, so catching up costs one round-trip per block. The
+//! [Prefetcher] is a [Provider] that fetches a window of upcoming blocks on
+//! worker threads and serves them in order, so a poll queue built on it
+//! syncs with as many requests in flight as there are workers.
+//!
+//! Blocks are only prefetched while lookups by number walk forward, and
+//! at most `window` blocks past the last lookup are fetched or buffered.
+//! Any other lookup by number, such as the walk back to a reorg's common
+//! ancestor, drops the prefetched blocks and is fetched directly.
+//!
+//! Each lookup of the latest block is checked against the previous one.
+//! Unless the new head builds directly on the old one, prefetched blocks
+//! may have been replaced, so they are dropped along with any fetches in
+//! flight. The blocks are fetched again from the new chain, and the reorg
+//! is seen before any replaced block is served.
+//!
+//! A block a worker has not returned within the
+//! [result timeout][Prefetcher::with_result_timeout], for instance because
+//! the worker panicked, is fetched directly instead.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use axos::ingest::poll_queue::PollQueue;
+//! use axos::ingest::prefetch::Prefetcher;
+//! use axos_providers::http::HttpProvider;
+//! use axos_providers::provider::Provider;
+//!
+//! let prefetcher = Prefetcher::new(
+//!     || Box::new(HttpProvider::new("http://localhost:8545")),
+//!     4,
+//!     16,
+//! );
+//! let poll_queue = PollQueue::from(Box::new(prefetcher) as Box<dyn Provider>);
+//! ```
+
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::vec::Vec;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    AccountProof, Address, BlockId, BlockInfo, BlockKind, BlockWithTransactions, Filter, Log,
+    TransactionReceipt, B256,
+};
+use axos_providers::provider::{Error, Provider};
+
+use crate::ingest::tracker;
+
+/// The default time to wait for a worker to return a requested block.
+pub const DEFAULT_RESULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The result of fetching a block.
+type Fetched = Result<Option<BlockWithTransactions>, Error>;
+
+/// A block to fetch, tagged with the generation it was requested in.
+type Job = (u64, u64);
+
+/// Prefetched blocks and the requests that produced them.
+#[derive(Debug, Default)]
+struct State {
+    /// The number the next in-order lookup is expected at.
+    next: Option<u64>,
+    /// The next number to hand to the workers.
+    requested: u64,
+    /// The latest block the provider reported.
+    latest: Option<BlockInfo>,
+    /// Fetched blocks that have not been served yet.
+    fetched: BTreeMap<u64, Fetched>,
+}
+
+/// Prefetcher
+///
+/// A [Provider] that serves lookups by number from blocks prefetched on
+/// worker threads. Every other lookup goes straight to the provider.
+pub struct Prefetcher {
+    /// The provider used for lookups that are not prefetched.
+    provider: Box<dyn Provider>,
+    /// Hands blocks to the workers.
+    jobs: Sender<Job>,
+    /// Fetched blocks, tagged with their generation and number.
+    results: Receiver<(u64, u64, Fetched)>,
+    /// Bumped whenever prefetched blocks are dropped, so the workers skip
+    /// requests that are no longer wanted.
+    generation: Arc<AtomicU64>,
+    /// The number of blocks to fetch ahead of the last lookup.
+    window: u64,
+    /// How long to wait for a worker to return a requested block.
+    result_timeout: Duration,
+    /// The prefetch state.
+    state: RefCell<State>,
+}
+
+impl core::fmt::Debug for Prefetcher {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Prefetcher")
+            .field("generation", &self.generation)
+            .field("window", &self.window)
+            .field("result_timeout", &self.result_timeout)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Prefetcher {
+    /// Instantiates a new [Prefetcher] that fetches up to `window` blocks
+    /// ahead on `workers` threads.
+    ///
+    /// Providers are not shared between threads, so `provider` is called
+    /// once for every worker and once for the lookups made directly.
+    pub fn new(
+        provider: impl Fn() -> Box<dyn Provider + Send>,
+        workers: usize,
+        window: u64,
+    ) -> Self {
+        let (jobs, queued) = mpsc::channel();
+        let (sender, results) = mpsc::channel();
+        let queued = Arc::new(Mutex::new(queued));
+        let generation = Arc::new(AtomicU64::new(0));
+        for _ in 0..workers.max(1) {
+            spawn_worker(
+                provider(),
+                Arc::clone(&queued),
+                sender.clone(),
+                Arc::clone(&generation),
+            );
+        }
+        Self {
+            provider: provider(),
+            jobs,
+            results,
+            generation,
+            window,
+            result_timeout: DEFAULT_RESULT_TIMEOUT,
+            state: RefCell::new(State::default()),
+        }
+    }
+
+    /// Sets how long to wait for a worker to return a requested block
+    /// before fetching it directly.
+    pub fn with_result_timeout(mut self, result_timeout: Duration) -> Self {
+        self.result_timeout = result_timeout;
+        self
+    }
+
+    /// Returns the number of blocks fetched ahead of the last lookup.
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    /// Returns the number of fetched blocks waiting to be served.
+    pub fn buffered(&self) -> usize {
+        self.state.borrow().fetched.len()
+    }
+
+    /// Serves a block by number, from the prefetched blocks if the lookup
+    /// follows the previous one.
+    fn get_block_by_number(&self, number: u64) -> Fetched {
+        let mut state = self.state.borrow_mut();
+        let in_order = state.next == Some(number);
+        state.next = Some(number + 1);
+        if !in_order {
+            self.reset(&mut state, number + 1);
+            drop(state);
+            return self.provider.get_block_with_txs(BlockId::Number(number));
+        }
+
+        self.collect(&mut state);
+        self.dispatch(&mut state, number);
+        if number >= state.requested {
+            // Past the latest block when the window was last filled.
+            state.requested = number + 1;
+            drop(state);
+            return self.provider.get_block_with_txs(BlockId::Number(number));
+        }
+        while !state.fetched.contains_key(&number) {
+            if let Err(e) = self.receive(&mut state) {
+                tracing::error!("[prefetch] {}, fetching block {} directly", e, number);
+                // Drop the missing block's request, so a late result is not buffered.
+                self.reset(&mut state, number + 1);
+                drop(state);
+                return self.provider.get_block_with_txs(BlockId::Number(number));
+            }
+        }
+        state.fetched.remove(&number).unwrap_or(Ok(None))
+    }
+
+    /// Waits up to the result timeout for the next fetched block, and
+    /// buffers it if it is still wanted.
+    fn receive(&self, state: &mut State) -> Result<(), mpsc::RecvTimeoutError> {
+        let (g, n, fetched) = self.results.recv_timeout(self.result_timeout)?;
+        if g == self.generation.load(Ordering::Relaxed) {
+            state.fetched.insert(n, fetched);
+        }
+        Ok(())
+    }
+
+    /// Records a newly reported latest block. Unless it builds on the
+    /// previous one, the prefetched blocks may have been replaced, so they
+    /// are dropped and fetched again.
+    fn update_latest(&self, latest: Option<BlockInfo>) {
+        let mut state = self.state.borrow_mut();
+        let extends = match (state.latest, latest) {
+            (Some(previous), Some(latest)) => {
+                latest.hash == previous.hash || latest.parent_hash == previous.hash
+            }
+            _ => true,
+        };
+        if !extends {
+            tracing::debug!("[prefetch] head changed to {:?}", latest.map(|l| l.number));
+            let from = state.next.unwrap_or(state.requested);
+            self.reset(&mut state, from);
+        }
+        state.latest = latest;
+    }
+
+    /// Drops the prefetched blocks and restarts prefetching at `from`.
+    fn reset(&self, state: &mut State, from: u64) {
+        if !state.fetched.is_empty() || state.requested > from {
+            tracing::debug!(
+                "[prefetch] dropping prefetched blocks, restarting at {}",
+                from
+            );
+        }
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        state.fetched.clear();
+        state.requested = from;
+    }
+
+    /// Moves the blocks the workers have fetched into the buffer.
+    fn collect(&self, state: &mut State) {
+        let generation = self.generation.load(Ordering::Relaxed);
+        for (g, n, fetched) in self.results.try_iter() {
+            if g == generation {
+                state.fetched.insert(n, fetched);
+            }
+        }
+    }
+
+    /// Requests the blocks up to `window` past `number`, stopping at the
+    /// latest block.
+    fn dispatch(&self, state: &mut State, number: u64) {
+        let Some(latest) = state.latest else {
+            return;
+        };
+        let generation = self.generation.load(Ordering::Relaxed);
+        let end = number.saturating_add(self.window).min(latest.number);
+        while state.requested <= end {
+            if self.jobs.send((generation, state.requested)).is_err() {
+                return;
+            }
+            state.requested += 1;
+        }
+    }
+}
+
+/// Spawns a worker that fetches the requested blocks with its own provider
+/// until the [Prefetcher] is dropped.
+fn spawn_worker(
+    provider: Box<dyn Provider + Send>,
+    jobs: Arc<Mutex<Receiver<Job>>>,
+    results: Sender<(u64, u64, Fetched)>,
+    generation: Arc<AtomicU64>,
+) {
+    std::thread::spawn(move || loop {
+        let job = match jobs.lock() {
+            Ok(jobs) => jobs.recv(),
+            Err(_) => return,
+        };
+        let Ok((g, number)) = job else {
+            return;
+        };
+        if g != generation.load(Ordering::Relaxed) {
+            continue;
+        }
+        let fetched = provider.get_block_with_txs(BlockId::Number(number));
+        if results.send((g, number, fetched)).is_err() {
+            return;
+        }
+    });
+}
+
+impl Provider for Prefetcher {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        if let BlockId::Number(number) = block_id {
+            return self.get_block_by_number(number);
+        }
+        let block = self.provider.get_block_with_txs(block_id)?;
+        if block_id == BlockId::Kind(BlockKind::Latest) {
+            self.update_latest(block.as_ref().map(tracker::block_info));
+        }
+        Ok(block)
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        self.provider.get_receipts(block_id)
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        self.provider.get_logs(filter)
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        self.provider.get_transaction(hash)
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.provider.get_proof(address, slots, block_id)
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        self.provider.get_storage_at(address, slot, block_id)
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.provider.chain_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::poll_queue::PollQueue;
+    use crate::ingest::{BlockIngestor, BlockUpdate};
+    use axos_providers::mock::MockChain;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Condvar;
+
+    /// A thread-safe snapshot of a [MockChain] that records how many
+    /// lookups by number run at once, and which blocks it served.
+    #[derive(Clone)]
+    struct Snapshot {
+        blocks: Arc<Mutex<Vec<BlockWithTransactions>>>,
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+        served: Arc<(Mutex<Vec<u64>>, Condvar)>,
+        panic_at: Option<u64>,
+    }
+
+    impl Snapshot {
+        fn new(chain: &MockChain) -> Self {
+            let snapshot = Self {
+                blocks: Arc::new(Mutex::new(Vec::new())),
+                running: Arc::default(),
+                max_running: Arc::default(),
+                served: Arc::default(),
+                panic_at: None,
+            };
+            snapshot.update(chain);
+            snapshot
+        }
+
+        fn update(&self, chain: &MockChain) {
+            *self.blocks.lock().unwrap() =
+                (0..=chain.head()).filter_map(|n| chain.block(n)).collect();
+        }
+
+        /// Blocks until the block with the given number has been served.
+        fn wait_served(&self, number: u64) {
+            let (served, condvar) = &*self.served;
+            let served = served.lock().unwrap();
+            drop(
+                condvar
+                    .wait_while(served, |s| !s.contains(&number))
+                    .unwrap(),
+            );
+        }
+    }
+
+    impl Provider for Snapshot {
+        fn get_block_with_txs(
+            &self,
+            block_id: BlockId,
+        ) -> Result<Option<BlockWithTransactions>, Error> {
+            let blocks = self.blocks.lock().unwrap().clone();
+            match block_id {
+                BlockId::Number(number) => {
+                    assert_ne!(self.panic_at, Some(number), "worker failed");
+                    let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+                    self.max_running.fetch_max(running, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(5));
+                    self.running.fetch_sub(1, Ordering::SeqCst);
+                    let (served, condvar) = &*self.served;
+                    served.lock().unwrap().push(number);
+                    condvar.notify_all();
+                    Ok(blocks.get(number as usize).cloned())
+                }
+                BlockId::Kind(BlockKind::Latest) => Ok(blocks.last().cloned()),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    fn drain(poll_queue: &mut PollQueue) -> Vec<BlockUpdate> {
+        core::iter::from_fn(|| poll_queue.try_ingest().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_prefetch_in_order() {
+        let chain = MockChain::builder().blocks(40).build();
+        let snapshot = Snapshot::new(&chain);
+        let prefetcher = Prefetcher::new(|| Box::new(snapshot.clone()), 4, 8);
+        let mut poll_queue = PollQueue::from(Box::new(prefetcher) as Box<dyn Provider>);
+        let updates = drain(&mut poll_queue);
+        let expected = (0..40)
+            .map(|n| BlockUpdate::NewBlock(chain.block(n).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(updates, expected);
+        assert!(snapshot.max_running.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn test_prefetch_window() {
+        let chain = MockChain::builder().blocks(40).build();
+        let snapshot = Snapshot::new(&chain);
+        let prefetcher = Prefetcher::new(|| Box::new(snapshot.clone()), 2, 4);
+        prefetcher
+            .get_block_with_txs(BlockId::Kind(BlockKind::Latest))
+            .unwrap();
+        for number in 0..3 {
+            let block = prefetcher
+                .get_block_with_txs(BlockId::Number(number))
+                .unwrap();
+            assert_eq!(block, chain.block(number));
+        }
+        let mut state = prefetcher.state.borrow_mut();
+        while state.fetched.len() < 4 {
+            prefetcher.receive(&mut state).unwrap();
+        }
+        // Blocks 3 through 6 are prefetched, and nothing further.
+        assert_eq!(state.requested, 7);
+        drop(state);
+        assert_eq!(prefetcher.buffered(), 4);
+    }
+
+    #[test]
+    fn test_prefetch_reorg() {
+        let chain = MockChain::builder().blocks(10).build();
+        let snapshot = Snapshot::new(&chain);
+        let prefetcher = Prefetcher::new(|| Box::new(snapshot.clone()), 4, 8);
+        let mut poll_queue = PollQueue::from(Box::new(prefetcher) as Box<dyn Provider>);
+        assert_eq!(drain(&mut poll_queue).len(), 10);
+
+        // The latest block is prefetched, but not ingested, before the reorg.
+        snapshot.wait_served(10);
+        chain.reorg(3);
+        chain.advance();
+        snapshot.update(&chain);
+        let updates = drain(&mut poll_queue);
+        let mut expected = std::vec![BlockUpdate::Reorg {
+            common_ancestor: crate::ingest::tracker::block_info(&chain.block(7).unwrap()),
+            depth: 2,
+        }];
+        expected.extend((8..=10).map(|n| BlockUpdate::NewBlock(chain.block(n).unwrap())));
+        assert_eq!(updates, expected);
+    }
+
+    #[test]
+    fn test_prefetch_failed_worker() {
+        let chain = MockChain::builder().blocks(10).build();
+        let snapshot = Snapshot::new(&chain);
+        let workers = core::cell::Cell::new(2usize);
+        // Both workers fail on block 3, so one of them exits while the
+        // other keeps running. Lookups made directly succeed.
+        let prefetcher = Prefetcher::new(
+            || {
+                let worker = workers.get() > 0;
+                workers.set(workers.get().saturating_sub(1));
+                Box::new(Snapshot {
+                    panic_at: worker.then_some(3),
+                    ..snapshot.clone()
+                })
+            },
+            2,
+            8,
+        )
+        .with_result_timeout(Duration::from_millis(200));
+        let mut poll_queue = PollQueue::from(Box::new(prefetcher) as Box<dyn Provider>);
+        let updates = drain(&mut poll_queue);
+        let expected = (0..10)
+            .map(|n| BlockUpdate::NewBlock(chain.block(n).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(updates, expected);
+    }
+}
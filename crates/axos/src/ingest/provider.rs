@@ -3,8 +3,15 @@
 #[cfg(feature = "alloc")]
 use alloc::boxed::Box;
 
-use axos_primitives::{BlockId, BlockWithTransactions};
-use axos_providers::mock::MockProvider;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{AccountProof, Address, BlockId, BlockWithTransactions, B256};
+#[cfg(feature = "alloc")]
+use axos_primitives::{Filter, Log, TransactionReceipt};
+#[cfg(feature = "alloc")]
+use axos_providers::mock::MockChain;
 use axos_providers::provider::{Error, Provider};
 
 /// InnerProvider wraps a [Provider].
@@ -15,19 +22,12 @@ pub struct InnerProvider(
 );
 
 #[cfg(feature = "alloc")]
-impl From<MockProvider> for InnerProvider {
-    fn from(provider: MockProvider) -> Self {
+impl From<MockChain> for InnerProvider {
+    fn from(provider: MockChain) -> Self {
         Self(Some(Box::new(provider)))
     }
 }
 
-#[cfg(not(feature = "alloc"))]
-impl From<MockProvider> for InnerProvider {
-    fn from(provider: MockProvider) -> Self {
-        Self(Some(&mut provider))
-    }
-}
-
 #[cfg(feature = "alloc")]
 impl From<Box<dyn Provider>> for InnerProvider {
     fn from(provider: Box<dyn Provider>) -> Self {
@@ -55,39 +55,79 @@ impl core::fmt::Debug for InnerProvider {
     }
 }
 
+impl InnerProvider {
+    /// Returns a reference to the wrapped [Provider], if any.
+    fn inner(&self) -> Option<&dyn Provider> {
+        match self.0 {
+            Some(ref provider) => Some(&**provider),
+            None => None,
+        }
+    }
+}
+
 impl Provider for InnerProvider {
     fn get_block_with_txs(
         &self,
         block_id: BlockId,
     ) -> Result<Option<BlockWithTransactions>, Error> {
-        match self.0 {
-            #[cfg(feature = "alloc")]
-            Some(ref provider) => provider.get_block_with_txs(block_id),
-            #[cfg(not(feature = "alloc"))]
-            Some(provider) => provider.get_block_with_txs(block_id),
-            None => Err(Error::BlockNotFound),
-        }
+        self.inner()
+            .ok_or(Error::BlockNotFound)?
+            .get_block_with_txs(block_id)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        self.inner()
+            .ok_or(Error::Unsupported)?
+            .get_receipts(block_id)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        self.inner().ok_or(Error::Unsupported)?.get_logs(filter)
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        self.inner()
+            .ok_or(Error::Unsupported)?
+            .get_transaction(hash)
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.inner()
+            .ok_or(Error::Unsupported)?
+            .get_proof(address, slots, block_id)
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        self.inner()
+            .ok_or(Error::Unsupported)?
+            .get_storage_at(address, slot, block_id)
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.inner().ok_or(Error::Unsupported)?.chain_id()
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    #[cfg(feature = "alloc")]
-    use alloc::string::ToString;
-    use axos_providers::mock::MockProvider;
-
-    fn build_mock_provider() -> MockProvider {
-        #[cfg(feature = "alloc")]
-        let url = "http://localhost:8080".to_string();
-        #[cfg(not(feature = "alloc"))]
-        let url = "http://localhost:8080";
-        MockProvider::new(url)
-    }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_provider() {
-        let provider = build_mock_provider();
+        let provider = MockChain::builder().blocks(2).build();
         let inner_provider = InnerProvider::from(provider);
         assert!(inner_provider
             .get_block_with_txs(BlockId::Number(1))
@@ -95,5 +135,17 @@ mod test {
         assert!(inner_provider
             .get_block_with_txs(BlockId::Number(1))
             .is_ok());
+        assert!(inner_provider.get_receipts(BlockId::Number(1)).is_ok());
+        assert!(inner_provider.chain_id().is_ok());
+    }
+
+    #[test]
+    fn test_empty_provider() {
+        let inner_provider = InnerProvider::default();
+        assert_eq!(
+            inner_provider.get_block_with_txs(BlockId::Number(1)),
+            Err(Error::BlockNotFound)
+        );
+        assert_eq!(inner_provider.chain_id(), Err(Error::Unsupported));
     }
 }
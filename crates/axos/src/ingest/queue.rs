@@ -1,17 +1,80 @@
 //! Internal queue for ingesting blocks.
+//!
+//! Ingestors buffer blocks in a [BlockQueue]. With `alloc`, the
+//! [InnerQueue] grows without bound, while a [RingQueue] holds at most `N`
+//! blocks in a fixed array and needs no allocator. An ingestor stops
+//! fetching while its queue is full.
+//!
+//! The [DefaultQueue] is the [InnerQueue] with `alloc`, and a [RingQueue]
+//! of [DEFAULT_RING_CAPACITY] blocks without.
 
 #[cfg(feature = "alloc")]
 use alloc::collections::VecDeque;
 
 use axos_primitives::BlockWithTransactions;
 
+/// A queue of blocks waiting to be ingested.
+pub trait BlockQueue {
+    /// Pushes a block onto the back of the queue, handing it back if the
+    /// queue is full.
+    // Handing the block back moves it, and boxing it needs an allocator.
+    #[allow(clippy::result_large_err)]
+    fn push_back(&mut self, block: BlockWithTransactions) -> Result<(), BlockWithTransactions>;
+
+    /// Pops the front of the queue.
+    fn pop_front(&mut self) -> Option<BlockWithTransactions>;
+
+    /// Returns the front of the queue.
+    fn front(&self) -> Option<&BlockWithTransactions>;
+
+    /// Returns the number of queued blocks.
+    fn len(&self) -> usize;
+
+    /// Returns true if no blocks are queued.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of blocks the queue can hold, if it is bounded.
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns true if no more blocks can be queued.
+    fn is_full(&self) -> bool {
+        self.capacity()
+            .is_some_and(|capacity| self.len() >= capacity)
+    }
+
+    /// Returns true if a block with the given number is queued.
+    fn contains(&self, number: u64) -> bool;
+
+    /// Retains only the blocks matching the predicate.
+    fn retain<F: FnMut(&BlockWithTransactions) -> bool>(&mut self, f: F);
+}
+
+/// The number of blocks the [DefaultQueue] holds without `alloc`.
+pub const DEFAULT_RING_CAPACITY: usize = 16;
+
+/// The queue ingestors use by default.
+#[cfg(feature = "alloc")]
+pub type DefaultQueue = InnerQueue;
+
+/// The queue ingestors use by default.
+#[cfg(not(feature = "alloc"))]
+pub type DefaultQueue = RingQueue<DEFAULT_RING_CAPACITY>;
+
+/// Returns true if the block has the given number.
+fn is_number(block: &BlockWithTransactions, number: u64) -> bool {
+    block.number.is_some_and(|n| n.to::<u64>() == number)
+}
+
 /// InnerQueue wraps a queue of [BlockWithTransactions].
+#[cfg(feature = "alloc")]
 #[derive(Debug, Default)]
-pub struct InnerQueue(
-    #[cfg(feature = "alloc")] pub VecDeque<BlockWithTransactions>,
-    #[cfg(not(feature = "alloc"))] pub &'static mut VecDeque<BlockWithTransactions>,
-);
+pub struct InnerQueue(pub VecDeque<BlockWithTransactions>);
 
+#[cfg(feature = "alloc")]
 impl Iterator for InnerQueue {
     type Item = BlockWithTransactions;
 
@@ -20,6 +83,7 @@ impl Iterator for InnerQueue {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl InnerQueue {
     /// Return an iterator over the queue.
     pub fn iter(&self) -> impl Iterator<Item = &BlockWithTransactions> {
@@ -46,17 +110,130 @@ impl InnerQueue {
         self.0.pop_front()
     }
 
+    /// Retains only the blocks matching the predicate.
+    pub fn retain(&mut self, f: impl FnMut(&BlockWithTransactions) -> bool) {
+        self.0.retain(f);
+    }
+
     /// Push to the back of the queue.
     pub fn push_back(&mut self, block: BlockWithTransactions) {
         self.0.push_back(block);
     }
 }
 
+#[cfg(feature = "alloc")]
+impl BlockQueue for InnerQueue {
+    fn push_back(&mut self, block: BlockWithTransactions) -> Result<(), BlockWithTransactions> {
+        self.0.push_back(block);
+        Ok(())
+    }
+
+    fn pop_front(&mut self) -> Option<BlockWithTransactions> {
+        self.0.pop_front()
+    }
+
+    fn front(&self) -> Option<&BlockWithTransactions> {
+        self.0.front()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn contains(&self, number: u64) -> bool {
+        self.0.iter().any(|b| is_number(b, number))
+    }
+
+    fn retain<F: FnMut(&BlockWithTransactions) -> bool>(&mut self, f: F) {
+        self.0.retain(f);
+    }
+}
+
+/// RingQueue is a fixed-capacity queue of up to `N` blocks.
+#[derive(Debug)]
+pub struct RingQueue<const N: usize> {
+    /// The queued blocks, as a ring.
+    blocks: [Option<BlockWithTransactions>; N],
+    /// The ring index of the front of the queue.
+    start: usize,
+    /// The number of queued blocks.
+    len: usize,
+}
+
+impl<const N: usize> Default for RingQueue<N> {
+    fn default() -> Self {
+        Self {
+            blocks: core::array::from_fn(|_| None),
+            start: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> RingQueue<N> {
+    /// Return an iterator over the queue.
+    pub fn iter(&self) -> impl Iterator<Item = &BlockWithTransactions> {
+        (0..self.len).filter_map(move |i| self.blocks[(self.start + i) % N].as_ref())
+    }
+}
+
+impl<const N: usize> BlockQueue for RingQueue<N> {
+    fn push_back(&mut self, block: BlockWithTransactions) -> Result<(), BlockWithTransactions> {
+        if self.len == N {
+            return Err(block);
+        }
+        self.blocks[(self.start + self.len) % N] = Some(block);
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop_front(&mut self) -> Option<BlockWithTransactions> {
+        if self.len == 0 {
+            return None;
+        }
+        let block = self.blocks[self.start].take();
+        self.start = (self.start + 1) % N;
+        self.len -= 1;
+        block
+    }
+
+    fn front(&self) -> Option<&BlockWithTransactions> {
+        self.iter().next()
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(N)
+    }
+
+    fn contains(&self, number: u64) -> bool {
+        self.iter().any(|b| is_number(b, number))
+    }
+
+    fn retain<F: FnMut(&BlockWithTransactions) -> bool>(&mut self, mut f: F) {
+        let mut kept = 0;
+        for i in 0..self.len {
+            let Some(block) = self.blocks[(self.start + i) % N].take() else {
+                continue;
+            };
+            if f(&block) {
+                self.blocks[(self.start + kept) % N] = Some(block);
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_queue() {
         let mut queue = InnerQueue::default();
         assert_eq!(queue.len(), 0);
@@ -70,4 +247,34 @@ mod test {
         assert_eq!(queue.len(), 0);
         assert!(queue.is_empty());
     }
+
+    #[test]
+    fn test_ring_queue() {
+        use axos_primitives::U64;
+
+        let block = |n: u64| BlockWithTransactions {
+            number: Some(U64::from(n)),
+            ..Default::default()
+        };
+        let mut queue = RingQueue::<3>::default();
+        assert_eq!(queue.capacity(), Some(3));
+        for n in 0..3 {
+            assert!(queue.push_back(block(n)).is_ok());
+        }
+        assert!(queue.is_full());
+        assert_eq!(queue.push_back(block(3)), Err(block(3)));
+
+        // Wrap around the end of the ring.
+        assert_eq!(queue.pop_front(), Some(block(0)));
+        assert!(queue.push_back(block(3)).is_ok());
+        assert!(queue.contains(3));
+        assert!(!queue.contains(0));
+
+        queue.retain(|b| b.number != Some(U64::from(2)));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop_front(), Some(block(1)));
+        assert_eq!(queue.pop_front(), Some(block(3)));
+        assert_eq!(queue.pop_front(), None);
+        assert!(queue.is_empty());
+    }
 }
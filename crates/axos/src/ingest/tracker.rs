@@ -0,0 +1,362 @@
+//! Chain Tracker
+//!
+//! The [ChainTracker] remembers the most recent canonical blocks an
+//! ingestor has seen, so that a block which does not build on them can
+//! be traced back to the last block both chains share.
+//!
+//! Blocks are kept in a fixed-size ring, so tracking needs no allocator.
+//! A reorg that replaces every tracked block fails with a
+//! [ReorgTooDeepError], since the last shared block is unknown.
+
+use axos_primitives::{BlockId, BlockInfo, BlockWithTransactions};
+use axos_providers::provider::{Error, Provider};
+
+/// The number of recent blocks a [ChainTracker] remembers.
+pub const MAX_TRACKED_BLOCKS: usize = 64;
+
+/// A reorg replaced every tracked block, so the common ancestor is
+/// older than the tracker remembers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorgTooDeepError {
+    /// The number of the block that does not build on the tracked blocks.
+    pub block: u64,
+    /// The number of the oldest tracked block.
+    pub oldest: u64,
+}
+
+impl core::fmt::Display for ReorgTooDeepError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "reorg at block {} is deeper than the tracked blocks from {}",
+            self.block, self.oldest
+        )
+    }
+}
+
+impl core::error::Error for ReorgTooDeepError {}
+
+/// Without an allocator, the error is reported as [Error::BlockNotFound].
+impl From<ReorgTooDeepError> for Error {
+    fn from(error: ReorgTooDeepError) -> Self {
+        #[cfg(feature = "alloc")]
+        return Error::other(error);
+        #[cfg(not(feature = "alloc"))]
+        {
+            let _ = error;
+            Error::BlockNotFound
+        }
+    }
+}
+
+/// Returns the [BlockInfo] of a block.
+pub fn block_info(block: &BlockWithTransactions) -> BlockInfo {
+    BlockInfo::new(
+        block.hash.unwrap_or_default(),
+        block.number.unwrap_or_default().to::<u64>(),
+        block.parent_hash,
+        block.timestamp.to::<u64>(),
+    )
+}
+
+/// Tracks a contiguous run of recent canonical blocks.
+#[derive(Debug, Clone)]
+pub struct ChainTracker {
+    /// The tracked blocks, as a ring.
+    blocks: [BlockInfo; MAX_TRACKED_BLOCKS],
+    /// The ring index of the oldest tracked block.
+    start: usize,
+    /// The number of tracked blocks.
+    len: usize,
+}
+
+impl Default for ChainTracker {
+    fn default() -> Self {
+        Self {
+            blocks: [BlockInfo::default(); MAX_TRACKED_BLOCKS],
+            start: 0,
+            len: 0,
+        }
+    }
+}
+
+impl ChainTracker {
+    /// Instantiates a new, empty [ChainTracker].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of tracked blocks.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no blocks are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the newest tracked block.
+    pub fn head(&self) -> Option<BlockInfo> {
+        self.len.checked_sub(1).map(|i| self.at(i))
+    }
+
+    /// Returns the oldest tracked block.
+    pub fn oldest(&self) -> Option<BlockInfo> {
+        (!self.is_empty()).then(|| self.at(0))
+    }
+
+    /// Returns the tracked block with the given number.
+    pub fn get(&self, number: u64) -> Option<BlockInfo> {
+        let offset = number.checked_sub(self.oldest()?.number)?;
+        (offset < self.len as u64).then(|| self.at(offset as usize))
+    }
+
+    /// Returns true if the block is tracked.
+    pub fn contains(&self, block: &BlockInfo) -> bool {
+        self.get(block.number).is_some_and(|b| b.hash == block.hash)
+    }
+
+    /// Returns true if the block builds on the head, or nothing is tracked.
+    pub fn extends(&self, block: &BlockInfo) -> bool {
+        match self.head() {
+            Some(head) => block.number == head.number + 1 && block.parent_hash == head.hash,
+            None => true,
+        }
+    }
+
+    /// Returns true if the block replaces a tracked block, or claims to
+    /// build on the head without doing so.
+    pub fn conflicts(&self, block: &BlockInfo) -> bool {
+        match (self.oldest(), self.head()) {
+            (Some(oldest), Some(head)) => {
+                (oldest.number..=head.number + 1).contains(&block.number)
+                    && !self.contains(block)
+                    && !self.extends(block)
+            }
+            _ => false,
+        }
+    }
+
+    /// Tracks a new head. If it does not follow the current head by
+    /// number, the tracked blocks are dropped first.
+    pub fn push(&mut self, block: BlockInfo) {
+        if self
+            .head()
+            .is_some_and(|head| block.number != head.number + 1)
+        {
+            self.clear();
+        }
+        if self.len == MAX_TRACKED_BLOCKS {
+            self.start = (self.start + 1) % MAX_TRACKED_BLOCKS;
+            self.len -= 1;
+        }
+        self.blocks[(self.start + self.len) % MAX_TRACKED_BLOCKS] = block;
+        self.len += 1;
+    }
+
+    /// Drops the tracked blocks above the given number and returns how
+    /// many were dropped.
+    pub fn truncate(&mut self, number: u64) -> u64 {
+        let Some(head) = self.head() else {
+            return 0;
+        };
+        let keep = match self.oldest() {
+            Some(oldest) if number >= oldest.number => (number - oldest.number + 1) as usize,
+            _ => 0,
+        };
+        let dropped = head.number.saturating_sub(number);
+        self.len = self.len.min(keep);
+        dropped
+    }
+
+    /// Drops the tracked blocks above the ancestor, tracking the ancestor
+    /// if it is older than every tracked block. Returns how many blocks
+    /// were dropped.
+    pub fn rewind(&mut self, ancestor: BlockInfo) -> u64 {
+        let dropped = self.truncate(ancestor.number);
+        if self.is_empty() {
+            self.push(ancestor);
+        }
+        dropped
+    }
+
+    /// Drops the tracked blocks below the given number, which can no
+    /// longer be reorged. The head is always kept.
+    pub fn prune(&mut self, number: u64) {
+        let Some(oldest) = self.oldest() else {
+            return;
+        };
+        let dropped = (number.saturating_sub(oldest.number) as usize).min(self.len - 1);
+        self.start = (self.start + dropped) % MAX_TRACKED_BLOCKS;
+        self.len -= dropped;
+    }
+
+    /// Drops all tracked blocks.
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Finds the newest tracked block that is still canonical and is an
+    /// ancestor of the given block.
+    ///
+    /// If the block's parent is tracked, it is the ancestor. Otherwise the
+    /// tracked blocks are compared with the provider's canonical chain,
+    /// newest first. When none of them is canonical any more, this fails
+    /// with a [ReorgTooDeepError].
+    pub fn find_common_ancestor(
+        &self,
+        block: &BlockInfo,
+        provider: &dyn Provider,
+    ) -> Result<BlockInfo, Error> {
+        let (Some(oldest), Some(head)) = (self.oldest(), self.head()) else {
+            return Err(Error::BlockNotFound);
+        };
+        if let Some(parent) = block.number.checked_sub(1).and_then(|n| self.get(n)) {
+            if parent.hash == block.parent_hash {
+                return Ok(parent);
+            }
+        }
+        for number in (oldest.number..=head.number).rev() {
+            let canonical = provider.get_block_with_txs(BlockId::Number(number))?;
+            if canonical.and_then(|b| b.hash) == self.get(number).map(|b| b.hash) {
+                return self.get(number).ok_or(Error::BlockNotFound);
+            }
+        }
+        tracing::error!(
+            "[tracker] reorg at block {} is deeper than the tracked blocks from {}",
+            block.number,
+            oldest.number
+        );
+        Err(ReorgTooDeepError {
+            block: block.number,
+            oldest: oldest.number,
+        }
+        .into())
+    }
+
+    /// Returns the block at the given offset from the oldest.
+    fn at(&self, offset: usize) -> BlockInfo {
+        self.blocks[(self.start + offset) % MAX_TRACKED_BLOCKS]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_primitives::B256;
+
+    fn info(number: u64, fork: u8) -> BlockInfo {
+        let hash = |n: u64, f: u8| {
+            let mut bytes = [0u8; 32];
+            bytes[..8].copy_from_slice(&n.to_be_bytes());
+            bytes[31] = f;
+            B256::from(bytes)
+        };
+        BlockInfo::new(
+            hash(number, fork),
+            number,
+            hash(number.wrapping_sub(1), fork),
+            0,
+        )
+    }
+
+    #[test]
+    fn test_tracker_ring() {
+        let mut tracker = ChainTracker::new();
+        for number in 0..MAX_TRACKED_BLOCKS as u64 + 10 {
+            assert!(tracker.extends(&info(number, 0)));
+            tracker.push(info(number, 0));
+        }
+        assert_eq!(tracker.len(), MAX_TRACKED_BLOCKS);
+        assert_eq!(tracker.oldest().unwrap().number, 10);
+        assert_eq!(tracker.get(40), Some(info(40, 0)));
+        assert_eq!(tracker.get(9), None);
+        assert!(tracker.contains(&info(50, 0)));
+        assert!(!tracker.contains(&info(50, 1)));
+    }
+
+    #[test]
+    fn test_tracker_conflicts() {
+        let mut tracker = ChainTracker::new();
+        assert!(!tracker.conflicts(&info(5, 0)));
+        for number in 5..10 {
+            tracker.push(info(number, 0));
+        }
+        assert!(tracker.conflicts(&info(8, 1)));
+        assert!(tracker.conflicts(&info(10, 1)));
+        assert!(!tracker.conflicts(&info(10, 0)));
+        assert!(!tracker.conflicts(&info(8, 0)));
+        assert!(!tracker.conflicts(&info(4, 1)));
+        assert!(!tracker.conflicts(&info(12, 1)));
+    }
+
+    #[test]
+    fn test_tracker_truncate() {
+        let mut tracker = ChainTracker::new();
+        for number in 5..10 {
+            tracker.push(info(number, 0));
+        }
+        assert_eq!(tracker.truncate(7), 2);
+        assert_eq!(tracker.head(), Some(info(7, 0)));
+        assert_eq!(tracker.truncate(2), 5);
+        assert!(tracker.is_empty());
+
+        for number in 5..10 {
+            tracker.push(info(number, 0));
+        }
+        assert_eq!(tracker.rewind(info(3, 0)), 6);
+        assert_eq!(tracker.head(), Some(info(3, 0)));
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[test]
+    fn test_tracker_prune() {
+        let mut tracker = ChainTracker::new();
+        for number in 5..10 {
+            tracker.push(info(number, 0));
+        }
+        tracker.prune(7);
+        assert_eq!(tracker.oldest(), Some(info(7, 0)));
+        assert_eq!(tracker.len(), 3);
+        tracker.prune(20);
+        assert_eq!(tracker.oldest(), Some(info(9, 0)));
+        assert_eq!(tracker.head(), Some(info(9, 0)));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_find_common_ancestor() {
+        use axos_providers::mock::MockChain;
+
+        let chain = MockChain::builder().blocks(10).build();
+        let mut tracker = ChainTracker::new();
+        for number in 4..=10 {
+            tracker.push(block_info(&chain.block(number).unwrap()));
+        }
+        chain.reorg(3);
+        chain.advance();
+        let new = block_info(&chain.block(11).unwrap());
+        assert!(tracker.conflicts(&new));
+        let ancestor = tracker.find_common_ancestor(&new, &chain).unwrap();
+        assert_eq!(ancestor, block_info(&chain.block(7).unwrap()));
+
+        let replaced = block_info(&chain.block(8).unwrap());
+        assert_eq!(
+            tracker.find_common_ancestor(&replaced, &chain),
+            Ok(ancestor)
+        );
+
+        chain.reorg(8);
+        let deep = block_info(&chain.block(10).unwrap());
+        let err = tracker.find_common_ancestor(&deep, &chain).unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&ReorgTooDeepError {
+                block: 10,
+                oldest: 4
+            })
+        );
+    }
+}
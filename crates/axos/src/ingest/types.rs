@@ -3,16 +3,29 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use axos_primitives::BlockWithTransactions;
+use axos_primitives::{BlockInfo, BlockWithTransactions, L1BlockData};
 
 /// Block Update
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Eq, PartialEq)]
+// Blocks are by far the most common update, and boxing them needs an allocator.
+#[allow(clippy::large_enum_variant)]
 pub enum BlockUpdate {
     /// A new block extending the current chain
     NewBlock(BlockWithTransactions),
+    /// A new block extending the current chain, stripped down to the
+    /// data derivation reads
+    NewBlockData(L1BlockData),
     /// Updates the most recent finalized block
     FinalityUpdate(u64),
-    /// Reorg detected
-    Reorg,
+    /// Updates the most recent safe block
+    SafeUpdate(u64),
+    /// Blocks that were already ingested have been replaced. The
+    /// replacement blocks follow as [BlockUpdate::NewBlock]s.
+    Reorg {
+        /// The newest block shared by the old and new chains
+        common_ancestor: BlockInfo,
+        /// The number of ingested blocks that were replaced
+        depth: u64,
+    },
 }
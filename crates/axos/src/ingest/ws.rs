@@ -0,0 +1,517 @@
+//! WebSocket Ingestor
+//!
+//! The [WsIngestor] is a [BlockIngestor] that subscribes to `newHeads`
+//! over a WebSocket connection and fetches the full block for every head
+//! it is notified of, instead of polling for the latest block.
+//!
+//! Blocks are fetched from a separate [Provider], so the subscription can
+//! live on a local node while blocks come from any provider stack. When a
+//! new head does not build on the previous ones, a [BlockUpdate::Reorg]
+//! with the common ancestor is emitted, followed by the new chain.
+//!
+//! Dropped connections are re-established on the next call to
+//! [BlockIngestor::try_ingest]. Heights missed while disconnected, or
+//! skipped between notifications, are backfilled by number.
+//!
+//! With a confirmation depth, blocks are only fetched once they are at
+//! least that many blocks below the notified head, the same rule the
+//! [PollQueue][poll_queue::PollQueue] applies.
+//!
+//! Only plain `ws://` urls are supported.
+
+use std::boxed::Box;
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::string::String;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::{Message, WebSocket};
+
+use axos_primitives::{BlockId, BlockInfo, BlockKind, BlockWithTransactions, B256, U64};
+use axos_providers::provider::{Error, Provider};
+
+use crate::ingest::*;
+
+/// The default time a call to [BlockIngestor::try_ingest] waits for a
+/// notification.
+pub const DEFAULT_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The timeout for connecting and completing the WebSocket handshake.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The `eth_subscribe` request for new heads.
+const SUBSCRIBE_REQUEST: &str =
+    r#"{"jsonrpc":"2.0","id":1,"method":"eth_subscribe","params":["newHeads"]}"#;
+
+/// A block header from a `newHeads` notification.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Header {
+    /// The block hash.
+    hash: B256,
+    /// The block number.
+    number: U64,
+}
+
+/// A json-rpc message received over the subscription.
+#[derive(Debug, Deserialize)]
+struct SubscriptionMessage {
+    /// The notification params.
+    #[serde(default)]
+    params: Option<SubscriptionParams>,
+    /// The json-rpc error, if a request failed.
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+/// The params of an `eth_subscription` notification.
+#[derive(Debug, Deserialize)]
+struct SubscriptionParams {
+    /// The new head.
+    result: Header,
+}
+
+/// WebSocket `newHeads` Ingestor
+pub struct WsIngestor {
+    /// The WebSocket url.
+    url: String,
+    /// The provider full blocks are fetched from.
+    provider: Box<dyn Provider>,
+    /// The open subscription, if connected.
+    socket: Option<WebSocket<TcpStream>>,
+    /// The time to wait for a notification.
+    poll_timeout: Duration,
+    /// Updates waiting to be returned.
+    pending: VecDeque<BlockUpdate>,
+    /// The recent blocks emitted.
+    tracker: tracker::ChainTracker,
+    /// The number of blocks a block must be below the head before it is
+    /// fetched.
+    confirmation_depth: u64,
+    /// The last error recovered from, until it is taken.
+    recovered: Option<anyhow::Error>,
+}
+
+impl core::fmt::Debug for WsIngestor {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WsIngestor")
+            .field("url", &self.url)
+            .field("connected", &self.socket.is_some())
+            .field("poll_timeout", &self.poll_timeout)
+            .field("pending", &self.pending.len())
+            .field("head", &self.tracker.head())
+            .field("confirmation_depth", &self.confirmation_depth)
+            .finish()
+    }
+}
+
+impl WsIngestor {
+    /// Instantiates a new [WsIngestor] that subscribes at the given url
+    /// and fetches blocks from the given provider.
+    pub fn new(url: impl Into<String>, provider: Box<dyn Provider>) -> Self {
+        Self {
+            url: url.into(),
+            provider,
+            socket: None,
+            poll_timeout: DEFAULT_POLL_TIMEOUT,
+            pending: VecDeque::new(),
+            tracker: tracker::ChainTracker::new(),
+            confirmation_depth: 0,
+            recovered: None,
+        }
+    }
+
+    /// Sets the time a call to [BlockIngestor::try_ingest] waits for a
+    /// notification.
+    pub fn with_poll_timeout(mut self, poll_timeout: Duration) -> Self {
+        self.poll_timeout = poll_timeout;
+        self
+    }
+
+    /// Sets the number of blocks a block must be below the head before
+    /// it is fetched.
+    pub fn with_confirmation_depth(mut self, confirmation_depth: u64) -> Self {
+        self.confirmation_depth = confirmation_depth;
+        self
+    }
+
+    /// Starts ingesting after the given block. Blocks between it and the
+    /// latest block are backfilled once connected.
+    pub fn with_head(mut self, head: BlockInfo) -> Self {
+        self.tracker.push(head);
+        self
+    }
+
+    /// Returns the last block emitted.
+    pub fn head(&self) -> Option<BlockInfo> {
+        self.tracker.head()
+    }
+
+    /// Returns true if the subscription is open.
+    pub fn is_connected(&self) -> bool {
+        self.socket.is_some()
+    }
+
+    /// Opens the WebSocket connection and subscribes to new heads.
+    fn connect(&mut self) -> anyhow::Result<()> {
+        let request = self.url.as_str().into_client_request()?;
+        let host = request
+            .uri()
+            .host()
+            .ok_or_else(|| anyhow::anyhow!("missing host in {}", self.url))?;
+        let port = request.uri().port_u16().unwrap_or(80);
+        let stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+        let (mut socket, _) = tungstenite::client(request, stream)
+            .map_err(|e| anyhow::anyhow!("websocket handshake failed: {}", e))?;
+        socket.get_ref().set_read_timeout(Some(self.poll_timeout))?;
+        socket.send(Message::Text(SUBSCRIBE_REQUEST.into()))?;
+        self.socket = Some(socket);
+        Ok(())
+    }
+
+    /// Waits up to the poll timeout for the next new head.
+    fn read_header(&mut self) -> anyhow::Result<Option<Header>> {
+        let Some(socket) = self.socket.as_mut() else {
+            return Ok(None);
+        };
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => anyhow::bail!("connection closed by server"),
+            Ok(_) => return Ok(None),
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                return Ok(None)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let message: SubscriptionMessage = serde_json::from_str(&text)?;
+        if let Some(error) = message.error {
+            anyhow::bail!("subscription failed: {}", error);
+        }
+        Ok(message.params.map(|p| p.result))
+    }
+
+    /// Fetches the blocks from the current head up to the latest block.
+    fn backfill(&mut self) -> Result<(), Error> {
+        let Some(head) = self.tracker.head() else {
+            return Ok(());
+        };
+        let Some(latest) = self
+            .provider
+            .get_block_with_txs(BlockId::Kind(BlockKind::Latest))?
+        else {
+            return Ok(());
+        };
+        let latest = latest.number.unwrap_or_default().to::<u64>();
+        for number in head.number + 1..=latest.saturating_sub(self.confirmation_depth) {
+            self.fetch(BlockId::Number(number))?;
+        }
+        Ok(())
+    }
+
+    /// Fetches the notified block, and any heights skipped since the
+    /// current head. With a confirmation depth, the blocks up to the
+    /// confirmed height are fetched by number instead.
+    fn on_header(&mut self, header: Header) -> Result<(), Error> {
+        let number = header.number.to::<u64>();
+        if self.confirmation_depth > 0 {
+            let Some(confirmed) = number.checked_sub(self.confirmation_depth) else {
+                return Ok(());
+            };
+            let next = self
+                .tracker
+                .head()
+                .map_or(confirmed, |head| head.number + 1);
+            for number in next..=confirmed {
+                self.fetch(BlockId::Number(number))?;
+            }
+            return Ok(());
+        }
+        if self
+            .tracker
+            .get(number)
+            .is_some_and(|b| b.hash == header.hash)
+        {
+            return Ok(());
+        }
+        if let Some(head) = self.tracker.head() {
+            for missed in head.number + 1..number {
+                self.fetch(BlockId::Number(missed))?;
+            }
+        }
+        self.fetch(BlockId::Hash(header.hash))
+    }
+
+    /// Fetches a block and queues the updates it produces.
+    fn fetch(&mut self, block_id: BlockId) -> Result<(), Error> {
+        match self.provider.get_block_with_txs(block_id)? {
+            Some(block) => self.push(block),
+            None => Err(Error::BlockNotFound),
+        }
+    }
+
+    /// Queues a block. If it does not build on the tracked blocks, a
+    /// reorg to the common ancestor is queued first, followed by the
+    /// blocks between the ancestor and the new block.
+    fn push(&mut self, block: BlockWithTransactions) -> Result<(), Error> {
+        let info = tracker::block_info(&block);
+        if self.tracker.contains(&info) {
+            return Ok(());
+        }
+        if self.tracker.conflicts(&info) {
+            let ancestor = self.tracker.find_common_ancestor(&info, &*self.provider)?;
+            if self
+                .tracker
+                .head()
+                .is_some_and(|head| head.hash == ancestor.hash)
+            {
+                tracing::debug!("[ws] block {} is not canonical, skipping", info.number);
+                return Ok(());
+            }
+            let depth = self.tracker.rewind(ancestor);
+            tracing::warn!(
+                "[ws] reorg detected at block {}: common ancestor {}, depth {}",
+                info.number,
+                ancestor.number,
+                depth
+            );
+            self.pending.push_back(BlockUpdate::Reorg {
+                common_ancestor: ancestor,
+                depth,
+            });
+            for number in ancestor.number + 1..info.number {
+                self.fetch(BlockId::Number(number))?;
+            }
+            return self.push(block);
+        }
+        self.tracker.push(info);
+        self.pending.push_back(BlockUpdate::NewBlock(block));
+        Ok(())
+    }
+}
+
+impl BlockIngestor for WsIngestor {
+    fn try_ingest(&mut self) -> anyhow::Result<Option<BlockUpdate>> {
+        if let Some(update) = self.pending.pop_front() {
+            return Ok(Some(update));
+        }
+
+        if self.socket.is_none() {
+            if let Err(e) = self.connect() {
+                tracing::warn!("[ws] failed to connect to {}: {}", self.url, e);
+                self.recovered = Some(e);
+                return Ok(None);
+            }
+            tracing::info!("[ws] subscribed to new heads at {}", self.url);
+            match self.backfill() {
+                Err(e) if !e.is_retryable() => return Err(anyhow::Error::msg(e)),
+                Err(e) => {
+                    tracing::warn!("[ws] backfill failed, will retry: {}", e);
+                    self.recovered = Some(anyhow::Error::msg(e));
+                }
+                Ok(()) => {}
+            }
+        }
+
+        let result = match self.read_header() {
+            Ok(Some(header)) => self.on_header(header),
+            Ok(None) => Ok(()),
+            Err(e) => {
+                tracing::warn!("[ws] subscription dropped, reconnecting: {}", e);
+                self.socket = None;
+                Ok(())
+            }
+        };
+        match result {
+            Err(e) if !e.is_retryable() && e != Error::BlockNotFound => {
+                return Err(anyhow::Error::msg(e))
+            }
+            Err(e) => {
+                tracing::warn!("[ws] failed to fetch block, will backfill: {}", e);
+                self.recovered = Some(anyhow::Error::msg(e));
+            }
+            Ok(()) => {}
+        }
+        Ok(self.pending.pop_front())
+    }
+
+    fn take_recovered_error(&mut self) -> Option<anyhow::Error> {
+        self.recovered.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_providers::mock::MockChain;
+    use std::net::TcpListener;
+    use std::string::ToString;
+    use std::time::Instant;
+    use std::vec::Vec;
+
+    /// Renders a `newHeads` notification for a block.
+    fn notification(chain: &MockChain, number: u64) -> String {
+        let hash = chain.hash(number).unwrap();
+        notification_for(hash, number)
+    }
+
+    fn notification_for(hash: B256, number: u64) -> String {
+        std::format!(
+            r#"{{"jsonrpc":"2.0","method":"eth_subscription","params":{{"subscription":"0x1","result":{{"hash":"{:?}","number":"{:#x}"}}}}}}"#,
+            hash,
+            number
+        )
+    }
+
+    /// Spawns a WebSocket server that serves one batch of notifications
+    /// per connection, closing the connection after each batch.
+    fn spawn_server(batches: Vec<Vec<String>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = std::format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for batch in batches {
+                let Ok((stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut socket = tungstenite::accept(stream).unwrap();
+                let subscribe = socket.read().unwrap();
+                assert!(subscribe.to_string().contains("newHeads"));
+                socket
+                    .send(Message::Text(
+                        r#"{"jsonrpc":"2.0","id":1,"result":"0x1"}"#.into(),
+                    ))
+                    .unwrap();
+                for message in batch {
+                    socket.send(Message::Text(message)).unwrap();
+                }
+                let _ = socket.close(None);
+                let _ = socket.flush();
+            }
+        });
+        url
+    }
+
+    /// Ingests until `count` updates are received or a deadline passes.
+    fn ingest(ingestor: &mut WsIngestor, count: usize) -> Vec<BlockUpdate> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut updates = Vec::new();
+        while updates.len() < count && Instant::now() < deadline {
+            if let Some(update) = ingestor.try_ingest().unwrap() {
+                updates.push(update);
+            }
+        }
+        updates
+    }
+
+    fn numbers(updates: &[BlockUpdate]) -> Vec<Option<u64>> {
+        updates
+            .iter()
+            .map(|u| match u {
+                BlockUpdate::NewBlock(b) => b.number.map(|n| n.to::<u64>()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ws_new_heads() {
+        let chain = MockChain::builder().blocks(3).build();
+        let url = spawn_server(std::vec![std::vec![
+            notification(&chain, 1),
+            notification(&chain, 3),
+        ]]);
+        let mut ingestor = WsIngestor::new(url, Box::new(chain));
+        let updates = ingest(&mut ingestor, 3);
+        assert_eq!(numbers(&updates), std::vec![Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_ws_reorg() {
+        let chain = MockChain::builder().blocks(3).build();
+        let old = std::vec![notification(&chain, 2), notification(&chain, 3)];
+        chain.reorg(1);
+        let url = spawn_server(std::vec![[old, std::vec![notification(&chain, 3)]].concat()]);
+        let mut ingestor = WsIngestor::new(url, Box::new(chain.clone()));
+        let updates = ingest(&mut ingestor, 4);
+        assert_eq!(
+            updates[2],
+            BlockUpdate::Reorg {
+                common_ancestor: BlockInfo::try_from(chain.block(2).unwrap()).unwrap(),
+                depth: 1,
+            }
+        );
+        assert_eq!(ingestor.head().map(|h| h.hash), chain.hash(3));
+    }
+
+    #[test]
+    fn test_ws_confirmation_depth() {
+        let chain = MockChain::builder().blocks(6).build();
+        let url = spawn_server(std::vec![std::vec![
+            notification(&chain, 4),
+            notification(&chain, 6),
+        ]]);
+        let mut ingestor = WsIngestor::new(url, Box::new(chain)).with_confirmation_depth(2);
+        let updates = ingest(&mut ingestor, 3);
+        assert_eq!(numbers(&updates), std::vec![Some(2), Some(3), Some(4)]);
+    }
+
+    #[test]
+    fn test_confirmation_depth_matches_poll_queue() {
+        use crate::ingest::poll_queue::PollQueue;
+
+        let chain = MockChain::builder().blocks(6).build();
+        let mut poll_queue = PollQueue::from(Box::new(chain.clone()) as Box<dyn Provider>)
+            .with_confirmation_depth(2);
+        poll_queue.l1_start_block = 2;
+        let polled = core::iter::from_fn(|| poll_queue.try_ingest().unwrap())
+            .filter(|u| matches!(u, BlockUpdate::NewBlock(_)))
+            .collect::<Vec<_>>();
+
+        let url = spawn_server(std::vec![std::vec![notification(&chain, 6)]]);
+        let head = tracker::block_info(&chain.block(1).unwrap());
+        let mut ingestor = WsIngestor::new(url, Box::new(chain))
+            .with_head(head)
+            .with_confirmation_depth(2);
+        let updates = ingest(&mut ingestor, 3);
+        assert_eq!(numbers(&updates), std::vec![Some(2), Some(3), Some(4)]);
+        assert_eq!(updates, polled);
+    }
+
+    #[test]
+    fn test_ws_reconnect_backfills() {
+        let chain = MockChain::builder().blocks(4).build();
+        let url = spawn_server(std::vec![
+            std::vec![notification(&chain, 1)],
+            std::vec![notification(&chain, 4)],
+        ]);
+        let mut ingestor = WsIngestor::new(url, Box::new(chain));
+        let updates = ingest(&mut ingestor, 4);
+        assert_eq!(
+            numbers(&updates),
+            std::vec![Some(1), Some(2), Some(3), Some(4)]
+        );
+    }
+
+    #[test]
+    fn test_ws_skips_known_head() {
+        let chain = MockChain::builder().blocks(2).build();
+        let head = chain.block(2).unwrap();
+        let url = spawn_server(std::vec![std::vec![notification_for(
+            head.hash.unwrap(),
+            2
+        )]]);
+        let start = BlockInfo::try_from(chain.block(1).unwrap()).unwrap();
+        let mut ingestor = WsIngestor::new(url, Box::new(chain)).with_head(start);
+        let updates = ingest(&mut ingestor, 1);
+        assert_eq!(numbers(&updates), std::vec![Some(2)]);
+        for _ in 0..5 {
+            assert_eq!(ingestor.try_ingest().unwrap(), None);
+        }
+    }
+}
@@ -15,7 +15,9 @@ extern crate alloc;
 /// Re-export [axos_primitives] for convenience.
 pub use axos_primitives::*;
 
+#[cfg(feature = "alloc")]
 pub mod engine;
 pub mod info;
 pub mod ingest;
+#[cfg(feature = "alloc")]
 pub mod stages;
@@ -1,3 +1,5 @@
 //! Derivation Pipeline Stages
+//!
+//! The stages box their ingestors, so they need `alloc`.
 
 pub mod driver;
@@ -1,20 +1,21 @@
 //! Derivation Pipeline Driver
 
-#[cfg(feature = "alloc")]
 use alloc::boxed::Box;
 
 use crate::info::HeadInfoQuery;
+use crate::ingest::cursor::CursorStore;
 use crate::ingest::poll_queue::PollQueue;
 use crate::ingest::BlockIngestor;
-use axos_primitives::ChainConfig;
 use axos_primitives::GenericString;
-use axos_providers::provider::Provider;
+use axos_primitives::{ChainConfig, HeadInfo};
+#[cfg(feature = "std")]
+use axos_providers::http::HttpProvider;
+use axos_providers::provider::{Error as ProviderError, Provider};
 
 use tracing::instrument;
 
 /// Derivation Pipeline Driver
 pub struct Driver {
-    // todo: put this box behind the alloc feature
     /// The block ingestor.
     pub ingestor: Box<dyn BlockIngestor>,
 }
@@ -28,8 +29,12 @@ impl core::fmt::Debug for Driver {
 /// The driver configuration.
 #[derive(Debug, Default)]
 pub struct DriverConfig {
+    /// The l1 rpc url.
+    pub l1_rpc_url: GenericString,
     /// The l2 rpc url.
     pub l2_rpc_url: GenericString,
+    /// Ingest from an auto-advancing mock chain instead of the l1 rpc.
+    pub mock: bool,
     /// The chain config
     pub chain_config: ChainConfig,
 }
@@ -41,15 +46,82 @@ pub struct DriverConfig {
 //       `axt` binary should be updated to build it instead of
 //       building the DriverConfig directly, and then create
 //       the Driver from the top-level Config like so:
-//       `Driver::from(config)`.
+//       `Driver::try_from(config)`.
+
+#[cfg(feature = "std")]
+impl TryFrom<DriverConfig> for Driver {
+    type Error = ProviderError;
 
-impl From<DriverConfig> for Driver {
     #[instrument(skip(config))]
-    fn from(config: DriverConfig) -> Self {
+    fn try_from(config: DriverConfig) -> Result<Self, Self::Error> {
         tracing::info!("Building driver from config");
-        let provider = axos_providers::mock::MockProvider::new(config.l2_rpc_url);
-        tracing::debug!("Constructed provider");
-        let head = HeadInfoQuery::get_head_info(&provider, &config.chain_config);
+        if config.mock {
+            tracing::warn!(
+                "Ingesting from a mock chain instead of {}",
+                config.l1_rpc_url
+            );
+            let provider = axos_providers::mock::MockChain::builder()
+                .auto_advance(true)
+                .build();
+            return Self::with_provider(provider, &config);
+        }
+        let l1 = HttpProvider::new(config.l1_rpc_url.as_str());
+        let l2 = HttpProvider::new(config.l2_rpc_url.as_str());
+        Self::with_providers(l1, &l2, &config)
+    }
+}
+
+/// Calculates the L1 start block number.
+/// If an overflow occurs during subtraction, the function returns the genesis block #0.
+pub fn get_l1_start_block(epoch_number: u64, channel_timeout: u64) -> u64 {
+    epoch_number.saturating_sub(channel_timeout)
+}
+
+impl Driver {
+    /// Instantiates a new [Driver].
+    pub fn new(ingestor: Box<dyn BlockIngestor>) -> Self {
+        Self { ingestor }
+    }
+
+    /// Builds a [Driver] that ingests blocks from the given provider,
+    /// which also serves the l2 head info.
+    pub fn with_provider<P: Provider + 'static>(
+        provider: P,
+        config: &DriverConfig,
+    ) -> Result<Self, ProviderError> {
+        let head = HeadInfoQuery::get_head_info(&provider, &config.chain_config)?;
+        Self::build(provider, head, config, None)
+    }
+
+    /// Builds a [Driver] that ingests blocks from the `l1` provider,
+    /// starting from the l2 head info served by the `l2` provider.
+    pub fn with_providers<P: Provider + 'static, Q: Provider>(
+        l1: P,
+        l2: &Q,
+        config: &DriverConfig,
+    ) -> Result<Self, ProviderError> {
+        let head = HeadInfoQuery::get_head_info(l2, &config.chain_config)?;
+        Self::build(l1, head, config, None)
+    }
+
+    /// Builds a [Driver] that ingests blocks from the given provider,
+    /// resuming from the cursor in the store if it is still canonical.
+    pub fn with_cursor_store<P: Provider + 'static>(
+        provider: P,
+        config: &DriverConfig,
+        store: Box<dyn CursorStore>,
+    ) -> Result<Self, ProviderError> {
+        let head = HeadInfoQuery::get_head_info(&provider, &config.chain_config)?;
+        Self::build(provider, head, config, Some(store))
+    }
+
+    #[instrument(skip(provider, head, config, store))]
+    fn build<P: Provider + 'static>(
+        provider: P,
+        head: HeadInfo,
+        config: &DriverConfig,
+        store: Option<Box<dyn CursorStore>>,
+    ) -> Result<Self, ProviderError> {
         tracing::debug!("Fetched head info");
         let finalized_head = head.l2_block_info;
         let finalized_epoch = head.l1_epoch;
@@ -58,34 +130,95 @@ impl From<DriverConfig> for Driver {
         tracing::debug!("Finalized epoch: {:?}", finalized_epoch);
         tracing::debug!("Finalized sequence num: {:?}", finalized_seq);
 
-        #[cfg(feature = "alloc")]
         let mut poll_queue = PollQueue::from(Box::new(provider) as Box<dyn Provider>);
-        #[cfg(not(feature = "alloc"))]
-        let mut poll_queue = PollQueue::from(&mut provider as &mut dyn BlockIngestor);
 
         poll_queue.l1_start_block =
             get_l1_start_block(finalized_epoch.number, config.chain_config.channel_timeout);
         poll_queue.l2_start_block = finalized_head.number;
+        if let Some(store) = store {
+            poll_queue = poll_queue.with_cursor_store(store);
+            if let Some(cursor) = poll_queue.resume()? {
+                tracing::info!(
+                    "Resuming from L1 block {} and L2 block {}",
+                    cursor.l1_number,
+                    cursor.l2_number
+                );
+            }
+        }
 
         // TODO: engine driver
 
         // TODO:
 
-        Self {
+        Ok(Self {
             ingestor: Box::new(poll_queue),
-        }
+        })
     }
 }
 
-/// Calculates the L1 start block number.
-/// If an overflow occurs during subtraction, the function returns the genesis block #0.
-pub fn get_l1_start_block(epoch_number: u64, channel_timeout: u64) -> u64 {
-    epoch_number.saturating_sub(channel_timeout)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::cursor::Cursor;
+    use crate::ingest::BlockUpdate;
+    use alloc::rc::Rc;
+    use axos_providers::mock::MockChain;
+    use core::cell::Cell;
 
-impl Driver {
-    /// Instantiates a new [Driver].
-    pub fn new(ingestor: Box<dyn BlockIngestor>) -> Self {
-        Self { ingestor }
+    /// A cursor store that outlives the driver it is handed to.
+    #[derive(Debug, Clone, Default)]
+    struct SharedStore(Rc<Cell<Option<Cursor>>>);
+
+    impl CursorStore for SharedStore {
+        fn load(&self) -> anyhow::Result<Option<Cursor>> {
+            Ok(self.0.get())
+        }
+
+        fn store(&mut self, cursor: Cursor) -> anyhow::Result<()> {
+            self.0.set(Some(cursor));
+            Ok(())
+        }
+    }
+
+    /// Ingests updates until the next block, returning its number.
+    fn next_block(driver: &mut Driver) -> Option<u64> {
+        core::iter::from_fn(|| driver.ingestor.try_ingest().unwrap()).find_map(
+            |update| match update {
+                BlockUpdate::NewBlock(block) => block.number.map(|n| n.to()),
+                _ => None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_driver_restart() {
+        let chain = MockChain::builder().blocks(10).build();
+        // Ingest the mock chain from its genesis.
+        let config = DriverConfig {
+            chain_config: ChainConfig {
+                l1_start_epoch: Default::default(),
+                ..ChainConfig::optimism()
+            },
+            ..Default::default()
+        };
+        let store = SharedStore::default();
+        let mut driver =
+            Driver::with_cursor_store(chain.clone(), &config, Box::new(store.clone())).unwrap();
+        for number in 0..5 {
+            assert_eq!(next_block(&mut driver), Some(number));
+        }
+        driver.ingestor.set_l2_head(42);
+        let cursor = Cursor {
+            l1_number: 4,
+            l1_hash: chain.hash(4).unwrap(),
+            l2_number: 42,
+        };
+        assert_eq!(store.load().unwrap(), Some(cursor));
+
+        let mut driver =
+            Driver::with_cursor_store(chain.clone(), &config, Box::new(store.clone())).unwrap();
+        assert_eq!(next_block(&mut driver), Some(5));
+        let cursor = store.load().unwrap().unwrap();
+        assert_eq!((cursor.l1_number, cursor.l2_number), (5, 42));
     }
 }
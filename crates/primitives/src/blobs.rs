@@ -0,0 +1,91 @@
+//! Blob Types
+//!
+//! Types for blob sidecars served by the consensus layer beacon api.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use alloy_primitives::{Bytes, FixedBytes, B256};
+
+/// The version byte prefixed to a hashed kzg commitment to form a versioned hash.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The number of bytes in a blob.
+pub const BYTES_PER_BLOB: usize = 131_072;
+
+/// A blob hash paired with the index of the blob in its block.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct IndexedBlobHash {
+    /// The index of the blob in the block.
+    pub index: u64,
+    /// The versioned hash of the blob.
+    pub hash: B256,
+}
+
+impl IndexedBlobHash {
+    /// Instantiates a new [IndexedBlobHash].
+    pub fn new(index: u64, hash: B256) -> Self {
+        Self { index, hash }
+    }
+}
+
+/// A Blob Sidecar
+///
+/// Only the fields needed to verify a blob are kept. The signed block
+/// header and commitment inclusion proof returned by the beacon api
+/// are ignored.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct BlobSidecar {
+    /// The index of the blob in the block.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "ser_u64_str", deserialize_with = "de_u64_str")
+    )]
+    pub index: u64,
+    /// The blob data.
+    pub blob: Bytes,
+    /// The kzg commitment to the blob.
+    pub kzg_commitment: FixedBytes<48>,
+    /// The kzg proof for the blob.
+    pub kzg_proof: FixedBytes<48>,
+}
+
+/// Serializes a [u64] as a decimal string, as the beacon api expects.
+#[cfg(feature = "serde")]
+pub fn ser_u64_str<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// Deserializes a [u64] from a decimal string, as the beacon api returns.
+#[cfg(feature = "serde")]
+pub fn de_u64_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    use serde::de::Error;
+    #[cfg(feature = "alloc")]
+    let value: alloc::string::String = Deserialize::deserialize(deserializer)?;
+    #[cfg(not(feature = "alloc"))]
+    let value: &str = Deserialize::deserialize(deserializer)?;
+    value.parse::<u64>().map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn test_blob_sidecar_deserialize() {
+        let json = r#"{
+            "index": "1",
+            "blob": "0x0102",
+            "kzg_commitment": "0x8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7",
+            "kzg_proof": "0xb0f6bbfab0f6bbfab0f6bbfab0f6bbfab0f6bbfab0f6bbfab0f6bbfab0f6bbfab0f6bbfab0f6bbfab0f6bbfab0f6bbfa",
+            "signed_block_header": {}
+        }"#;
+        let sidecar: BlobSidecar = serde_json::from_str(json).unwrap();
+        assert_eq!(sidecar.index, 1);
+        assert_eq!(sidecar.blob, Bytes::from_static(&[1, 2]));
+        assert_eq!(sidecar.kzg_commitment[0], 0x8f);
+    }
+}
@@ -4,9 +4,10 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
-use alloy_primitives::{Address, Bloom, Bytes, B256, U256, U64};
+use alloy_primitives::{Address, Bloom, Bytes, FixedBytes, B256, U256, U64};
 pub use alloy_primitives::{BlockHash, BlockNumber};
 
+use crate::receipts::Log;
 use crate::transactions::Transaction;
 
 /// Block Header Info
@@ -65,6 +66,7 @@ pub enum BlockId {
 /// The block kinds are:
 /// - `Earliest`: The earliest known block.
 /// - `Latest`: The latest pending block.
+/// - `Safe`: The latest safe block.
 /// - `Finalized`: The latest finalized block.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -73,12 +75,30 @@ pub enum BlockKind {
     Earliest,
     /// The latest pending block.
     Latest,
+    /// The latest safe block.
+    Safe,
     /// The latest finalized block.
     Finalized,
 }
 
+impl BlockKind {
+    /// Returns the json-rpc block tag for the block kind.
+    pub fn as_tag(&self) -> &'static str {
+        match self {
+            BlockKind::Earliest => "earliest",
+            BlockKind::Latest => "latest",
+            BlockKind::Safe => "safe",
+            BlockKind::Finalized => "finalized",
+        }
+    }
+}
+
 /// A Block with Transactions
+///
+/// Serialized with the field names used by the `eth_getBlockByNumber`
+/// and `eth_getBlockByHash` json-rpc methods.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct BlockWithTransactions {
     /// The block hash
@@ -86,6 +106,7 @@ pub struct BlockWithTransactions {
     /// The parent block hash
     pub parent_hash: B256,
     /// The block author
+    #[cfg_attr(feature = "serde", serde(default, rename = "miner"))]
     pub author: Option<Address>,
     /// The block state root hash
     pub state_root: B256,
@@ -100,13 +121,21 @@ pub struct BlockWithTransactions {
     /// Block extra data
     pub extra_data: Bytes,
     /// The block logs bloom filter
+    #[cfg_attr(feature = "serde", serde(default))]
     pub logs_bloom: Option<Bloom>,
     /// The block timestamp
     pub timestamp: U256,
     /// The block total difficulty
+    #[cfg_attr(feature = "serde", serde(default))]
     pub total_difficulty: Option<U256>,
     /// The block seal fields
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg(feature = "alloc")]
     pub seal_fields: Vec<Bytes>,
+    /// The block seal fields
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg(not(feature = "alloc"))]
+    pub seal_fields: &'static [Bytes],
     /// The block transactions
     #[cfg(feature = "alloc")]
     pub transactions: Vec<Transaction>,
@@ -114,7 +143,90 @@ pub struct BlockWithTransactions {
     #[cfg(not(feature = "alloc"))]
     pub transactions: &'static [Transaction],
     /// The block size
+    #[cfg_attr(feature = "serde", serde(default))]
     pub size: Option<U256>,
     /// The block base fee per gas
+    #[cfg_attr(feature = "serde", serde(default))]
     pub base_fee_per_gas: Option<U256>,
+    /// The hash of the block ommers
+    #[cfg_attr(feature = "serde", serde(default, rename = "sha3Uncles"))]
+    pub uncles_hash: Option<B256>,
+    /// The block difficulty
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub difficulty: Option<U256>,
+    /// The block gas limit
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_limit: Option<U256>,
+    /// The block mix hash, or the beacon chain randomness after the merge
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mix_hash: Option<B256>,
+    /// The block proof of work nonce
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nonce: Option<FixedBytes<8>>,
+    /// The block withdrawals root hash
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub withdrawals_root: Option<B256>,
+    /// The amount of blob gas used in the block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blob_gas_used: Option<U64>,
+    /// The excess blob gas of the block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub excess_blob_gas: Option<U64>,
+    /// The parent beacon block root
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub parent_beacon_block_root: Option<B256>,
+    /// The hash of the execution layer requests
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub requests_hash: Option<B256>,
+}
+
+/// L1 Block Data
+///
+/// The parts of an L1 block that derivation reads: the header fields,
+/// the batcher transactions sent to the batch inbox, and the logs of the
+/// deposit and system config contracts.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct L1BlockData {
+    /// The block header info
+    pub info: BlockInfo,
+    /// The block base fee per gas
+    pub base_fee_per_gas: Option<U256>,
+    /// The block mix hash, or the beacon chain randomness after the merge
+    pub mix_hash: Option<B256>,
+    /// The excess blob gas of the block
+    pub excess_blob_gas: Option<U64>,
+    /// The batcher transactions
+    #[cfg(feature = "alloc")]
+    pub batcher_transactions: Vec<Transaction>,
+    /// The batcher transactions
+    #[cfg(not(feature = "alloc"))]
+    pub batcher_transactions: &'static [Transaction],
+    /// The deposit and system config contract logs
+    #[cfg(feature = "alloc")]
+    pub logs: Vec<Log>,
+    /// The deposit and system config contract logs
+    #[cfg(not(feature = "alloc"))]
+    pub logs: &'static [Log],
+}
+
+#[cfg(feature = "alloc")]
+impl L1BlockData {
+    /// Instantiates a new [L1BlockData] from a block whose transactions
+    /// are already filtered down to the batcher transactions.
+    pub fn new(block: BlockWithTransactions, logs: Vec<Log>) -> Self {
+        Self {
+            info: BlockInfo {
+                number: block.number.unwrap_or_default().to::<u64>(),
+                hash: block.hash.unwrap_or_default(),
+                parent_hash: block.parent_hash,
+                timestamp: block.timestamp.to::<u64>(),
+            },
+            base_fee_per_gas: block.base_fee_per_gas,
+            mix_hash: block.mix_hash,
+            excess_blob_gas: block.excess_blob_gas,
+            batcher_transactions: block.transactions,
+            logs,
+        }
+    }
 }
@@ -0,0 +1,130 @@
+//! Log Filter
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use alloc::vec::Vec;
+
+use alloy_primitives::{Address, B256};
+
+use crate::blocks::BlockNumber;
+use crate::receipts::Log;
+
+/// A Log Filter
+///
+/// Matches logs by block range or block hash, emitting address and
+/// topics. An empty address list or a `None` topic matches anything.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Filter {
+    /// The first block of the range, inclusive
+    pub from_block: Option<BlockNumber>,
+    /// The last block of the range, inclusive
+    pub to_block: Option<BlockNumber>,
+    /// A single block hash, which takes precedence over the block range
+    pub block_hash: Option<B256>,
+    /// The emitting contract addresses
+    pub address: Vec<Address>,
+    /// The topics to match at each position
+    pub topics: Vec<Option<Vec<B256>>>,
+}
+
+impl Filter {
+    /// Instantiates an empty [Filter].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the first block of the range.
+    pub fn from_block(mut self, number: BlockNumber) -> Self {
+        self.from_block = Some(number);
+        self
+    }
+
+    /// Sets the last block of the range.
+    pub fn to_block(mut self, number: BlockNumber) -> Self {
+        self.to_block = Some(number);
+        self
+    }
+
+    /// Restricts the filter to a single block hash.
+    pub fn at_block_hash(mut self, hash: B256) -> Self {
+        self.block_hash = Some(hash);
+        self
+    }
+
+    /// Adds an emitting contract address.
+    pub fn address(mut self, address: Address) -> Self {
+        self.address.push(address);
+        self
+    }
+
+    /// Matches the given event signature as the first topic.
+    pub fn event(self, signature: B256) -> Self {
+        self.topic(0, signature)
+    }
+
+    /// Adds a topic to match at the given position.
+    pub fn topic(mut self, position: usize, topic: B256) -> Self {
+        if self.topics.len() <= position {
+            self.topics.resize(position + 1, None);
+        }
+        self.topics[position]
+            .get_or_insert_with(Vec::new)
+            .push(topic);
+        self
+    }
+
+    /// Returns true if the log matches the filter's address and topics.
+    /// The block range is not checked.
+    pub fn matches(&self, log: &Log) -> bool {
+        if !self.address.is_empty() && !self.address.contains(&log.address) {
+            return false;
+        }
+        self.topics
+            .iter()
+            .enumerate()
+            .all(|(i, topics)| match topics {
+                Some(topics) if !topics.is_empty() => {
+                    log.topics.get(i).is_some_and(|t| topics.contains(t))
+                }
+                _ => true,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn log(address: Address, topics: Vec<B256>) -> Log {
+        Log {
+            address,
+            topics,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let address = Address::with_last_byte(1);
+        let filter = Filter::new()
+            .address(address)
+            .event(B256::with_last_byte(2));
+        assert!(filter.matches(&log(address, vec![B256::with_last_byte(2)])));
+        assert!(!filter.matches(&log(address, vec![B256::with_last_byte(3)])));
+        assert!(!filter.matches(&log(Address::ZERO, vec![B256::with_last_byte(2)])));
+        assert!(!filter.matches(&log(address, vec![])));
+    }
+
+    #[test]
+    fn test_filter_wildcard_topic() {
+        let filter = Filter::new().topic(1, B256::with_last_byte(4));
+        assert_eq!(filter.topics.len(), 2);
+        assert!(filter.matches(&log(
+            Address::ZERO,
+            vec![B256::with_last_byte(9), B256::with_last_byte(4)]
+        )));
+    }
+}
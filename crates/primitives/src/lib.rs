@@ -13,7 +13,7 @@
 extern crate alloc;
 
 /// Re-export used [alloy_primitives] types for convenience.
-pub use alloy_primitives::{address, b256, FixedBytes, B256, U256, U64};
+pub use alloy_primitives::{address, b256, keccak256, Address, Bytes, FixedBytes, B256, U256, U64};
 
 // Testing utils
 #[cfg(any(test, feature = "test-utils"))]
@@ -26,12 +26,17 @@ pub mod payload;
 pub mod transactions;
 
 mod attributes;
+mod blobs;
 mod blocks;
 mod chain;
 mod epoch;
+#[cfg(feature = "alloc")]
+mod filter;
 mod head;
 mod l1_block;
 mod peers;
+mod proof;
+mod receipts;
 mod str;
 mod sync;
 mod system;
@@ -39,11 +44,16 @@ mod system;
 #[doc(inline)]
 pub use attributes::*;
 #[doc(inline)]
+pub use blobs::*;
+#[doc(inline)]
 pub use blocks::*;
 #[doc(inline)]
 pub use chain::*;
 #[doc(inline)]
 pub use epoch::*;
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use filter::*;
 #[doc(inline)]
 pub use head::*;
 #[doc(inline)]
@@ -51,6 +61,10 @@ pub use l1_block::*;
 #[doc(inline)]
 pub use peers::*;
 #[doc(inline)]
+pub use proof::*;
+#[doc(inline)]
+pub use receipts::*;
+#[doc(inline)]
 pub use str::*;
 #[doc(inline)]
 pub use sync::*;
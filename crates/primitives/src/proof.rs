@@ -0,0 +1,66 @@
+//! Account and Storage Proofs
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use alloy_primitives::{Address, Bytes, B256, U256, U64};
+
+/// A Merkle proof for an account and a set of its storage slots,
+/// as returned by `eth_getProof`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AccountProof {
+    /// The account address
+    pub address: Address,
+    /// The account balance
+    pub balance: U256,
+    /// The account code hash
+    pub code_hash: B256,
+    /// The account nonce
+    pub nonce: U64,
+    /// The account storage root hash
+    pub storage_hash: B256,
+    /// The account trie nodes from the state root to the account
+    #[cfg(feature = "alloc")]
+    pub account_proof: Vec<Bytes>,
+    /// The account trie nodes from the state root to the account
+    #[cfg(not(feature = "alloc"))]
+    pub account_proof: &'static [Bytes],
+    /// The storage proofs for the requested slots
+    #[cfg(feature = "alloc")]
+    pub storage_proof: Vec<StorageProof>,
+    /// The storage proofs for the requested slots
+    #[cfg(not(feature = "alloc"))]
+    pub storage_proof: &'static [StorageProof],
+}
+
+impl AccountProof {
+    /// Returns the proven value of the given storage slot, if it was requested.
+    pub fn storage_value(&self, slot: B256) -> Option<U256> {
+        let key = U256::from_be_bytes(slot.0);
+        self.storage_proof
+            .iter()
+            .find(|p| p.key == key)
+            .map(|p| p.value)
+    }
+}
+
+/// A Merkle proof for a single storage slot.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StorageProof {
+    /// The storage slot
+    pub key: U256,
+    /// The storage value
+    pub value: U256,
+    /// The storage trie nodes from the storage root to the slot
+    #[cfg(feature = "alloc")]
+    pub proof: Vec<Bytes>,
+    /// The storage trie nodes from the storage root to the slot
+    #[cfg(not(feature = "alloc"))]
+    pub proof: &'static [Bytes],
+}
@@ -0,0 +1,150 @@
+//! Receipt and Log Types
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use alloy_primitives::{Address, Bloom, Bytes, B256, U256, U64};
+
+/// A Log emitted by a transaction
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Log {
+    /// The address of the contract that emitted the log
+    pub address: Address,
+    /// The log topics
+    #[cfg(feature = "alloc")]
+    pub topics: Vec<B256>,
+    /// The log topics
+    #[cfg(not(feature = "alloc"))]
+    pub topics: &'static [B256],
+    /// The log data
+    pub data: Bytes,
+    /// The hash of the block containing the log
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_hash: Option<B256>,
+    /// The number of the block containing the log
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_number: Option<U64>,
+    /// The hash of the transaction that emitted the log
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub transaction_hash: Option<B256>,
+    /// The index of the transaction in the block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub transaction_index: Option<U64>,
+    /// The index of the log in the block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub log_index: Option<U256>,
+    /// True if the log was removed by a chain reorganization
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub removed: bool,
+}
+
+/// A Transaction Receipt
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TransactionReceipt {
+    /// The transaction hash
+    pub transaction_hash: B256,
+    /// The index of the transaction in the block
+    pub transaction_index: U64,
+    /// The hash of the block containing the transaction
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_hash: Option<B256>,
+    /// The number of the block containing the transaction
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_number: Option<U64>,
+    /// The transaction sender
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub from: Address,
+    /// The transaction recipient
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub to: Option<Address>,
+    /// The cumulative gas used in the block after this transaction
+    pub cumulative_gas_used: U256,
+    /// The gas used by this transaction
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_used: Option<U256>,
+    /// The address of the contract created by the transaction
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub contract_address: Option<Address>,
+    /// The logs emitted by the transaction
+    #[cfg(feature = "alloc")]
+    pub logs: Vec<Log>,
+    /// The logs emitted by the transaction
+    #[cfg(not(feature = "alloc"))]
+    pub logs: &'static [Log],
+    /// The transaction status, `1` for success and `0` for failure
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status: Option<U64>,
+    /// The receipt logs bloom filter
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub logs_bloom: Bloom,
+    /// The transaction type
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "type", default, skip_serializing_if = "Option::is_none")
+    )]
+    pub transaction_type: Option<U64>,
+    /// The effective gas price paid by the transaction
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub effective_gas_price: Option<U256>,
+    /// The nonce of a deposit transaction
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub deposit_nonce: Option<U64>,
+    /// The receipt version of a deposit transaction
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub deposit_receipt_version: Option<U64>,
+}
+
+impl TransactionReceipt {
+    /// Returns true if the transaction succeeded.
+    pub fn is_success(&self) -> bool {
+        self.status.map(|s| s == U64::from(1)).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn test_receipt_deserialize() {
+        let json = r#"{
+            "transactionHash": "0x00000000000000000000000000000000000000000000000000000000000000aa",
+            "transactionIndex": "0x0",
+            "blockHash": "0x0000000000000000000000000000000000000000000000000000000000000002",
+            "blockNumber": "0x2",
+            "from": "0xdeaddeaddeaddeaddeaddeaddeaddeaddead0001",
+            "to": "0x4200000000000000000000000000000000000015",
+            "cumulativeGasUsed": "0xb4e3",
+            "gasUsed": "0xb4e3",
+            "contractAddress": null,
+            "logs": [{
+                "address": "0x4200000000000000000000000000000000000015",
+                "topics": ["0x00000000000000000000000000000000000000000000000000000000000000cc"],
+                "data": "0x01",
+                "logIndex": "0x0",
+                "removed": false
+            }],
+            "status": "0x1",
+            "type": "0x7e"
+        }"#;
+        let receipt: TransactionReceipt = serde_json::from_str(json).unwrap();
+        assert!(receipt.is_success());
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(receipt.logs[0].topics[0], B256::with_last_byte(0xcc));
+        assert_eq!(receipt.transaction_type, Some(U64::from(0x7e)));
+    }
+}
@@ -40,23 +40,29 @@ pub struct Transaction {
     /// The transaction value
     pub value: U256,
     /// The gas price
-    #[cfg_attr(feature = "serde", serde(rename = "gasPrice"))]
+    #[cfg_attr(feature = "serde", serde(default, rename = "gasPrice"))]
     pub gas_price: Option<U256>,
     /// The amount of gas used
     pub gas: U256,
     /// The input data
     pub input: Bytes,
     /// `v` value of the transaction signature
+    #[cfg_attr(feature = "serde", serde(default))]
     pub v: U64,
     /// `r` value of the transaction signature
+    #[cfg_attr(feature = "serde", serde(default))]
     pub r: U256,
     /// `s` value of the transaction signature
+    #[cfg_attr(feature = "serde", serde(default))]
     pub s: U256,
     /// The source hash
+    #[cfg_attr(feature = "serde", serde(default, rename = "sourceHash"))]
     pub source_hash: B256,
     /// If the transaction mints
+    #[cfg_attr(feature = "serde", serde(default))]
     pub mint: Option<U256>,
     /// If the transaction is a system transaction
+    #[cfg_attr(feature = "serde", serde(default, rename = "isSystemTx"))]
     pub is_system_tx: bool,
     /// The transaction type
     #[cfg_attr(
@@ -68,7 +74,7 @@ pub struct Transaction {
     #[cfg_attr(
         feature = "serde",
         serde(
-            rename = "accessList",
+            rename = "maxPriorityFeePerGas",
             default,
             skip_serializing_if = "Option::is_none"
         )
@@ -90,6 +96,116 @@ pub struct Transaction {
         serde(rename = "chainId", default, skip_serializing_if = "Option::is_none")
     )]
     pub chain_id: Option<U256>,
+    /// The access list
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "accessList",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    #[cfg(feature = "alloc")]
+    pub access_list: Option<Vec<AccessListItem>>,
+    /// The access list
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "accessList",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    #[cfg(not(feature = "alloc"))]
+    pub access_list: Option<&'static [AccessListItem]>,
+    /// The max fee per blob gas
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "maxFeePerBlobGas",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// The versioned hashes of the blobs
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "blobVersionedHashes",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    #[cfg(feature = "alloc")]
+    pub blob_versioned_hashes: Option<Vec<B256>>,
+    /// The versioned hashes of the blobs
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "blobVersionedHashes",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    #[cfg(not(feature = "alloc"))]
+    pub blob_versioned_hashes: Option<&'static [B256]>,
+    /// The signed code delegations of a set code transaction
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "authorizationList",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    #[cfg(feature = "alloc")]
+    pub authorization_list: Option<Vec<Authorization>>,
+    /// The signed code delegations of a set code transaction
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "authorizationList",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    #[cfg(not(feature = "alloc"))]
+    pub authorization_list: Option<&'static [Authorization]>,
+}
+
+/// An access list entry
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AccessListItem {
+    /// The accessed address
+    pub address: Address,
+    /// The accessed storage slots
+    #[cfg(feature = "alloc")]
+    pub storage_keys: Vec<B256>,
+    /// The accessed storage slots
+    #[cfg(not(feature = "alloc"))]
+    pub storage_keys: &'static [B256],
+}
+
+/// A signed code delegation
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Authorization {
+    /// The chain id the delegation is valid on
+    pub chain_id: U256,
+    /// The address delegated to
+    pub address: Address,
+    /// The nonce of the authority
+    pub nonce: U64,
+    /// The parity of the signature `y` value
+    pub y_parity: U64,
+    /// `r` value of the signature
+    pub r: U256,
+    /// `s` value of the signature
+    pub s: U256,
 }
 
 /// A raw transaction
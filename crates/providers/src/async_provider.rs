@@ -0,0 +1,482 @@
+//! Async Provider Trait
+//!
+//! The [AsyncProvider] is the asynchronous counterpart of [Provider]. It
+//! does not depend on any executor: methods return boxed [Send] futures
+//! that can be awaited on tokio, an embedded executor, or driven with
+//! [block_on].
+//!
+//! Two adapters bridge the traits:
+//!
+//! - [BlockingProvider] runs an [AsyncProvider] as a [Provider] by blocking
+//!   on each call.
+//! - [AsyncAdapter] exposes a [Provider] as an [AsyncProvider]. Each sync
+//!   call runs through a [BlockingRunner] when the method is called, and the
+//!   returned future is immediately ready, so the wrapped provider does not
+//!   need to be [Sync]. Providers that block on I/O need a runner that tells
+//!   the executor, such as one over tokio's `block_in_place`.
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::future::{ready, Future};
+use core::pin::Pin;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    AccountProof, Address, BlockId, BlockWithTransactions, Filter, Log, TransactionReceipt, B256,
+};
+
+use crate::executor::block_on;
+use crate::provider::{Error, Provider};
+
+/// A boxed, [Send] future.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A boxed future resolving to a provider result.
+pub type ProviderFuture<'a, T> = BoxFuture<'a, Result<T, Error>>;
+
+/// Returns a future that resolves to [Error::Unsupported].
+fn unsupported<'a, T: Send + 'a>() -> ProviderFuture<'a, T> {
+    Box::pin(ready(Err(Error::Unsupported)))
+}
+
+/// Async Provider Trait
+///
+/// Only [AsyncProvider::get_block_with_txs] is required. Like the
+/// [Provider] trait, methods without an implementation resolve to
+/// [Error::Unsupported], except where they can be built from other methods.
+pub trait AsyncProvider {
+    /// Fetch a block with transactions.
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, Option<BlockWithTransactions>>;
+
+    /// Fetch all transaction receipts for a block.
+    fn get_receipts(
+        &self,
+        _block_id: BlockId,
+    ) -> ProviderFuture<'_, Option<Vec<TransactionReceipt>>> {
+        unsupported()
+    }
+
+    /// Fetch the logs matching a filter.
+    ///
+    /// By default, the logs are collected from the receipts of each block
+    /// the filter covers. A filter without a block hash must set both ends
+    /// of its block range.
+    fn get_logs<'a>(&'a self, filter: &'a Filter) -> ProviderFuture<'a, Vec<Log>> {
+        let blocks: Vec<_> = match (filter.block_hash, filter.from_block, filter.to_block) {
+            (Some(hash), _, _) => vec![self.get_receipts(BlockId::Hash(hash))],
+            (None, Some(from), Some(to)) => (from..=to)
+                .map(|number| self.get_receipts(BlockId::Number(number)))
+                .collect(),
+            _ => return unsupported(),
+        };
+        Box::pin(async move {
+            let mut logs = Vec::new();
+            for receipts in blocks {
+                let receipts = receipts.await?.unwrap_or_default();
+                logs.extend(
+                    receipts
+                        .into_iter()
+                        .flat_map(|r| r.logs)
+                        .filter(|l| filter.matches(l)),
+                );
+            }
+            Ok(logs)
+        })
+    }
+
+    /// Fetch a transaction by hash.
+    fn get_transaction(&self, _hash: B256) -> ProviderFuture<'_, Option<Transaction>> {
+        unsupported()
+    }
+
+    /// Fetch the Merkle proof for an account and the given storage slots.
+    ///
+    /// The returned future must not borrow the slots.
+    fn get_proof(
+        &self,
+        _address: Address,
+        _slots: &[B256],
+        _block_id: BlockId,
+    ) -> ProviderFuture<'_, AccountProof> {
+        unsupported()
+    }
+
+    /// Fetch the value of a storage slot.
+    ///
+    /// By default, the value is read from a storage proof for the slot.
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, B256> {
+        let proof = self.get_proof(address, &[slot], block_id);
+        Box::pin(async move {
+            let value = proof.await?.storage_value(slot).unwrap_or_default();
+            Ok(B256::from(value.to_be_bytes::<32>()))
+        })
+    }
+
+    /// Fetch the chain id.
+    fn chain_id(&self) -> ProviderFuture<'_, u64> {
+        unsupported()
+    }
+}
+
+impl<P: AsyncProvider + ?Sized> AsyncProvider for Box<P> {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, Option<BlockWithTransactions>> {
+        (**self).get_block_with_txs(block_id)
+    }
+
+    fn get_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, Option<Vec<TransactionReceipt>>> {
+        (**self).get_receipts(block_id)
+    }
+
+    fn get_logs<'a>(&'a self, filter: &'a Filter) -> ProviderFuture<'a, Vec<Log>> {
+        (**self).get_logs(filter)
+    }
+
+    fn get_transaction(&self, hash: B256) -> ProviderFuture<'_, Option<Transaction>> {
+        (**self).get_transaction(hash)
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, AccountProof> {
+        (**self).get_proof(address, slots, block_id)
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, B256> {
+        (**self).get_storage_at(address, slot, block_id)
+    }
+
+    fn chain_id(&self) -> ProviderFuture<'_, u64> {
+        (**self).chain_id()
+    }
+}
+
+/// Runs an [AsyncProvider] as a blocking [Provider].
+///
+/// Each call is driven to completion with [block_on], so the wrapped
+/// provider's futures must make progress when polled without a reactor.
+#[derive(Debug, Clone, Default)]
+pub struct BlockingProvider<P>(pub P);
+
+impl<P> BlockingProvider<P> {
+    /// Instantiates a new [BlockingProvider].
+    pub fn new(provider: P) -> Self {
+        Self(provider)
+    }
+
+    /// Returns the wrapped provider.
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P: AsyncProvider> Provider for BlockingProvider<P> {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        block_on(self.0.get_block_with_txs(block_id))
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        block_on(self.0.get_receipts(block_id))
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        block_on(self.0.get_logs(filter))
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        block_on(self.0.get_transaction(hash))
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        block_on(self.0.get_proof(address, slots, block_id))
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        block_on(self.0.get_storage_at(address, slot, block_id))
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        block_on(self.0.chain_id())
+    }
+}
+
+/// Runs the sync calls of an [AsyncAdapter].
+///
+/// A runner for an executor moves the call off the executor's task
+/// threads, or tells the executor the call blocks. With tokio's
+/// multi-threaded runtime, for example:
+///
+/// ```ignore
+/// struct BlockInPlace;
+///
+/// impl BlockingRunner for BlockInPlace {
+///     fn run<T>(&self, call: impl FnOnce() -> T) -> T {
+///         tokio::task::block_in_place(call)
+///     }
+/// }
+/// ```
+pub trait BlockingRunner {
+    /// Runs a call that may block.
+    fn run<T>(&self, call: impl FnOnce() -> T) -> T;
+}
+
+/// A [BlockingRunner] that runs calls on the calling thread.
+///
+/// Only suits calls that never block, such as those of in-memory or
+/// cached providers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Inline;
+
+impl BlockingRunner for Inline {
+    fn run<T>(&self, call: impl FnOnce() -> T) -> T {
+        call()
+    }
+}
+
+/// Exposes a synchronous value through async methods.
+///
+/// Wrapping a [Provider] yields an [AsyncProvider]. The sync call runs
+/// through the [BlockingRunner] when the async method is called, and the
+/// returned future is ready. Network-backed providers need a runner for
+/// their executor, or should implement [AsyncProvider] directly.
+#[derive(Debug, Clone, Default)]
+pub struct AsyncAdapter<P, R = Inline>(pub P, pub R);
+
+impl<P> AsyncAdapter<P> {
+    /// Instantiates a new [AsyncAdapter] that runs calls on the calling
+    /// thread. Only use this if the wrapped calls never block.
+    pub fn inline(inner: P) -> Self {
+        Self(inner, Inline)
+    }
+}
+
+impl<P, R: BlockingRunner> AsyncAdapter<P, R> {
+    /// Instantiates a new [AsyncAdapter] that runs calls with the given
+    /// [BlockingRunner].
+    pub fn new(inner: P, runner: R) -> Self {
+        Self(inner, runner)
+    }
+
+    /// Runs a sync call through the [BlockingRunner] and returns its
+    /// result as a ready future.
+    pub fn call<'a, T: Send + 'a>(&self, call: impl FnOnce() -> T) -> BoxFuture<'a, T> {
+        Box::pin(ready(self.1.run(call)))
+    }
+
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P: Provider, R: BlockingRunner> AsyncProvider for AsyncAdapter<P, R> {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, Option<BlockWithTransactions>> {
+        self.call(|| self.0.get_block_with_txs(block_id))
+    }
+
+    fn get_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, Option<Vec<TransactionReceipt>>> {
+        self.call(|| self.0.get_receipts(block_id))
+    }
+
+    fn get_logs<'a>(&'a self, filter: &'a Filter) -> ProviderFuture<'a, Vec<Log>> {
+        self.call(|| self.0.get_logs(filter))
+    }
+
+    fn get_transaction(&self, hash: B256) -> ProviderFuture<'_, Option<Transaction>> {
+        self.call(|| self.0.get_transaction(hash))
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, AccountProof> {
+        self.call(|| self.0.get_proof(address, slots, block_id))
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> ProviderFuture<'_, B256> {
+        self.call(|| self.0.get_storage_at(address, slot, block_id))
+    }
+
+    fn chain_id(&self) -> ProviderFuture<'_, u64> {
+        self.call(|| self.0.chain_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_primitives::{StorageProof, U256, U64};
+    use core::cell::Cell;
+
+    /// A sync provider serving a single block.
+    struct SingleBlock;
+
+    impl Provider for SingleBlock {
+        fn get_block_with_txs(&self, id: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+            Ok(match id {
+                BlockId::Number(1) => Some(BlockWithTransactions {
+                    number: Some(U64::from(1)),
+                    ..Default::default()
+                }),
+                _ => None,
+            })
+        }
+
+        fn chain_id(&self) -> Result<u64, Error> {
+            Ok(10)
+        }
+    }
+
+    /// An async provider whose futures yield once before resolving.
+    struct Yielding;
+
+    /// A future that is pending on its first poll.
+    struct YieldOnce<T>(Option<T>, bool);
+
+    impl<T: Unpin> Future for YieldOnce<T> {
+        type Output = T;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> core::task::Poll<T> {
+            if !self.1 {
+                self.1 = true;
+                cx.waker().wake_by_ref();
+                return core::task::Poll::Pending;
+            }
+            core::task::Poll::Ready(self.0.take().expect("polled after completion"))
+        }
+    }
+
+    impl AsyncProvider for Yielding {
+        fn get_block_with_txs(
+            &self,
+            _: BlockId,
+        ) -> ProviderFuture<'_, Option<BlockWithTransactions>> {
+            Box::pin(YieldOnce(Some(Ok(None)), false))
+        }
+
+        fn chain_id(&self) -> ProviderFuture<'_, u64> {
+            Box::pin(YieldOnce(Some(Ok(5)), false))
+        }
+
+        fn get_proof(
+            &self,
+            address: Address,
+            slots: &[B256],
+            _: BlockId,
+        ) -> ProviderFuture<'_, AccountProof> {
+            let proof = AccountProof {
+                address,
+                storage_proof: slots
+                    .iter()
+                    .map(|slot| StorageProof {
+                        key: U256::from_be_bytes(slot.0),
+                        value: U256::from(7),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            Box::pin(YieldOnce(Some(Ok(proof)), false))
+        }
+    }
+
+    /// A runner counting the calls it runs.
+    #[derive(Default)]
+    struct Counting(Cell<u32>);
+
+    impl BlockingRunner for &Counting {
+        fn run<T>(&self, call: impl FnOnce() -> T) -> T {
+            self.0.set(self.0.get() + 1);
+            call()
+        }
+    }
+
+    #[test]
+    fn test_async_adapter() {
+        let runner = Counting::default();
+        let provider = AsyncAdapter::new(SingleBlock, &runner);
+        let block = block_on(provider.get_block_with_txs(BlockId::Number(1))).unwrap();
+        assert_eq!(block.unwrap().number, Some(U64::from(1)));
+        assert_eq!(block_on(provider.chain_id()), Ok(10));
+        assert_eq!(
+            block_on(provider.get_transaction(B256::ZERO)),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(runner.0.get(), 3);
+    }
+
+    #[test]
+    fn test_blocking_provider() {
+        let provider = BlockingProvider::new(Yielding);
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(1)), Ok(None));
+        assert_eq!(provider.chain_id(), Ok(5));
+        assert_eq!(
+            provider.get_receipts(BlockId::Number(1)),
+            Err(Error::Unsupported)
+        );
+        let slot = B256::with_last_byte(1);
+        assert_eq!(
+            provider.get_storage_at(Address::ZERO, slot, BlockId::Number(1)),
+            Ok(B256::with_last_byte(7))
+        );
+    }
+
+    #[test]
+    fn test_round_trip_dyn() {
+        let provider: Box<dyn AsyncProvider> = Box::new(AsyncAdapter::inline(SingleBlock));
+        let provider = BlockingProvider::new(provider);
+        assert!(provider
+            .get_block_with_txs(BlockId::Number(1))
+            .unwrap()
+            .is_some());
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(2)), Ok(None));
+    }
+}
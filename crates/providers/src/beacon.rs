@@ -0,0 +1,385 @@
+//! Beacon Provider
+//!
+//! The beacon provider fetches [BlobSidecar]s for an L1 block from a
+//! consensus layer beacon node. The [Provider][crate::provider::Provider]
+//! trait only knows about execution layer blocks, so blobs are served
+//! through the separate [BlobProvider] trait.
+//!
+//! An L1 block timestamp is converted to a beacon slot using the genesis
+//! time and slot duration reported by the beacon node. Each returned blob
+//! is checked against its versioned hash and kzg commitment before it is
+//! handed to the caller.
+
+use core::cell::OnceCell;
+use std::path::Path;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use axos_primitives::{
+    de_u64_str, BlobSidecar, BlockInfo, Bytes, IndexedBlobHash, B256, VERSIONED_HASH_VERSION_KZG,
+};
+use c_kzg::{Blob, Bytes48, KzgProof, KzgSettings};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// The beacon api genesis endpoint.
+const GENESIS_METHOD: &str = "eth/v1/beacon/genesis";
+
+/// The beacon api spec endpoint.
+const SPEC_METHOD: &str = "eth/v1/config/spec";
+
+/// The beacon api blob sidecars endpoint.
+const SIDECARS_METHOD_PREFIX: &str = "eth/v1/beacon/blob_sidecars";
+
+/// Blob Provider Trait
+pub trait BlobProvider {
+    /// Fetch the blobs with the given indexed hashes for the given L1 block.
+    /// Blobs are returned in the same order as the hashes.
+    fn get_blobs(
+        &self,
+        block: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Bytes>, BlobError>;
+}
+
+/// Blob Provider Errors
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BlobError {
+    /// The beacon node could not be reached or returned a bad status.
+    Http(String),
+    /// The beacon node response could not be decoded.
+    Decode(String),
+    /// The block timestamp is before the beacon chain genesis.
+    TimestampBeforeGenesis(u64),
+    /// The beacon node did not return a sidecar for the blob index.
+    MissingSidecar(u64),
+    /// The sidecar commitment does not hash to the expected versioned hash.
+    VersionedHashMismatch(u64),
+    /// The blob does not match its kzg commitment and proof.
+    InvalidKzgProof(u64),
+    /// The kzg library rejected the sidecar.
+    Kzg(String),
+}
+
+impl core::fmt::Display for BlobError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BlobError::Http(e) => write!(f, "beacon request failed: {}", e),
+            BlobError::Decode(e) => write!(f, "failed to decode beacon response: {}", e),
+            BlobError::TimestampBeforeGenesis(t) => {
+                write!(f, "timestamp {} is before the beacon genesis", t)
+            }
+            BlobError::MissingSidecar(i) => write!(f, "missing blob sidecar for index {}", i),
+            BlobError::VersionedHashMismatch(i) => {
+                write!(f, "versioned hash mismatch for blob index {}", i)
+            }
+            BlobError::InvalidKzgProof(i) => write!(f, "invalid kzg proof for blob index {}", i),
+            BlobError::Kzg(e) => write!(f, "kzg error: {}", e),
+        }
+    }
+}
+
+impl core::error::Error for BlobError {}
+
+/// A beacon api response wrapper.
+#[derive(Debug, Deserialize)]
+struct BeaconResponse<T> {
+    data: T,
+}
+
+/// The beacon api genesis response data.
+#[derive(Debug, Deserialize)]
+struct GenesisData {
+    #[serde(deserialize_with = "de_u64_str")]
+    genesis_time: u64,
+}
+
+/// The beacon api spec response data.
+#[derive(Debug, Deserialize)]
+struct SpecData {
+    #[serde(rename = "SECONDS_PER_SLOT", deserialize_with = "de_u64_str")]
+    seconds_per_slot: u64,
+}
+
+/// Computes the versioned hash for a kzg commitment.
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> B256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    B256::from(hash)
+}
+
+/// A beacon node provider for blob sidecars.
+pub struct BeaconProvider {
+    /// The base URL of the beacon node.
+    pub base: String,
+    /// The internal HTTP agent.
+    agent: ureq::Agent,
+    /// The kzg trusted setup used to verify blobs.
+    kzg_settings: KzgSettings,
+    /// The cached beacon chain genesis time.
+    genesis_time: OnceCell<u64>,
+    /// The cached number of seconds per slot.
+    seconds_per_slot: OnceCell<u64>,
+}
+
+impl core::fmt::Debug for BeaconProvider {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BeaconProvider")
+            .field("base", &self.base)
+            .field("genesis_time", &self.genesis_time.get())
+            .field("seconds_per_slot", &self.seconds_per_slot.get())
+            .finish()
+    }
+}
+
+impl BeaconProvider {
+    /// Instantiates a new [BeaconProvider] with the given kzg trusted setup.
+    pub fn new(base: impl Into<String>, kzg_settings: KzgSettings) -> Self {
+        Self {
+            base: base.into().trim_end_matches('/').to_string(),
+            agent: ureq::Agent::new(),
+            kzg_settings,
+            genesis_time: OnceCell::new(),
+            seconds_per_slot: OnceCell::new(),
+        }
+    }
+
+    /// Instantiates a new [BeaconProvider], loading the kzg trusted setup from a file.
+    pub fn with_trusted_setup_file(
+        base: impl Into<String>,
+        trusted_setup: &Path,
+    ) -> Result<Self, BlobError> {
+        let kzg_settings = KzgSettings::load_trusted_setup_file(trusted_setup)
+            .map_err(|e| BlobError::Kzg(std::format!("{:?}", e)))?;
+        Ok(Self::new(base, kzg_settings))
+    }
+
+    /// Sends a GET request to the beacon node and decodes the `data` field.
+    fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, BlobError> {
+        let url = std::format!("{}/{}", self.base, path);
+        tracing::debug!(target: "beacon_provider", "GET {}", url);
+        let response = self
+            .agent
+            .get(&url)
+            .call()
+            .map_err(|e| BlobError::Http(e.to_string()))?;
+        let response: BeaconResponse<T> = response
+            .into_json()
+            .map_err(|e| BlobError::Decode(e.to_string()))?;
+        Ok(response.data)
+    }
+
+    /// Returns the beacon chain genesis time, fetching it on first use.
+    pub fn genesis_time(&self) -> Result<u64, BlobError> {
+        if let Some(time) = self.genesis_time.get() {
+            return Ok(*time);
+        }
+        let genesis: GenesisData = self.get(GENESIS_METHOD)?;
+        Ok(*self.genesis_time.get_or_init(|| genesis.genesis_time))
+    }
+
+    /// Returns the number of seconds per slot, fetching it on first use.
+    pub fn seconds_per_slot(&self) -> Result<u64, BlobError> {
+        if let Some(seconds) = self.seconds_per_slot.get() {
+            return Ok(*seconds);
+        }
+        let spec: SpecData = self.get(SPEC_METHOD)?;
+        if spec.seconds_per_slot == 0 {
+            return Err(BlobError::Decode(String::from("zero seconds per slot")));
+        }
+        Ok(*self.seconds_per_slot.get_or_init(|| spec.seconds_per_slot))
+    }
+
+    /// Converts an L1 block timestamp into a beacon slot.
+    pub fn slot(&self, timestamp: u64) -> Result<u64, BlobError> {
+        let genesis_time = self.genesis_time()?;
+        let seconds_per_slot = self.seconds_per_slot()?;
+        timestamp
+            .checked_sub(genesis_time)
+            .map(|elapsed| elapsed / seconds_per_slot)
+            .ok_or(BlobError::TimestampBeforeGenesis(timestamp))
+    }
+
+    /// Fetch the blob sidecars at the given slot, filtered by blob index.
+    pub fn get_sidecars(&self, slot: u64, indices: &[u64]) -> Result<Vec<BlobSidecar>, BlobError> {
+        let mut path = std::format!("{}/{}", SIDECARS_METHOD_PREFIX, slot);
+        if !indices.is_empty() {
+            let indices = indices.iter().map(u64::to_string).collect::<Vec<_>>();
+            path = std::format!("{}?indices={}", path, indices.join(","));
+        }
+        self.get(&path)
+    }
+
+    /// Verifies a sidecar against its expected versioned hash and kzg proof.
+    pub fn verify_sidecar(
+        &self,
+        sidecar: &BlobSidecar,
+        expected: &IndexedBlobHash,
+    ) -> Result<(), BlobError> {
+        if kzg_to_versioned_hash(sidecar.kzg_commitment.as_slice()) != expected.hash {
+            return Err(BlobError::VersionedHashMismatch(expected.index));
+        }
+        let kzg_err = |e: c_kzg::Error| BlobError::Kzg(std::format!("{:?}", e));
+        let blob = Blob::from_bytes(&sidecar.blob).map_err(kzg_err)?;
+        let commitment = Bytes48::from_bytes(sidecar.kzg_commitment.as_slice()).map_err(kzg_err)?;
+        let proof = Bytes48::from_bytes(sidecar.kzg_proof.as_slice()).map_err(kzg_err)?;
+        match KzgProof::verify_blob_kzg_proof(&blob, &commitment, &proof, &self.kzg_settings) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(BlobError::InvalidKzgProof(expected.index)),
+            Err(e) => Err(kzg_err(e)),
+        }
+    }
+}
+
+impl BlobProvider for BeaconProvider {
+    fn get_blobs(
+        &self,
+        block: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Bytes>, BlobError> {
+        if blob_hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let slot = self.slot(block.timestamp)?;
+        let indices = blob_hashes.iter().map(|h| h.index).collect::<Vec<_>>();
+        let sidecars = self.get_sidecars(slot, &indices)?;
+
+        blob_hashes
+            .iter()
+            .map(|expected| {
+                let sidecar = sidecars
+                    .iter()
+                    .find(|s| s.index == expected.index)
+                    .ok_or(BlobError::MissingSidecar(expected.index))?;
+                self.verify_sidecar(sidecar, expected)?;
+                Ok(sidecar.blob.clone())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::beacon::MockBeacon;
+    use axos_primitives::{FixedBytes, BYTES_PER_BLOB};
+    use c_kzg::KzgCommitment;
+    use std::vec;
+
+    const TRUSTED_SETUP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/res/trusted_setup.txt");
+
+    fn load_settings() -> KzgSettings {
+        KzgSettings::load_trusted_setup_file(Path::new(TRUSTED_SETUP)).unwrap()
+    }
+
+    /// Builds a valid sidecar for a blob filled with the given byte.
+    fn build_sidecar(index: u64, settings: &KzgSettings) -> (BlobSidecar, IndexedBlobHash) {
+        // Keep the high byte of each field element zero so the blob is canonical.
+        let mut bytes = vec![0u8; BYTES_PER_BLOB];
+        bytes.chunks_mut(32).for_each(|c| c[31] = index as u8 + 1);
+        let blob = Blob::from_bytes(&bytes).unwrap();
+        let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, settings).unwrap();
+        let proof =
+            KzgProof::compute_blob_kzg_proof(&blob, &commitment.to_bytes(), settings).unwrap();
+        let sidecar = BlobSidecar {
+            index,
+            blob: Bytes::from(bytes),
+            kzg_commitment: FixedBytes::from_slice(&commitment.to_bytes().into_inner()),
+            kzg_proof: FixedBytes::from_slice(&proof.to_bytes().into_inner()),
+        };
+        let hash = kzg_to_versioned_hash(sidecar.kzg_commitment.as_slice());
+        (sidecar, IndexedBlobHash::new(index, hash))
+    }
+
+    fn block_at(timestamp: u64) -> BlockInfo {
+        BlockInfo::new(B256::ZERO, 1, B256::ZERO, timestamp)
+    }
+
+    #[test]
+    fn test_timestamp_to_slot() {
+        let server = MockBeacon::new(1_000, 12).spawn();
+        let provider = BeaconProvider::new(server.url(), load_settings());
+        assert_eq!(provider.slot(1_000).unwrap(), 0);
+        assert_eq!(provider.slot(1_030).unwrap(), 2);
+        assert_eq!(
+            provider.slot(999),
+            Err(BlobError::TimestampBeforeGenesis(999))
+        );
+        // Genesis and spec are only fetched once.
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_get_blobs() {
+        let settings = load_settings();
+        let (first, first_hash) = build_sidecar(0, &settings);
+        let (second, second_hash) = build_sidecar(1, &settings);
+        let server = MockBeacon::new(1_000, 12)
+            .with_sidecars(5, vec![first.clone(), second.clone()])
+            .spawn();
+        let provider = BeaconProvider::new(server.url(), settings);
+
+        let blobs = provider
+            .get_blobs(&block_at(1_060), &[second_hash, first_hash])
+            .unwrap();
+        assert_eq!(blobs, vec![second.blob, first.blob]);
+        let last = server.requests().pop().unwrap();
+        assert_eq!(last.path, "/eth/v1/beacon/blob_sidecars/5?indices=1,0");
+    }
+
+    #[test]
+    fn test_get_blobs_versioned_hash_mismatch() {
+        let settings = load_settings();
+        let (sidecar, _) = build_sidecar(0, &settings);
+        let server = MockBeacon::new(0, 12)
+            .with_sidecars(1, vec![sidecar])
+            .spawn();
+        let provider = BeaconProvider::new(server.url(), settings);
+        let wrong = IndexedBlobHash::new(0, B256::ZERO);
+        assert_eq!(
+            provider.get_blobs(&block_at(12), &[wrong]),
+            Err(BlobError::VersionedHashMismatch(0))
+        );
+    }
+
+    #[test]
+    fn test_get_blobs_invalid_proof() {
+        let settings = load_settings();
+        let (mut sidecar, hash) = build_sidecar(0, &settings);
+        let (other, _) = build_sidecar(1, &settings);
+        sidecar.blob = other.blob;
+        let server = MockBeacon::new(0, 12)
+            .with_sidecars(1, vec![sidecar])
+            .spawn();
+        let provider = BeaconProvider::new(server.url(), settings);
+        assert_eq!(
+            provider.get_blobs(&block_at(12), &[hash]),
+            Err(BlobError::InvalidKzgProof(0))
+        );
+    }
+
+    #[test]
+    fn test_get_blobs_missing_sidecar() {
+        let settings = load_settings();
+        let (sidecar, _) = build_sidecar(0, &settings);
+        let server = MockBeacon::new(0, 12)
+            .with_sidecars(1, vec![sidecar])
+            .spawn();
+        let provider = BeaconProvider::new(server.url(), settings);
+        let missing = IndexedBlobHash::new(3, B256::ZERO);
+        assert_eq!(
+            provider.get_blobs(&block_at(12), &[missing]),
+            Err(BlobError::MissingSidecar(3))
+        );
+    }
+
+    #[test]
+    fn test_blob_error_into_anyhow() {
+        let err = anyhow::Error::from(BlobError::MissingSidecar(3));
+        assert_eq!(err.to_string(), "missing blob sidecar for index 3");
+        assert_eq!(
+            err.downcast_ref::<BlobError>(),
+            Some(&BlobError::MissingSidecar(3))
+        );
+    }
+}
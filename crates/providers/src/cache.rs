@@ -0,0 +1,461 @@
+//! Caching Provider
+//!
+//! The [CachingProvider] wraps a [Provider] and keeps the blocks it
+//! fetches in a bounded, least recently used cache. Blocks are cached by
+//! hash, and by number once they are finalized, since only then can a
+//! number no longer be reorged to a different block. When a finalized
+//! block is cached, its cached ancestors are indexed by number too, so
+//! blocks fetched before they were finalized are found by number.
+//!
+//! The finalized height is learned from `finalized` tag queries, or set
+//! with [CachingProvider::set_finalized]. Under `std`, a [FileStore] can
+//! back the cache so blocks survive restarts.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use axos_providers::cache::CachingProvider;
+//! use axos_providers::provider::{Error, Provider};
+//! use axos_primitives::{BlockId, BlockWithTransactions, B256};
+//!
+//! struct Node;
+//!
+//! impl Provider for Node {
+//!     fn get_block_with_txs(&self, _: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+//!         Ok(Some(BlockWithTransactions {
+//!             hash: Some(B256::with_last_byte(1)),
+//!             ..Default::default()
+//!         }))
+//!     }
+//! }
+//!
+//! let provider = CachingProvider::new(Node).with_capacity(64);
+//! let hash = BlockId::Hash(B256::with_last_byte(1));
+//! provider.get_block_with_txs(hash).unwrap();
+//! provider.get_block_with_txs(hash).unwrap();
+//! assert_eq!(provider.stats().hits, 1);
+//! assert_eq!(provider.stats().misses, 1);
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    AccountProof, Address, BlockId, BlockKind, BlockWithTransactions, Filter, Log,
+    TransactionReceipt, B256,
+};
+
+use crate::provider::{Error, Provider};
+
+mod lru;
+#[cfg(feature = "std")]
+mod store;
+
+#[doc(inline)]
+pub use lru::Lru;
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use store::FileStore;
+
+/// The default number of blocks held in memory.
+///
+/// The capacity counts blocks, not bytes. Blocks with many transactions
+/// take far more memory than empty ones, so size it for the largest
+/// blocks you expect.
+pub const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+/// Cache hit and miss counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups served from the cache.
+    pub hits: u64,
+    /// Lookups forwarded to the inner provider.
+    pub misses: u64,
+}
+
+/// A [Provider] that caches blocks.
+#[derive(Debug)]
+pub struct CachingProvider<P> {
+    /// The inner provider.
+    inner: P,
+    /// Blocks by hash.
+    blocks: RefCell<Lru<B256, BlockWithTransactions>>,
+    /// Finalized block hashes by number.
+    numbers: RefCell<BTreeMap<u64, B256>>,
+    /// The highest known finalized block number.
+    finalized: Cell<Option<u64>>,
+    /// The cache hit and miss counts.
+    stats: Cell<CacheStats>,
+    /// The optional on-disk store.
+    #[cfg(feature = "std")]
+    store: Option<FileStore>,
+}
+
+impl<P> CachingProvider<P> {
+    /// Instantiates a new [CachingProvider] with the default capacity.
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            blocks: RefCell::new(Lru::new(DEFAULT_CACHE_CAPACITY)),
+            numbers: RefCell::new(BTreeMap::new()),
+            finalized: Cell::new(None),
+            stats: Cell::new(CacheStats::default()),
+            #[cfg(feature = "std")]
+            store: None,
+        }
+    }
+
+    /// Sets the maximum number of blocks held in memory. The capacity
+    /// counts blocks, not bytes. Cached blocks are dropped.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.blocks = RefCell::new(Lru::new(capacity));
+        self.numbers.get_mut().clear();
+        self
+    }
+
+    /// Backs the cache with an on-disk store.
+    #[cfg(feature = "std")]
+    pub fn with_store(mut self, store: FileStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Returns the inner provider.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the cache hit and miss counts.
+    pub fn stats(&self) -> CacheStats {
+        self.stats.get()
+    }
+
+    /// Returns the number of blocks held in memory.
+    pub fn len(&self) -> usize {
+        self.blocks.borrow().len()
+    }
+
+    /// Returns true if no blocks are held in memory.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.borrow().is_empty()
+    }
+
+    /// Returns the highest known finalized block number.
+    pub fn finalized(&self) -> Option<u64> {
+        self.finalized.get()
+    }
+
+    /// Marks every block up to and including `number` as finalized.
+    /// The finalized height never moves backwards.
+    pub fn set_finalized(&self, number: u64) {
+        if self.finalized.get().is_none_or(|f| number > f) {
+            self.finalized.set(Some(number));
+        }
+    }
+
+    /// Returns true if the block number is finalized.
+    fn is_finalized(&self, number: u64) -> bool {
+        self.finalized.get().is_some_and(|f| number <= f)
+    }
+
+    /// Records a cache hit or miss.
+    fn record(&self, hit: bool) {
+        let mut stats = self.stats.get();
+        if hit {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+        self.stats.set(stats);
+    }
+
+    /// Looks up a cached block by hash.
+    fn cached_by_hash(&self, hash: B256) -> Option<BlockWithTransactions> {
+        if let Some(block) = self.blocks.borrow_mut().get(&hash) {
+            return Some(block.clone());
+        }
+        #[cfg(feature = "std")]
+        if let Some(block) = self.store.as_ref().and_then(|s| s.get(hash)) {
+            self.remember(hash, block.clone());
+            return Some(block);
+        }
+        None
+    }
+
+    /// Looks up a cached finalized block by number.
+    fn cached_by_number(&self, number: u64) -> Option<BlockWithTransactions> {
+        if !self.is_finalized(number) {
+            return None;
+        }
+        let hash = self.numbers.borrow().get(&number).copied();
+        #[cfg(feature = "std")]
+        let hash = hash.or_else(|| self.store.as_ref().and_then(|s| s.hash_of(number)));
+        self.cached_by_hash(hash?)
+    }
+
+    /// Keeps a block in memory, dropping the number index of any evicted block.
+    fn remember(&self, hash: B256, block: BlockWithTransactions) {
+        let number = block.number.map(|n| n.to::<u64>());
+        let evicted = self.blocks.borrow_mut().insert(hash, block);
+        if let Some((evicted, block)) = evicted {
+            let mut numbers = self.numbers.borrow_mut();
+            if let Some(n) = block.number {
+                if numbers.get(&n.to::<u64>()) == Some(&evicted) {
+                    numbers.remove(&n.to::<u64>());
+                }
+            }
+        }
+        if let Some(number) = number.filter(|n| self.is_finalized(*n)) {
+            self.index_finalized(number, hash);
+        }
+    }
+
+    /// Indexes a cached finalized block by number, along with the cached
+    /// ancestors it links to by parent hash.
+    fn index_finalized(&self, mut number: u64, mut hash: B256) {
+        let blocks = self.blocks.borrow();
+        let mut numbers = self.numbers.borrow_mut();
+        while let Some(block) = blocks.peek(&hash) {
+            if block.number.map(|n| n.to::<u64>()) != Some(number)
+                || numbers.insert(number, hash) == Some(hash)
+            {
+                break;
+            }
+            #[cfg(feature = "std")]
+            if let Some(store) = &self.store {
+                if let Err(e) = store.put_number(number, hash) {
+                    tracing::warn!(target: "caching_provider", "failed to index block {}: {}", hash, e);
+                }
+            }
+            let Some(parent) = number.checked_sub(1) else {
+                break;
+            };
+            number = parent;
+            hash = block.parent_hash;
+        }
+    }
+
+    /// Caches a block fetched from the inner provider.
+    fn insert(&self, block: &BlockWithTransactions) {
+        let Some(hash) = block.hash else {
+            return;
+        };
+        self.remember(hash, block.clone());
+        #[cfg(feature = "std")]
+        if let Some(store) = &self.store {
+            let finalized = block
+                .number
+                .is_some_and(|n| self.is_finalized(n.to::<u64>()));
+            if let Err(e) = store.put(hash, block, finalized) {
+                tracing::warn!(target: "caching_provider", "failed to persist block {}: {}", hash, e);
+            }
+        }
+    }
+}
+
+impl<P: Provider> Provider for CachingProvider<P> {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        let cached = match block_id {
+            BlockId::Hash(hash) => self.cached_by_hash(hash),
+            BlockId::Number(number) => self.cached_by_number(number),
+            BlockId::Kind(_) => None,
+        };
+        if cached.is_some() {
+            self.record(true);
+            return Ok(cached);
+        }
+        if !matches!(block_id, BlockId::Kind(_)) {
+            self.record(false);
+        }
+
+        let block = self.inner.get_block_with_txs(block_id)?;
+        if let Some(block) = &block {
+            if block_id == BlockId::Kind(BlockKind::Finalized) {
+                if let Some(number) = block.number {
+                    self.set_finalized(number.to::<u64>());
+                }
+            }
+            self.insert(block);
+        }
+        Ok(block)
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        self.inner.get_receipts(block_id)
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        self.inner.get_logs(filter)
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        self.inner.get_transaction(hash)
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.inner.get_proof(address, slots, block_id)
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        self.inner.get_storage_at(address, slot, block_id)
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.inner.chain_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_primitives::U64;
+
+    /// A provider serving blocks 0 through 9 and counting its calls.
+    #[derive(Default)]
+    struct CountingProvider {
+        calls: Cell<u64>,
+    }
+
+    fn block(number: u64) -> BlockWithTransactions {
+        BlockWithTransactions {
+            number: Some(U64::from(number)),
+            hash: Some(B256::with_last_byte(number as u8 + 1)),
+            parent_hash: B256::with_last_byte(number as u8),
+            ..Default::default()
+        }
+    }
+
+    impl Provider for CountingProvider {
+        fn get_block_with_txs(&self, id: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(match id {
+                BlockId::Number(n) if n < 10 => Some(block(n)),
+                BlockId::Hash(h) if h[31] > 0 && h[31] <= 10 => Some(block(h[31] as u64 - 1)),
+                BlockId::Kind(BlockKind::Finalized) => Some(block(5)),
+                BlockId::Kind(_) => Some(block(9)),
+                _ => None,
+            })
+        }
+    }
+
+    #[test]
+    fn test_cache_by_hash() {
+        let provider = CachingProvider::new(CountingProvider::default());
+        let id = BlockId::Hash(B256::with_last_byte(3));
+        assert_eq!(provider.get_block_with_txs(id), Ok(Some(block(2))));
+        assert_eq!(provider.get_block_with_txs(id), Ok(Some(block(2))));
+        assert_eq!(provider.inner().calls.get(), 1);
+        assert_eq!(provider.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[test]
+    fn test_cache_by_number_once_finalized() {
+        let provider = CachingProvider::new(CountingProvider::default());
+        provider.get_block_with_txs(BlockId::Number(3)).unwrap();
+        provider.get_block_with_txs(BlockId::Number(3)).unwrap();
+        assert_eq!(provider.inner().calls.get(), 2);
+
+        provider
+            .get_block_with_txs(BlockId::Kind(BlockKind::Finalized))
+            .unwrap();
+        assert_eq!(provider.finalized(), Some(5));
+        provider.get_block_with_txs(BlockId::Number(3)).unwrap();
+        provider.get_block_with_txs(BlockId::Number(3)).unwrap();
+        provider.get_block_with_txs(BlockId::Number(5)).unwrap();
+        assert_eq!(provider.inner().calls.get(), 4);
+
+        // Unfinalized numbers are always refetched.
+        provider.get_block_with_txs(BlockId::Number(7)).unwrap();
+        provider.get_block_with_txs(BlockId::Number(7)).unwrap();
+        assert_eq!(provider.inner().calls.get(), 6);
+        assert_eq!(provider.stats(), CacheStats { hits: 2, misses: 5 });
+    }
+
+    #[test]
+    fn test_cache_indexes_newly_finalized_blocks() {
+        let provider = CachingProvider::new(CountingProvider::default());
+        for n in 2..5 {
+            provider.get_block_with_txs(BlockId::Number(n)).unwrap();
+        }
+        assert_eq!(provider.inner().calls.get(), 3);
+
+        // Finalizing block 5 links the cached blocks 2 through 4 to it.
+        provider
+            .get_block_with_txs(BlockId::Kind(BlockKind::Finalized))
+            .unwrap();
+        for n in 2..6 {
+            assert_eq!(
+                provider.get_block_with_txs(BlockId::Number(n)),
+                Ok(Some(block(n)))
+            );
+        }
+        assert_eq!(provider.inner().calls.get(), 4);
+    }
+
+    #[test]
+    fn test_cache_capacity() {
+        let provider = CachingProvider::new(CountingProvider::default()).with_capacity(2);
+        provider.set_finalized(9);
+        for n in 0..3 {
+            provider.get_block_with_txs(BlockId::Number(n)).unwrap();
+        }
+        assert_eq!(provider.len(), 2);
+        provider.get_block_with_txs(BlockId::Number(0)).unwrap();
+        assert_eq!(provider.inner().calls.get(), 4);
+        provider.get_block_with_txs(BlockId::Number(2)).unwrap();
+        assert_eq!(provider.inner().calls.get(), 4);
+    }
+
+    #[test]
+    fn test_cache_misses_not_cached() {
+        let provider = CachingProvider::new(CountingProvider::default());
+        provider.set_finalized(20);
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(15)), Ok(None));
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(15)), Ok(None));
+        assert_eq!(provider.inner().calls.get(), 2);
+        assert!(provider.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_file_store_survives_restart() {
+        let dir = std::env::temp_dir().join(std::format!(
+            "axos-cache-{}-{}",
+            std::process::id(),
+            line!()
+        ));
+        let first = CachingProvider::new(CountingProvider::default())
+            .with_store(FileStore::open(&dir).unwrap());
+        first.set_finalized(9);
+        first.get_block_with_txs(BlockId::Number(4)).unwrap();
+
+        let second = CachingProvider::new(CountingProvider::default())
+            .with_store(FileStore::open(&dir).unwrap());
+        second.set_finalized(9);
+        assert_eq!(
+            second.get_block_with_txs(BlockId::Number(4)),
+            Ok(Some(block(4)))
+        );
+        assert_eq!(
+            second.get_block_with_txs(BlockId::Hash(B256::with_last_byte(5))),
+            Ok(Some(block(4)))
+        );
+        assert_eq!(second.inner().calls.get(), 0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
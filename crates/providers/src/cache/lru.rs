@@ -0,0 +1,121 @@
+//! Least Recently Used Map
+
+use alloc::collections::BTreeMap;
+
+/// A bounded map that evicts the least recently used entry.
+#[derive(Debug, Clone)]
+pub struct Lru<K, V> {
+    /// The maximum number of entries.
+    capacity: usize,
+    /// A counter incremented on every access.
+    tick: u64,
+    /// The entries, with the tick of their last access.
+    entries: BTreeMap<K, (V, u64)>,
+    /// The keys, ordered by last access.
+    order: BTreeMap<u64, K>,
+}
+
+impl<K: Ord + Clone, V> Lru<K, V> {
+    /// Instantiates a new [Lru] holding at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: BTreeMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the next access tick.
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Returns the value for the key and marks it as most recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let tick = self.next_tick();
+        let (_, last) = self.entries.get_mut(key)?;
+        self.order.remove(last);
+        *last = tick;
+        self.order.insert(tick, key.clone());
+        self.entries.get(key).map(|(v, _)| v)
+    }
+
+    /// Returns the value for the key, without marking it as used.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|(v, _)| v)
+    }
+
+    /// Returns true if the map holds the key, without marking it as used.
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Inserts a value, returning the entries evicted to stay within capacity.
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        let tick = self.next_tick();
+        if let Some((_, last)) = self.entries.insert(key.clone(), (value, tick)) {
+            self.order.remove(&last);
+        }
+        self.order.insert(tick, key);
+        if self.entries.len() <= self.capacity {
+            return None;
+        }
+        let (_, oldest) = self.order.pop_first()?;
+        self.entries.remove(&oldest).map(|(v, _)| (oldest, v))
+    }
+
+    /// Removes the value for the key.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, tick) = self.entries.remove(key)?;
+        self.order.remove(&tick);
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_evicts_least_recently_used() {
+        let mut lru = Lru::new(2);
+        assert_eq!(lru.insert(1, "a"), None);
+        assert_eq!(lru.insert(2, "b"), None);
+        assert_eq!(lru.get(&1), Some(&"a"));
+        assert_eq!(lru.insert(3, "c"), Some((2, "b")));
+        assert!(lru.contains(&1));
+        assert!(!lru.contains(&2));
+        assert_eq!(lru.len(), 2);
+    }
+
+    #[test]
+    fn test_lru_reinsert_and_remove() {
+        let mut lru = Lru::new(2);
+        lru.insert(1, "a");
+        lru.insert(1, "b");
+        assert_eq!(lru.len(), 1);
+        assert_eq!(lru.remove(&1), Some("b"));
+        assert!(lru.is_empty());
+        assert_eq!(Lru::new(0).insert(1, "a"), Some((1, "a")));
+    }
+}
@@ -0,0 +1,79 @@
+//! File Block Store
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+
+use axos_primitives::{BlockWithTransactions, B256};
+
+/// A block store persisted to a directory.
+///
+/// Blocks are written as json to `blocks/<hash>.json`, and finalized
+/// block numbers map to their hash in `numbers/<number>`.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    /// The root directory of the store.
+    root: PathBuf,
+}
+
+impl FileStore {
+    /// Opens a [FileStore] at the given directory, creating it if needed.
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(root.join("blocks"))?;
+        fs::create_dir_all(root.join("numbers"))?;
+        Ok(Self { root })
+    }
+
+    /// Returns the root directory of the store.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the path of the block with the given hash.
+    fn block_path(&self, hash: B256) -> PathBuf {
+        self.root
+            .join("blocks")
+            .join(std::format!("{:x}.json", hash))
+    }
+
+    /// Returns the path of the finalized block number index entry.
+    fn number_path(&self, number: u64) -> PathBuf {
+        self.root.join("numbers").join(number.to_string())
+    }
+
+    /// Reads a block by hash.
+    pub fn get(&self, hash: B256) -> Option<BlockWithTransactions> {
+        let bytes = fs::read(self.block_path(hash)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Reads the hash of a finalized block by number.
+    pub fn hash_of(&self, number: u64) -> Option<B256> {
+        let hash = fs::read_to_string(self.number_path(number)).ok()?;
+        hash.trim().parse().ok()
+    }
+
+    /// Writes a block, indexing it by number if it is finalized.
+    pub fn put(
+        &self,
+        hash: B256,
+        block: &BlockWithTransactions,
+        finalized: bool,
+    ) -> io::Result<()> {
+        let bytes =
+            serde_json::to_vec(block).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(self.block_path(hash), bytes)?;
+        if let (true, Some(number)) = (finalized, block.number) {
+            self.put_number(number.to::<u64>(), hash)?;
+        }
+        Ok(())
+    }
+
+    /// Indexes a finalized block by number.
+    pub fn put_number(&self, number: u64, hash: B256) -> io::Result<()> {
+        let hash: String = std::format!("{:x}", hash);
+        fs::write(self.number_path(number), hash)
+    }
+}
@@ -1,57 +1,148 @@
-// #![feature(async_fn_in_trait)]
-// use embedded_io_adapters::tokio_1::FromTokio;
-// use embedded_io_async::BufRead;
-// use embedded_nal_async::{AddrType, IpAddr, Ipv4Addr};
-//
-// /// Loopback DNS resolver
-// #[derive(Debug)]
-// pub struct LoopbackDns;
-//
-//
-// impl embedded_nal_async::Dns for LoopbackDns {
-//     type Error = TestError;
-//
-//     async fn get_host_by_name(&self, _: &str, _: AddrType) -> Result<IpAddr, Self::Error> {
-//         Ok(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
-//     }
-//
-//     async fn get_host_by_address(&self, _: IpAddr, _: &mut [u8]) -> Result<usize, Self::Error> {
-//         Err(TestError)
-//     }
-// }
-//
-// /// Tokio TCP
-// #[derive(Debug)]
-// pub struct TokioTcp;
-//
-// /// An embedded io Error
-// #[derive(Debug)]
-// pub enum TcpError {
-//     /// Some other error
-//     Other,
-// }
-//
-// impl embedded_io::Error for TcpError {
-//     fn kind(&self) -> embedded_io::ErrorKind {
-//         embedded_io::ErrorKind::Other
-//     }
-// }
-//
-// impl embedded_nal_async::TcpConnect for TokioTcp {
-//     type Error = std::io::Error;
-//     type Connection<'m> = FromTokio<TcpStream>;
-//
-//     async fn connect<'m>(
-//         &'m self,
-//         remote: embedded_nal_async::SocketAddr,
-//     ) -> Result<Self::Connection<'m>, Self::Error> {
-//         let ip = match remote {
-//             embedded_nal_async::SocketAddr::V4(a) => a.ip().octets().into(),
-//             embedded_nal_async::SocketAddr::V6(a) => a.ip().octets().into(),
-//         };
-//         let remote = SocketAddr::new(ip, remote.port());
-//         let stream = TcpStream::connect(remote).await?;
-//         let stream = FromTokio::new(stream);
-//         Ok(stream)
-//     }
-// }
+//! Network Stack Adapters
+//!
+//! The [PurpleProvider][crate::purple::PurpleProvider] is generic over an
+//! [embedded_nal_async] DNS resolver and TCP stack, so firmware can plug in
+//! its own network driver. This module provides a static resolver that
+//! works anywhere, and blocking adapters over [std::net] when the `std`
+//! feature is enabled.
+
+use embedded_nal_async::heapless::String;
+use embedded_nal_async::{AddrType, IpAddr, Ipv4Addr};
+
+/// A DNS resolver that resolves every host name to a fixed address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticDns(pub IpAddr);
+
+impl StaticDns {
+    /// A [StaticDns] resolving every host to `127.0.0.1`.
+    pub fn loopback() -> Self {
+        Self(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+    }
+}
+
+/// Static DNS Errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaticDnsError {
+    /// Reverse lookups are not supported.
+    Unsupported,
+}
+
+impl embedded_nal_async::Dns for StaticDns {
+    type Error = StaticDnsError;
+
+    async fn get_host_by_name(&self, _: &str, _: AddrType) -> Result<IpAddr, Self::Error> {
+        Ok(self.0)
+    }
+
+    async fn get_host_by_address(&self, _: IpAddr) -> Result<String<256>, Self::Error> {
+        Err(StaticDnsError::Unsupported)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::std_stack::{StdConnection, StdDns, StdTcp};
+
+#[cfg(feature = "std")]
+mod std_stack {
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    use embedded_nal_async::heapless::String;
+    use embedded_nal_async::{AddrType, IpAddr, SocketAddr};
+
+    /// A DNS resolver backed by the operating system.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct StdDns;
+
+    impl embedded_nal_async::Dns for StdDns {
+        type Error = std::io::Error;
+
+        async fn get_host_by_name(
+            &self,
+            host: &str,
+            addr_type: AddrType,
+        ) -> Result<IpAddr, Self::Error> {
+            (host, 0)
+                .to_socket_addrs()?
+                .find_map(|addr| match (addr.ip(), &addr_type) {
+                    (std::net::IpAddr::V4(ip), AddrType::IPv4 | AddrType::Either) => {
+                        Some(IpAddr::V4(ip.octets().into()))
+                    }
+                    (std::net::IpAddr::V6(ip), AddrType::IPv6 | AddrType::Either) => {
+                        Some(IpAddr::V6(ip.octets().into()))
+                    }
+                    _ => None,
+                })
+                .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))
+        }
+
+        async fn get_host_by_address(&self, _: IpAddr) -> Result<String<256>, Self::Error> {
+            Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+        }
+    }
+
+    /// A blocking TCP stack backed by [std::net::TcpStream].
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct StdTcp;
+
+    /// A blocking TCP connection.
+    #[derive(Debug)]
+    pub struct StdConnection(pub TcpStream);
+
+    impl embedded_nal_async::TcpConnect for StdTcp {
+        type Error = std::io::Error;
+        type Connection<'m> = StdConnection;
+
+        async fn connect<'m>(
+            &'m self,
+            remote: SocketAddr,
+        ) -> Result<Self::Connection<'m>, Self::Error>
+        where
+            Self: 'm,
+        {
+            let ip: std::net::IpAddr = match remote {
+                SocketAddr::V4(a) => a.ip().octets().into(),
+                SocketAddr::V6(a) => a.ip().octets().into(),
+            };
+            let stream = TcpStream::connect(std::net::SocketAddr::new(ip, remote.port()))?;
+            Ok(StdConnection(stream))
+        }
+    }
+
+    impl embedded_io_async::ErrorType for StdConnection {
+        type Error = std::io::Error;
+    }
+
+    impl embedded_io_async::Read for StdConnection {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            std::io::Read::read(&mut self.0, buf)
+        }
+    }
+
+    impl embedded_io_async::Write for StdConnection {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            std::io::Write::write(&mut self.0, buf)
+        }
+
+        async fn flush(&mut self) -> Result<(), Self::Error> {
+            std::io::Write::flush(&mut self.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::block_on;
+    use embedded_nal_async::Dns;
+
+    #[test]
+    fn test_static_dns() {
+        let dns = StaticDns::loopback();
+        let ip = block_on(dns.get_host_by_name("example.com", AddrType::Either)).unwrap();
+        assert_eq!(ip, IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+        assert_eq!(
+            block_on(dns.get_host_by_address(ip)),
+            Err(StaticDnsError::Unsupported)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Clocks
+//!
+//! A [Clock] supplies the time for providers that wait, such as the
+//! [RetryProvider][crate::retry::RetryProvider]. Swapping the clock lets
+//! firmware plug in a hardware timer, and tests run without sleeping.
+
+use core::cell::Cell;
+use core::time::Duration;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::cell::RefCell;
+
+/// Clock Trait
+pub trait Clock {
+    /// Returns the time elapsed since an arbitrary, fixed point.
+    /// The value must never decrease.
+    fn now(&self) -> Duration;
+
+    /// Blocks for the given duration.
+    fn sleep(&self, duration: Duration);
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+/// A [Clock] backed by [std::time::Instant] and [std::thread::sleep].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    /// The instant the clock was created.
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A [Clock] that only moves when told to.
+/// Sleeping advances the clock instantly.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    /// The current time.
+    now: Cell<Duration>,
+    /// The durations slept so far.
+    #[cfg(feature = "alloc")]
+    sleeps: RefCell<Vec<Duration>>,
+}
+
+impl ManualClock {
+    /// Instantiates a new [ManualClock] at time zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Returns the durations slept so far.
+    #[cfg(feature = "alloc")]
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.borrow().clone()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        #[cfg(feature = "alloc")]
+        self.sleeps.borrow_mut().push(duration);
+        self.advance(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new();
+        clock.advance(Duration::from_secs(1));
+        Clock::sleep(&&clock, Duration::from_millis(500));
+        assert_eq!(clock.now(), Duration::from_millis(1500));
+        #[cfg(feature = "alloc")]
+        assert_eq!(clock.sleeps(), [Duration::from_millis(500)]);
+    }
+}
@@ -0,0 +1,525 @@
+//! Era1 Provider
+//!
+//! The [Era1Provider] is a read-only [Provider] that serves historical L1
+//! blocks and receipts from local `.era1` archive files, without an
+//! execution client. Each file holds up to [MAX_ERA_BLOCKS] pre-merge
+//! blocks as snappy-compressed rlp, framed in e2store records and
+//! followed by a header accumulator root and a block index.
+//!
+//! Lookups by number seek straight to the block through the index.
+//! Lookups by hash build a hash index lazily, one file at a time, the
+//! first time a hash is not found. The latest, safe and finalized blocks
+//! are all the last block in the archive, since archived history is final.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use axos_providers::era::Era1Provider;
+//! use axos_providers::provider::Provider;
+//! use axos_primitives::BlockId;
+//!
+//! let provider = Era1Provider::open_dir("/data/era1").unwrap().with_chain_id(1);
+//! let block = provider.get_block_with_txs(BlockId::Number(1_000_000));
+//! ```
+
+use core::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::string::ToString;
+use std::vec::Vec;
+
+use axos_primitives::{BlockId, BlockKind, BlockWithTransactions, TransactionReceipt, B256, U256};
+
+use crate::provider::{Error, Provider};
+
+mod decode;
+pub mod e2store;
+mod file;
+
+#[doc(inline)]
+pub use decode::*;
+#[doc(inline)]
+pub use file::*;
+
+/// The file extension of era1 files.
+pub const ERA1_EXTENSION: &str = "era1";
+
+/// A [Provider] backed by a set of era1 files.
+#[derive(Debug)]
+pub struct Era1Provider {
+    /// The era1 files, ordered by their first block.
+    files: Vec<Era1File>,
+    /// The chain id, if known.
+    chain_id: Option<u64>,
+    /// The block numbers of the hashes indexed so far.
+    hashes: RefCell<BTreeMap<B256, u64>>,
+    /// The number of files whose hashes are indexed.
+    indexed: Cell<usize>,
+}
+
+impl Era1Provider {
+    /// Instantiates a new [Era1Provider] from open era1 files.
+    pub fn new(mut files: Vec<Era1File>) -> Self {
+        files.sort_by_key(|f| f.start());
+        Self {
+            files,
+            chain_id: None,
+            hashes: RefCell::new(BTreeMap::new()),
+            indexed: Cell::new(0),
+        }
+    }
+
+    /// Opens every `.era1` file in the given directory.
+    pub fn open_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == ERA1_EXTENSION) {
+                files.push(Era1File::open(path)?);
+            }
+        }
+        Ok(Self::new(files))
+    }
+
+    /// Sets the chain id returned by [Provider::chain_id].
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Returns the era1 files, ordered by their first block.
+    pub fn files(&self) -> &[Era1File] {
+        &self.files
+    }
+
+    /// Returns the number of the first archived block.
+    pub fn earliest(&self) -> Option<u64> {
+        self.files.iter().find(|f| !f.is_empty()).map(|f| f.start())
+    }
+
+    /// Returns the number of the last archived block.
+    pub fn latest(&self) -> Option<u64> {
+        self.files.iter().filter_map(|f| f.end()).max()
+    }
+
+    /// Reads the raw block with the given number.
+    pub fn block(&self, number: u64) -> Result<Option<Era1Block>, Error> {
+        match self.files.iter().find(|f| f.contains(number)) {
+            Some(file) => file.block(number).map_err(io_error),
+            None => Ok(None),
+        }
+    }
+
+    /// Resolves a block id to a block number.
+    fn resolve(&self, block_id: BlockId) -> Result<Option<u64>, Error> {
+        match block_id {
+            BlockId::Number(number) => Ok(Some(number)),
+            BlockId::Hash(hash) => self.number_of(hash),
+            BlockId::Kind(BlockKind::Earliest) => Ok(self.earliest()),
+            BlockId::Kind(BlockKind::Latest | BlockKind::Safe | BlockKind::Finalized) => {
+                Ok(self.latest())
+            }
+        }
+    }
+
+    /// Looks up the number of the block with the given hash, indexing
+    /// the hashes of one more file each time the hash is not found.
+    fn number_of(&self, hash: B256) -> Result<Option<u64>, Error> {
+        loop {
+            if let Some(number) = self.hashes.borrow().get(&hash) {
+                return Ok(Some(*number));
+            }
+            let Some(file) = self.files.get(self.indexed.get()) else {
+                return Ok(None);
+            };
+            let mut hashes = self.hashes.borrow_mut();
+            for number in file.start()..file.start() + file.len() {
+                if let Some(hash) = file.block_hash(number).map_err(io_error)? {
+                    hashes.insert(hash, number);
+                }
+            }
+            self.indexed.set(self.indexed.get() + 1);
+        }
+    }
+
+    /// Decodes the block with the given number.
+    fn decoded(&self, number: u64) -> Result<Option<(BlockWithTransactions, Era1Block)>, Error> {
+        let Some(raw) = self.block(number)? else {
+            return Ok(None);
+        };
+        let mut block = decode_header(&raw.header).map_err(decode_error)?;
+        let hash = block.hash.unwrap_or_default();
+        block.total_difficulty = Some(raw.total_difficulty);
+        block.transactions = decode_body(&raw.body).map_err(decode_error)?;
+        for (index, tx) in block.transactions.iter_mut().enumerate() {
+            tx.block_hash = Some(hash);
+            tx.block_number = Some(U256::from(number));
+            tx.transaction_index = Some(U256::from(index));
+        }
+        self.hashes.borrow_mut().insert(hash, number);
+        Ok(Some((block, raw)))
+    }
+}
+
+impl Provider for Era1Provider {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        let Some(number) = self.resolve(block_id)? else {
+            return Ok(None);
+        };
+        Ok(self.decoded(number)?.map(|(block, _)| block))
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        let Some(number) = self.resolve(block_id)? else {
+            return Ok(None);
+        };
+        match self.decoded(number)? {
+            Some((block, raw)) => decode_receipts(&raw.receipts, &block)
+                .map(Some)
+                .map_err(decode_error),
+            None => Ok(None),
+        }
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.chain_id.ok_or(Error::Unsupported)
+    }
+}
+
+/// Maps a file error to a provider error.
+fn io_error(e: io::Error) -> Error {
+    Error::Transport(e.to_string())
+}
+
+/// Maps an rlp error to a provider error.
+fn decode_error(e: rlp::DecoderError) -> Error {
+    Error::Decode(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use e2store::Record;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::vec;
+
+    use axos_primitives::{keccak256, Address, Filter};
+    use k256::ecdsa::SigningKey;
+    use rlp::RlpStream;
+
+    const CHAIN_ID: u64 = 1;
+    const BASE_FEE: u64 = 7;
+
+    fn key() -> SigningKey {
+        SigningKey::from_slice(&[0x42; 32]).unwrap()
+    }
+
+    fn sender() -> Address {
+        let point = key().verifying_key().to_encoded_point(false);
+        Address::from_slice(&keccak256(&point.as_bytes()[1..])[12..])
+    }
+
+    /// Signs a hash and returns the recovery parity and the trimmed r and s.
+    fn sign(preimage: &[u8]) -> (u64, Vec<u8>, Vec<u8>) {
+        let (signature, recovery_id) = key()
+            .sign_prehash_recoverable(keccak256(preimage).as_slice())
+            .unwrap();
+        let bytes = signature.to_bytes();
+        let r = U256::from_be_slice(&bytes[..32]).to_be_bytes_trimmed_vec();
+        let s = U256::from_be_slice(&bytes[32..]).to_be_bytes_trimmed_vec();
+        (u64::from(recovery_id.is_y_odd()), r, s)
+    }
+
+    /// Appends the unsigned fields shared by every transaction type.
+    fn append_call(stream: &mut RlpStream, nonce: u64, to: Address) {
+        stream
+            .append(&21_000u64)
+            .append(&to.as_slice())
+            .append(&nonce)
+            .append(&vec![nonce as u8; 4]);
+    }
+
+    fn dynamic_fee_tx(nonce: u64, to: Address) -> Vec<u8> {
+        let unsigned = |stream: &mut RlpStream| {
+            stream
+                .append(&CHAIN_ID)
+                .append(&nonce)
+                .append(&2u64)
+                .append(&10u64);
+            append_call(stream, nonce, to);
+            stream.begin_list(0);
+        };
+        let mut stream = RlpStream::new_list(9);
+        unsigned(&mut stream);
+        let preimage = [&[2u8][..], &stream.out()[..]].concat();
+        let (parity, r, s) = sign(&preimage);
+        let mut stream = RlpStream::new_list(12);
+        unsigned(&mut stream);
+        stream.append(&parity).append(&r).append(&s);
+        [&[2u8][..], &stream.out()[..]].concat()
+    }
+
+    fn legacy_tx(nonce: u64, to: Address) -> Vec<u8> {
+        let unsigned = |stream: &mut RlpStream| {
+            stream.append(&nonce).append(&9u64);
+            append_call(stream, nonce, to);
+        };
+        let mut stream = RlpStream::new_list(9);
+        unsigned(&mut stream);
+        stream.append(&CHAIN_ID).append(&0u8).append(&0u8);
+        let (parity, r, s) = sign(&stream.out());
+        let mut stream = RlpStream::new_list(9);
+        unsigned(&mut stream);
+        stream
+            .append(&(parity + 35 + 2 * CHAIN_ID))
+            .append(&r)
+            .append(&s);
+        stream.out().to_vec()
+    }
+
+    fn header(number: u64, parent: B256) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(16);
+        stream
+            .append(&parent.as_slice())
+            .append(&[0x1du8; 32].as_slice())
+            .append(&[0xc0u8; 20].as_slice())
+            .append(&[0x01u8; 32].as_slice())
+            .append(&[0x02u8; 32].as_slice())
+            .append(&[0x03u8; 32].as_slice())
+            .append(&[0u8; 256].as_slice())
+            .append(&1u64)
+            .append(&number)
+            .append(&30_000_000u64)
+            .append(&42_000u64)
+            .append(&(1_600_000_000 + number * 12))
+            .append(&b"axos".as_slice())
+            .append(&[0u8; 32].as_slice())
+            .append(&[0u8; 8].as_slice())
+            .append(&BASE_FEE);
+        stream.out().to_vec()
+    }
+
+    /// Builds a block whose transactions each emit one log.
+    fn block(number: u64, parent: B256) -> Era1Block {
+        let to = Address::repeat_byte(0xaa);
+        let txs = [
+            dynamic_fee_tx(2 * number, to),
+            legacy_tx(2 * number + 1, to),
+        ];
+        let mut body = RlpStream::new_list(2);
+        body.begin_list(txs.len());
+        body.append(&txs[0]).append_raw(&txs[1], 1);
+        body.begin_list(0);
+
+        let mut receipts = RlpStream::new_list(txs.len());
+        for (index, tx) in txs.iter().enumerate() {
+            let mut receipt = RlpStream::new_list(4);
+            receipt
+                .append(&1u8)
+                .append(&(21_000 * (index as u64 + 1)))
+                .append(&[0u8; 256].as_slice());
+            receipt.begin_list(1).begin_list(3);
+            receipt.append(&to.as_slice());
+            receipt.begin_list(1).append(&keccak256(tx).as_slice());
+            receipt.append(&vec![index as u8]);
+            match index {
+                0 => receipts.append(&[&[2u8][..], &receipt.out()[..]].concat()),
+                _ => receipts.append_raw(&receipt.out(), 1),
+            };
+        }
+        Era1Block {
+            header: header(number, parent),
+            body: body.out().to_vec(),
+            receipts: receipts.out().to_vec(),
+            total_difficulty: U256::from(100 + number),
+        }
+    }
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+        encoder.write_all(data).unwrap();
+        encoder.into_inner().unwrap()
+    }
+
+    /// Writes an era1 file holding `count` blocks from `start`.
+    fn write_era1(name: &str, start: u64, count: u64, parent: B256) -> (PathBuf, B256) {
+        let dir =
+            std::env::temp_dir().join(std::format!("axos-era-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(std::format!("mainnet-{:05}.era1", start));
+        let mut out = Vec::new();
+        Record::new(e2store::VERSION, Vec::new())
+            .write(&mut out)
+            .unwrap();
+        let mut offsets = Vec::new();
+        let mut records = Vec::new();
+        let mut parent = parent;
+        for number in start..start + count {
+            let block = block(number, parent);
+            parent = keccak256(&block.header);
+            records.push((parent, block.total_difficulty));
+            offsets.push(out.len() as i64);
+            Record::new(e2store::COMPRESSED_HEADER, compress(&block.header))
+                .write(&mut out)
+                .unwrap();
+            Record::new(e2store::COMPRESSED_BODY, compress(&block.body))
+                .write(&mut out)
+                .unwrap();
+            Record::new(e2store::COMPRESSED_RECEIPTS, compress(&block.receipts))
+                .write(&mut out)
+                .unwrap();
+            Record::new(
+                e2store::TOTAL_DIFFICULTY,
+                block.total_difficulty.to_le_bytes::<32>(),
+            )
+            .write(&mut out)
+            .unwrap();
+        }
+        Record::new(e2store::ACCUMULATOR, accumulator_root(&records).as_slice())
+            .write(&mut out)
+            .unwrap();
+        let index_offset = out.len() as i64;
+        let mut index = start.to_le_bytes().to_vec();
+        for offset in offsets {
+            index.extend_from_slice(&(offset - index_offset).to_le_bytes());
+        }
+        index.extend_from_slice(&count.to_le_bytes());
+        Record::new(e2store::BLOCK_INDEX, index)
+            .write(&mut out)
+            .unwrap();
+        fs::write(&path, out).unwrap();
+        (dir, parent)
+    }
+
+    #[test]
+    fn test_era1_blocks() {
+        let (dir, _) = write_era1("blocks", 0, 3, B256::ZERO);
+        let provider = Era1Provider::open_dir(&dir)
+            .unwrap()
+            .with_chain_id(CHAIN_ID);
+        assert_eq!(provider.earliest(), Some(0));
+        assert_eq!(provider.latest(), Some(2));
+        assert_eq!(provider.chain_id(), Ok(CHAIN_ID));
+        assert!(provider.files()[0].verify_accumulator().unwrap());
+
+        let block = provider
+            .get_block_with_txs(BlockId::Number(1))
+            .unwrap()
+            .unwrap();
+        let parent = provider
+            .get_block_with_txs(BlockId::Kind(BlockKind::Earliest))
+            .unwrap();
+        assert_eq!(parent.unwrap().hash, Some(block.parent_hash));
+        assert_eq!(block.number.unwrap().to::<u64>(), 1);
+        assert_eq!(block.base_fee_per_gas, Some(U256::from(BASE_FEE)));
+        assert_eq!(block.total_difficulty, Some(U256::from(101)));
+        assert_eq!(block.gas_limit, Some(U256::from(30_000_000)));
+        assert_eq!(block.nonce, Some(Default::default()));
+        assert_eq!(block.transactions.len(), 2);
+        for (index, tx) in block.transactions.iter().enumerate() {
+            assert_eq!(tx.from, sender());
+            assert_eq!(tx.chain_id, Some(U256::from(CHAIN_ID)));
+            assert_eq!(tx.nonce, U256::from(2 + index));
+            assert_eq!(tx.block_hash, block.hash);
+            assert_eq!(tx.transaction_index, Some(U256::from(index)));
+        }
+        assert_eq!(
+            block.transactions[0].transaction_type.unwrap().to::<u64>(),
+            2
+        );
+        assert_eq!(
+            block.transactions[1].transaction_type.unwrap().to::<u64>(),
+            0
+        );
+        assert_eq!(block.transactions[0].access_list, Some(vec![]));
+        assert_eq!(block.transactions[1].access_list, None);
+
+        let by_hash = provider.get_block_with_txs(BlockId::Hash(block.hash.unwrap()));
+        assert_eq!(by_hash.unwrap(), Some(block));
+        let latest = provider.get_block_with_txs(BlockId::Kind(BlockKind::Finalized));
+        assert_eq!(latest.unwrap().unwrap().number.unwrap().to::<u64>(), 2);
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(3)), Ok(None));
+        assert_eq!(
+            provider.get_block_with_txs(BlockId::Hash(B256::ZERO)),
+            Ok(None)
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_era1_receipts_and_logs() {
+        let (dir, _) = write_era1("receipts", 0, 2, B256::ZERO);
+        let provider = Era1Provider::open_dir(&dir).unwrap();
+        let block = provider
+            .get_block_with_txs(BlockId::Number(1))
+            .unwrap()
+            .unwrap();
+        let receipts = provider.get_receipts(BlockId::Number(1)).unwrap().unwrap();
+        assert_eq!(receipts.len(), 2);
+        for (index, receipt) in receipts.iter().enumerate() {
+            let tx = &block.transactions[index];
+            assert_eq!(receipt.transaction_hash, tx.hash);
+            assert_eq!(receipt.from, sender());
+            assert_eq!(receipt.gas_used, Some(U256::from(21_000)));
+            assert_eq!(receipt.status.unwrap().to::<u64>(), 1);
+            assert_eq!(receipt.logs[0].topics, vec![tx.hash]);
+            assert_eq!(receipt.logs[0].log_index, Some(U256::from(index)));
+        }
+        // The dynamic fee transaction pays the base fee plus its tip.
+        assert_eq!(
+            receipts[0].effective_gas_price,
+            Some(U256::from(BASE_FEE + 2))
+        );
+        assert_eq!(receipts[1].effective_gas_price, Some(U256::from(9)));
+
+        let filter = Filter {
+            block_hash: block.hash,
+            ..Default::default()
+        };
+        assert_eq!(provider.get_logs(&filter).unwrap().len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_era1_multiple_files() {
+        let (dir, parent) = write_era1("multi", 0, 2, B256::ZERO);
+        let (other, _) = write_era1("multi", 2, 2, parent);
+        assert_eq!(dir, other);
+        let provider = Era1Provider::open_dir(&dir).unwrap();
+        assert_eq!(provider.files().len(), 2);
+        assert_eq!(provider.latest(), Some(3));
+
+        let hash = provider.files()[1].block_hash(3).unwrap().unwrap();
+        let block = provider
+            .get_block_with_txs(BlockId::Hash(hash))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.number.unwrap().to::<u64>(), 3);
+        let first = provider
+            .get_block_with_txs(BlockId::Number(2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.parent_hash, parent);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_era1_tampered_accumulator() {
+        let (dir, _) = write_era1("tampered", 0, 2, B256::ZERO);
+        let path = dir.join("mainnet-00000.era1");
+        let mut data = fs::read(&path).unwrap();
+        // The accumulator root sits right before the 40 byte block index.
+        let root = data.len() - 40 - 32;
+        data[root] ^= 0xff;
+        fs::write(&path, data).unwrap();
+        let file = Era1File::open(&path).unwrap();
+        assert!(!file.verify_accumulator().unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,245 @@
+//! Block Decoding
+//!
+//! Decodes the consensus rlp encoding of headers, bodies and receipts
+//! into the json-rpc shaped types the [Provider][crate::provider::Provider]
+//! trait returns. Transaction senders are recovered from their signatures.
+
+use std::vec::Vec;
+
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use rlp::{DecoderError, Rlp, RlpStream};
+
+use axos_primitives::transactions::{AccessListItem, Transaction};
+use axos_primitives::{
+    keccak256, Address, BlockWithTransactions, Bytes, TransactionReceipt, B256, U256, U64,
+};
+
+/// The index of the base fee in a London header.
+const BASE_FEE_INDEX: usize = 15;
+
+/// Decodes an rlp header into a block without transactions.
+pub fn decode_header(header: &[u8]) -> Result<BlockWithTransactions, DecoderError> {
+    let rlp = Rlp::new(header);
+    let fields = rlp.item_count()?;
+    if fields < BASE_FEE_INDEX {
+        return Err(DecoderError::RlpIncorrectListLen);
+    }
+    Ok(BlockWithTransactions {
+        hash: Some(keccak256(header)),
+        parent_hash: b256(&rlp, 0)?,
+        uncles_hash: Some(b256(&rlp, 1)?),
+        author: address(&rlp, 2)?,
+        state_root: b256(&rlp, 3)?,
+        transactions_root: b256(&rlp, 4)?,
+        receipts_root: b256(&rlp, 5)?,
+        logs_bloom: Some(
+            rlp.at(6)?
+                .data()?
+                .try_into()
+                .map_err(|_| DecoderError::RlpInvalidLength)?,
+        ),
+        difficulty: Some(u256(&rlp, 7)?),
+        number: Some(u256(&rlp, 8)?.saturating_to()),
+        gas_limit: Some(u256(&rlp, 9)?),
+        gas_used: u256(&rlp, 10)?,
+        timestamp: u256(&rlp, 11)?,
+        extra_data: Bytes::copy_from_slice(rlp.at(12)?.data()?),
+        mix_hash: Some(b256(&rlp, 13)?),
+        nonce: Some(
+            rlp.at(14)?
+                .data()?
+                .try_into()
+                .map_err(|_| DecoderError::RlpInvalidLength)?,
+        ),
+        seal_fields: std::vec![
+            Bytes::copy_from_slice(rlp.at(13)?.data()?),
+            Bytes::copy_from_slice(rlp.at(14)?.data()?),
+        ],
+        base_fee_per_gas: match fields > BASE_FEE_INDEX {
+            true => Some(u256(&rlp, BASE_FEE_INDEX)?),
+            false => None,
+        },
+        size: Some(U256::from(header.len())),
+        ..Default::default()
+    })
+}
+
+/// Decodes the transactions of an rlp block body.
+pub fn decode_body(body: &[u8]) -> Result<Vec<Transaction>, DecoderError> {
+    let rlp = Rlp::new(body);
+    rlp.at(0)?
+        .iter()
+        .map(|tx| match tx.is_list() {
+            true => decode_transaction(tx.as_raw()),
+            false => decode_transaction(tx.data()?),
+        })
+        .collect()
+}
+
+/// Decodes a transaction from its consensus encoding and recovers its
+/// sender.
+pub fn decode_transaction(raw: &[u8]) -> Result<Transaction, DecoderError> {
+    let hash = keccak256(raw);
+    let (kind, payload) = match raw.first() {
+        Some(&byte) if byte >= 0xc0 => (None, raw),
+        Some(&kind @ (1 | 2)) => (Some(kind), &raw[1..]),
+        _ => return Err(DecoderError::Custom("unsupported transaction type")),
+    };
+    let rlp = Rlp::new(payload);
+    let fields = rlp.item_count()?;
+    if fields < 9 {
+        return Err(DecoderError::RlpIncorrectListLen);
+    }
+
+    // Legacy and access list transactions share a layout after the chain
+    // id of the latter, which dynamic fee transactions extend with two fees.
+    let offset = usize::from(kind.is_some());
+    let signed = fields - 3;
+    let mut tx = Transaction {
+        hash,
+        nonce: u256(&rlp, offset)?,
+        transaction_type: Some(U64::from(kind.unwrap_or_default())),
+        ..Default::default()
+    };
+    let rest = match kind {
+        Some(2) => {
+            tx.max_priority_fee_per_gas = Some(u256(&rlp, 2)?);
+            tx.max_fee_per_gas = Some(u256(&rlp, 3)?);
+            4
+        }
+        _ => {
+            tx.gas_price = Some(u256(&rlp, offset + 1)?);
+            offset + 2
+        }
+    };
+    tx.gas = u256(&rlp, rest)?;
+    tx.to = address(&rlp, rest + 1)?;
+    tx.value = u256(&rlp, rest + 2)?;
+    tx.input = Bytes::copy_from_slice(rlp.at(rest + 3)?.data()?);
+    if kind.is_some() {
+        tx.access_list = Some(access_list(&rlp.at(rest + 4)?)?);
+    }
+    let v = u256(&rlp, signed)?.saturating_to::<u64>();
+    tx.v = U64::from(v);
+    tx.r = u256(&rlp, signed + 1)?;
+    tx.s = u256(&rlp, signed + 2)?;
+
+    // Recompute the hash the sender signed: typed transactions sign their
+    // fields behind the type byte, legacy ones may commit to a chain id.
+    let (preimage, parity) = match kind {
+        Some(kind) => {
+            tx.chain_id = Some(u256(&rlp, 0)?);
+            let mut stream = RlpStream::new_list(signed);
+            append_fields(&mut stream, &rlp, signed)?;
+            let mut preimage = std::vec![kind];
+            preimage.extend_from_slice(&stream.out());
+            (preimage, v)
+        }
+        None if v >= 35 => {
+            let chain_id = (v - 35) / 2;
+            tx.chain_id = Some(U256::from(chain_id));
+            let mut stream = RlpStream::new_list(signed + 3);
+            append_fields(&mut stream, &rlp, signed)?;
+            stream.append(&chain_id).append(&0u8).append(&0u8);
+            (stream.out().to_vec(), (v - 35) % 2)
+        }
+        None => {
+            let mut stream = RlpStream::new_list(signed);
+            append_fields(&mut stream, &rlp, signed)?;
+            (stream.out().to_vec(), v.saturating_sub(27))
+        }
+    };
+    tx.from = recover_signer(keccak256(preimage), parity, tx.r, tx.s)?;
+    Ok(tx)
+}
+
+/// Decodes rlp receipts, filling in the block and transaction context.
+pub fn decode_receipts(
+    receipts: &[u8],
+    block: &BlockWithTransactions,
+) -> Result<Vec<TransactionReceipt>, DecoderError> {
+    // Typed receipts are wrapped in an rlp string in the network encoding.
+    let raw = Rlp::new(receipts)
+        .iter()
+        .map(|item| match item.is_list() {
+            true => Ok(item.as_raw()),
+            false => item.data(),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    crate::receipts::decode_receipts(raw.into_iter(), block)
+}
+
+/// Recovers the address that signed the given hash.
+fn recover_signer(hash: B256, parity: u64, r: U256, s: U256) -> Result<Address, DecoderError> {
+    let invalid = |_| DecoderError::Custom("invalid transaction signature");
+    let signature =
+        Signature::from_scalars(r.to_be_bytes::<32>(), s.to_be_bytes::<32>()).map_err(invalid)?;
+    let mut recovery_id = RecoveryId::new(parity == 1, false);
+    // Signatures from before homestead may carry a high s value.
+    let signature = match signature.normalize_s() {
+        Some(normalized) => {
+            recovery_id = RecoveryId::new(parity != 1, false);
+            normalized
+        }
+        None => signature,
+    };
+    let key = VerifyingKey::recover_from_prehash(hash.as_slice(), &signature, recovery_id)
+        .map_err(invalid)?;
+    let point = key.to_encoded_point(false);
+    Ok(Address::from_slice(
+        &keccak256(&point.as_bytes()[1..])[12..],
+    ))
+}
+
+/// Decodes an access list.
+fn access_list(rlp: &Rlp<'_>) -> Result<Vec<AccessListItem>, DecoderError> {
+    rlp.iter()
+        .map(|item| {
+            Ok(AccessListItem {
+                address: address(&item, 0)?.unwrap_or_default(),
+                storage_keys: item
+                    .at(1)?
+                    .iter()
+                    .map(|key| key.data().and_then(to_b256))
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect()
+}
+
+/// Appends the first `count` fields of a list to a stream as is.
+fn append_fields(stream: &mut RlpStream, rlp: &Rlp<'_>, count: usize) -> Result<(), DecoderError> {
+    for index in 0..count {
+        stream.append_raw(rlp.at(index)?.as_raw(), 1);
+    }
+    Ok(())
+}
+
+/// Decodes a big-endian integer field.
+fn u256(rlp: &Rlp<'_>, index: usize) -> Result<U256, DecoderError> {
+    let data = rlp.at(index)?.data()?;
+    if data.len() > 32 {
+        return Err(DecoderError::RlpIsTooBig);
+    }
+    Ok(U256::from_be_slice(data))
+}
+
+/// Decodes a 32 byte hash field.
+fn b256(rlp: &Rlp<'_>, index: usize) -> Result<B256, DecoderError> {
+    to_b256(rlp.at(index)?.data()?)
+}
+
+/// Converts 32 bytes into a hash.
+fn to_b256(data: &[u8]) -> Result<B256, DecoderError> {
+    B256::try_from(data).map_err(|_| DecoderError::RlpInvalidLength)
+}
+
+/// Decodes an address field, which is empty for contract creations.
+fn address(rlp: &Rlp<'_>, index: usize) -> Result<Option<Address>, DecoderError> {
+    match rlp.at(index)?.data()? {
+        [] => Ok(None),
+        data => Address::try_from(data)
+            .map(Some)
+            .map_err(|_| DecoderError::RlpInvalidLength),
+    }
+}
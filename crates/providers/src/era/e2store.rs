@@ -0,0 +1,124 @@
+//! E2Store Records
+//!
+//! An e2store file is a flat sequence of records. Each record starts with
+//! an 8 byte header: a little-endian `u16` type, a little-endian `u32`
+//! data length and two reserved zero bytes.
+
+use std::io::{self, Read, Write};
+use std::vec;
+use std::vec::Vec;
+
+/// The version record that starts every e2store file.
+pub const VERSION: u16 = 0x3265;
+/// A snappy-compressed, rlp-encoded block header.
+pub const COMPRESSED_HEADER: u16 = 0x03;
+/// A snappy-compressed, rlp-encoded block body.
+pub const COMPRESSED_BODY: u16 = 0x04;
+/// Snappy-compressed, rlp-encoded block receipts.
+pub const COMPRESSED_RECEIPTS: u16 = 0x05;
+/// The little-endian total difficulty of a block.
+pub const TOTAL_DIFFICULTY: u16 = 0x06;
+/// The root of the header accumulator of an era.
+pub const ACCUMULATOR: u16 = 0x07;
+/// The block index of an era.
+pub const BLOCK_INDEX: u16 = 0x3266;
+
+/// The size of a record header in bytes.
+pub const HEADER_SIZE: u64 = 8;
+
+/// An e2store record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// The record type.
+    pub kind: u16,
+    /// The record data.
+    pub data: Vec<u8>,
+}
+
+impl Record {
+    /// Instantiates a new [Record].
+    pub fn new(kind: u16, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            kind,
+            data: data.into(),
+        }
+    }
+
+    /// Reads the next record. Returns [None] at the end of the reader.
+    pub fn read(reader: &mut impl Read) -> io::Result<Option<Self>> {
+        let mut header = [0u8; HEADER_SIZE as usize];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let kind = u16::from_le_bytes([header[0], header[1]]);
+        let length = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+        if header[6..] != [0, 0] {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "reserved e2store header bytes are not zero",
+            ));
+        }
+        let mut data = vec![0u8; length as usize];
+        reader.read_exact(&mut data)?;
+        Ok(Some(Self { kind, data }))
+    }
+
+    /// Reads the next record and checks its type.
+    pub fn read_kind(reader: &mut impl Read, kind: u16) -> io::Result<Self> {
+        let record = Self::read(reader)?.ok_or(io::ErrorKind::UnexpectedEof)?;
+        if record.kind != kind {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                std::format!(
+                    "expected e2store record {:#06x}, found {:#06x}",
+                    kind,
+                    record.kind
+                ),
+            ));
+        }
+        Ok(record)
+    }
+
+    /// Writes the record.
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let length = u32::try_from(self.data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "e2store record too large"))?;
+        writer.write_all(&self.kind.to_le_bytes())?;
+        writer.write_all(&length.to_le_bytes())?;
+        writer.write_all(&[0, 0])?;
+        writer.write_all(&self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_roundtrip() {
+        let mut buf = Vec::new();
+        Record::new(VERSION, Vec::new()).write(&mut buf).unwrap();
+        Record::new(TOTAL_DIFFICULTY, [1u8; 32])
+            .write(&mut buf)
+            .unwrap();
+        assert_eq!(&buf[..8], &[0x65, 0x32, 0, 0, 0, 0, 0, 0]);
+
+        let mut reader = buf.as_slice();
+        assert_eq!(
+            Record::read(&mut reader).unwrap(),
+            Some(Record::new(VERSION, Vec::new()))
+        );
+        let td = Record::read_kind(&mut reader, TOTAL_DIFFICULTY).unwrap();
+        assert_eq!(td.data, [1u8; 32]);
+        assert_eq!(Record::read(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_record_wrong_kind() {
+        let mut buf = Vec::new();
+        Record::new(ACCUMULATOR, [0u8; 32]).write(&mut buf).unwrap();
+        assert!(Record::read_kind(&mut buf.as_slice(), BLOCK_INDEX).is_err());
+    }
+}
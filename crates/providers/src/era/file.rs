@@ -0,0 +1,242 @@
+//! Era1 Files
+//!
+//! An era1 file holds up to [MAX_ERA_BLOCKS] consecutive pre-merge blocks:
+//!
+//! ```text
+//! era1 := Version | block-tuple* | Accumulator | BlockIndex
+//! block-tuple := CompressedHeader | CompressedBody | CompressedReceipts | TotalDifficulty
+//! BlockIndex := starting-number | offset* | count
+//! ```
+//!
+//! Block offsets in the index are relative to the start of the index
+//! record, so a block is read with a single seek.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+
+use sha2::{Digest, Sha256};
+
+use axos_primitives::{keccak256, B256, U256};
+
+use super::e2store::{self, Record};
+
+/// The maximum number of blocks in an era.
+pub const MAX_ERA_BLOCKS: u64 = 8192;
+
+/// The raw records of a block in an era1 file, decompressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The rlp-encoded header.
+    pub header: Vec<u8>,
+    /// The rlp-encoded body.
+    pub body: Vec<u8>,
+    /// The rlp-encoded receipts.
+    pub receipts: Vec<u8>,
+    /// The total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+/// An open era1 file.
+#[derive(Debug)]
+pub struct Era1File {
+    /// The path of the file.
+    path: PathBuf,
+    /// The open file.
+    file: File,
+    /// The number of the first block.
+    start: u64,
+    /// The absolute offset of each block.
+    offsets: Vec<u64>,
+    /// The header accumulator root.
+    accumulator: B256,
+}
+
+impl Era1File {
+    /// Opens an era1 file and reads its block index.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = File::open(&path)?;
+        Record::read_kind(&mut file, e2store::VERSION)?;
+
+        let length = file.metadata()?.len();
+        let count = read_u64_at(&mut file, length.checked_sub(8).ok_or_else(truncated)?)?;
+        let index_offset = count
+            .checked_mul(8)
+            .and_then(|n| length.checked_sub(n + 3 * 8))
+            .ok_or_else(truncated)?;
+        file.seek(SeekFrom::Start(index_offset))?;
+        let index = Record::read_kind(&mut file, e2store::BLOCK_INDEX)?;
+        if index.data.len() as u64 != 16 + count * 8 {
+            return Err(invalid("block index length does not match its count"));
+        }
+        let start = u64::from_le_bytes(index.data[..8].try_into().expect("8 bytes"));
+        let offsets = index.data[8..index.data.len() - 8]
+            .chunks_exact(8)
+            .map(|o| index_offset.wrapping_add(u64::from_le_bytes(o.try_into().expect("8 bytes"))))
+            .collect();
+
+        let accumulator_offset = index_offset
+            .checked_sub(e2store::HEADER_SIZE + 32)
+            .ok_or_else(truncated)?;
+        file.seek(SeekFrom::Start(accumulator_offset))?;
+        let accumulator = Record::read_kind(&mut file, e2store::ACCUMULATOR)?;
+        let accumulator = B256::try_from(accumulator.data.as_slice())
+            .map_err(|_| invalid("accumulator root is not 32 bytes"))?;
+
+        Ok(Self {
+            path,
+            file,
+            start,
+            offsets,
+            accumulator,
+        })
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of the first block.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the number of blocks.
+    pub fn len(&self) -> u64 {
+        self.offsets.len() as u64
+    }
+
+    /// Returns true if the file holds no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns the number of the last block, if any.
+    pub fn end(&self) -> Option<u64> {
+        (self.start + self.len())
+            .checked_sub(1)
+            .filter(|_| !self.is_empty())
+    }
+
+    /// Returns true if the file holds the given block.
+    pub fn contains(&self, number: u64) -> bool {
+        number >= self.start && number - self.start < self.len()
+    }
+
+    /// Returns the header accumulator root stored in the file.
+    pub fn accumulator(&self) -> B256 {
+        self.accumulator
+    }
+
+    /// Reads the block with the given number, if the file holds it.
+    pub fn block(&self, number: u64) -> io::Result<Option<Era1Block>> {
+        if !self.contains(number) {
+            return Ok(None);
+        }
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(
+            self.offsets[(number - self.start) as usize],
+        ))?;
+        let header = decompress(&Record::read_kind(&mut file, e2store::COMPRESSED_HEADER)?)?;
+        let body = decompress(&Record::read_kind(&mut file, e2store::COMPRESSED_BODY)?)?;
+        let receipts = decompress(&Record::read_kind(&mut file, e2store::COMPRESSED_RECEIPTS)?)?;
+        let total_difficulty = Record::read_kind(&mut file, e2store::TOTAL_DIFFICULTY)?;
+        Ok(Some(Era1Block {
+            header,
+            body,
+            receipts,
+            total_difficulty: U256::try_from_le_slice(&total_difficulty.data)
+                .ok_or_else(|| invalid("total difficulty is longer than 32 bytes"))?,
+        }))
+    }
+
+    /// Reads the header hash of the block with the given number.
+    pub fn block_hash(&self, number: u64) -> io::Result<Option<B256>> {
+        if !self.contains(number) {
+            return Ok(None);
+        }
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(
+            self.offsets[(number - self.start) as usize],
+        ))?;
+        let header = decompress(&Record::read_kind(&mut file, e2store::COMPRESSED_HEADER)?)?;
+        Ok(Some(keccak256(header)))
+    }
+
+    /// Recomputes the header accumulator root from every block in the
+    /// file and checks it against the stored root.
+    pub fn verify_accumulator(&self) -> io::Result<bool> {
+        let mut records = Vec::with_capacity(self.offsets.len());
+        for number in self.start..self.start + self.len() {
+            let block = self.block(number)?.ok_or_else(truncated)?;
+            records.push((keccak256(&block.header), block.total_difficulty));
+        }
+        Ok(accumulator_root(&records) == self.accumulator)
+    }
+}
+
+/// Computes the ssz hash tree root of a list of header records, each a
+/// block hash and total difficulty, with a limit of [MAX_ERA_BLOCKS].
+pub fn accumulator_root(records: &[(B256, U256)]) -> B256 {
+    let leaves = records
+        .iter()
+        .map(|(hash, td)| sha256(hash.as_slice(), &td.to_le_bytes::<32>()))
+        .collect::<Vec<_>>();
+    let root = merkleize(leaves, MAX_ERA_BLOCKS.trailing_zeros());
+    let mut length = [0u8; 32];
+    length[..8].copy_from_slice(&(records.len() as u64).to_le_bytes());
+    B256::from(sha256(&root, &length))
+}
+
+/// Merkleizes the chunks into a tree of the given depth, padding with
+/// zero chunks.
+fn merkleize(mut layer: Vec<[u8; 32]>, depth: u32) -> [u8; 32] {
+    let mut zero = [0u8; 32];
+    for _ in 0..depth {
+        if layer.len() % 2 == 1 {
+            layer.push(zero);
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| sha256(&pair[0], &pair[1]))
+            .collect();
+        zero = sha256(&zero, &zero);
+    }
+    layer.first().copied().unwrap_or(zero)
+}
+
+/// Hashes two chunks together.
+fn sha256(left: &[u8], right: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Decompresses a snappy framed record.
+fn decompress(record: &Record) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    snap::read::FrameDecoder::new(record.data.as_slice()).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Reads a little-endian `u64` at the given offset.
+fn read_u64_at(file: &mut File, offset: u64) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// An error for a file that ends early.
+fn truncated() -> io::Error {
+    invalid("era1 file is truncated")
+}
+
+/// An error for a malformed file.
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,64 @@
+//! Executor
+//!
+//! A minimal, allocation-free executor used to run asynchronous I/O
+//! from synchronous (purple) functions. The executor busy-polls its
+//! future with a no-op waker, so it is best suited to I/O stacks that
+//! make progress on every poll, such as blocking or interrupt-driven
+//! network drivers.
+
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+/// The no-op waker vtable.
+const NOOP_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(|_| noop_raw_waker(), |_| {}, |_| {}, |_| {});
+
+/// Returns a [RawWaker] that does nothing when woken.
+const fn noop_raw_waker() -> RawWaker {
+    RawWaker::new(core::ptr::null(), &NOOP_VTABLE)
+}
+
+/// Runs a future to completion on the current thread.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    // SAFETY: the no-op vtable never dereferences the null data pointer.
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A future that is pending for a fixed number of polls.
+    struct Countdown(u8);
+
+    impl Future for Countdown {
+        type Output = &'static str;
+
+        fn poll(mut self: core::pin::Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.0 == 0 {
+                return Poll::Ready("done");
+            }
+            self.0 -= 1;
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn test_block_on_ready() {
+        assert_eq!(block_on(async { 7 }), 7);
+    }
+
+    #[test]
+    fn test_block_on_pending() {
+        assert_eq!(block_on(Countdown(3)), "done");
+    }
+}
@@ -0,0 +1,305 @@
+//! HTTP Provider
+//!
+//! The http provider is a blocking [Provider] that talks to an Ethereum
+//! json-rpc endpoint over HTTP. It requires the `std` feature.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use axos_providers::http::HttpProvider;
+//! use axos_providers::provider::Provider;
+//! use axos_primitives::{BlockId, BlockKind};
+//!
+//! let provider = HttpProvider::new("http://localhost:8545");
+//! let block = provider.get_block_with_txs(BlockId::Kind(BlockKind::Finalized));
+//! ```
+
+use std::format;
+use std::io::{self, Read};
+use std::string::{String, ToString};
+use std::time::Duration;
+use std::vec::Vec;
+
+use crate::provider::Error;
+use crate::rpc::JsonRpcProvider;
+use crate::transport::Transport;
+
+/// An HTTP json-rpc provider.
+pub type HttpProvider = JsonRpcProvider<HttpTransport>;
+
+impl HttpProvider {
+    /// Instantiates a new [HttpProvider] for the given json-rpc url.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_transport(HttpTransport::new(url))
+    }
+}
+
+/// A blocking HTTP [Transport].
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    /// The url of the json-rpc endpoint.
+    pub url: String,
+    /// The internal HTTP agent.
+    agent: ureq::Agent,
+}
+
+impl HttpTransport {
+    /// Instantiates a new [HttpTransport] for the given url.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    /// Sets the overall timeout for each request.
+    /// Requests that exceed it fail with [Error::Timeout].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.agent = ureq::AgentBuilder::new().timeout(timeout).build();
+        self
+    }
+}
+
+impl Transport for HttpTransport {
+    fn send(&self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        let response = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_bytes(request)
+            .map_err(|e| {
+                tracing::warn!(target: "http_provider", "request to {} failed: {}", self.url, e);
+                transport_error(e)
+            })?;
+        let mut body = Vec::new();
+        response.into_reader().read_to_end(&mut body).map_err(|e| {
+            tracing::warn!(target: "http_provider", "failed to read response: {}", e);
+            io_error(e)
+        })?;
+        Ok(body)
+    }
+}
+
+/// Maps a [ureq::Error] to a provider [Error].
+fn transport_error(error: ureq::Error) -> Error {
+    match error {
+        ureq::Error::Status(429, response) => Error::RateLimited {
+            retry_after: response
+                .header("Retry-After")
+                .and_then(|v| v.trim().parse().ok()),
+        },
+        ureq::Error::Status(status, _) => Error::Transport(format!("http status {}", status)),
+        ureq::Error::Transport(transport) => {
+            let timed_out = std::error::Error::source(&transport)
+                .and_then(|e| e.downcast_ref::<io::Error>())
+                .is_some_and(is_timeout);
+            if timed_out {
+                Error::Timeout
+            } else {
+                Error::Transport(transport.to_string())
+            }
+        }
+    }
+}
+
+/// Maps an [io::Error] to a provider [Error].
+fn io_error(error: io::Error) -> Error {
+    if is_timeout(&error) {
+        Error::Timeout
+    } else {
+        Error::Transport(error.to_string())
+    }
+}
+
+/// Returns true if the io error is a timeout.
+fn is_timeout(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use crate::test_utils::rpc::{rpc_method, rpc_result, BLOCK};
+    use crate::test_utils::{MockResponse, MockServer};
+    use axos_primitives::{Address, BlockId, BlockKind, B256, U64};
+    use serde_json::Value;
+    use std::string::ToString;
+
+    #[test]
+    fn test_get_block_by_number() {
+        let server = MockServer::spawn(|_| rpc_result(BLOCK));
+        let provider = HttpProvider::new(server.url());
+        let block = provider
+            .get_block_with_txs(BlockId::Number(2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.number, Some(U64::from(2)));
+        assert_eq!(block.parent_hash, B256::with_last_byte(1));
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(
+            block.transactions[0].source_hash,
+            B256::with_last_byte(0xbb)
+        );
+        assert_eq!(
+            block.transactions[0].transaction_type,
+            Some(U64::from(0x7e))
+        );
+
+        let request: Value = serde_json::from_slice(&server.requests()[0].body).unwrap();
+        assert_eq!(request["method"], "eth_getBlockByNumber");
+        assert_eq!(request["params"], serde_json::json!(["0x2", true]));
+    }
+
+    #[test]
+    fn test_get_block_by_hash() {
+        let server = MockServer::spawn(|_| rpc_result(BLOCK));
+        let provider = HttpProvider::new(server.url());
+        let hash = B256::with_last_byte(2);
+        let block = provider.get_block_with_txs(BlockId::Hash(hash)).unwrap();
+        assert_eq!(block.unwrap().hash, Some(hash));
+        assert_eq!(rpc_method(&server.requests()[0].body), "eth_getBlockByHash");
+    }
+
+    #[test]
+    fn test_get_block_by_tag() {
+        let server = MockServer::spawn(|_| rpc_result(BLOCK));
+        let provider = HttpProvider::new(server.url());
+        for kind in [BlockKind::Latest, BlockKind::Safe, BlockKind::Finalized] {
+            assert!(provider
+                .get_block_with_txs(BlockId::Kind(kind))
+                .unwrap()
+                .is_some());
+        }
+        let tags = server
+            .requests()
+            .iter()
+            .map(|r| {
+                let request: Value = serde_json::from_slice(&r.body).unwrap();
+                request["params"][0].as_str().unwrap().to_string()
+            })
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(tags, ["latest", "safe", "finalized"]);
+    }
+
+    #[test]
+    fn test_chain_id() {
+        let server = MockServer::spawn(|_| rpc_result(r#""0xa""#));
+        let provider = HttpProvider::new(server.url());
+        assert_eq!(provider.chain_id(), Ok(10));
+        assert_eq!(rpc_method(&server.requests()[0].body), "eth_chainId");
+    }
+
+    #[test]
+    fn test_get_receipts() {
+        let server = MockServer::spawn(|_| {
+            rpc_result(
+                r#"[{
+                    "transactionHash": "0x00000000000000000000000000000000000000000000000000000000000000aa",
+                    "transactionIndex": "0x0",
+                    "cumulativeGasUsed": "0x5208",
+                    "logs": [],
+                    "status": "0x1"
+                }]"#,
+            )
+        });
+        let provider = HttpProvider::new(server.url());
+        let receipts = provider
+            .get_receipts(BlockId::Hash(B256::ZERO))
+            .unwrap()
+            .unwrap();
+        assert_eq!(receipts.len(), 1);
+        assert!(receipts[0].is_success());
+        let request: Value = serde_json::from_slice(&server.requests()[0].body).unwrap();
+        assert_eq!(request["method"], "eth_getBlockReceipts");
+        assert_eq!(
+            request["params"],
+            serde_json::json!([{ "blockHash": B256::ZERO }])
+        );
+    }
+
+    #[test]
+    fn test_get_storage_at() {
+        let server = MockServer::spawn(|_| {
+            rpc_result(r#""0x000000000000000000000000000000000000000000000000000000000000002a""#)
+        });
+        let provider = HttpProvider::new(server.url());
+        let value = provider
+            .get_storage_at(Address::ZERO, B256::ZERO, BlockId::Kind(BlockKind::Latest))
+            .unwrap();
+        assert_eq!(value, B256::with_last_byte(42));
+        let request: Value = serde_json::from_slice(&server.requests()[0].body).unwrap();
+        assert_eq!(request["method"], "eth_getStorageAt");
+        assert_eq!(request["params"][2], "latest");
+    }
+
+    #[test]
+    fn test_block_not_found() {
+        let server = MockServer::spawn(|_| rpc_result("null"));
+        let provider = HttpProvider::new(server.url());
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(9)), Ok(None));
+    }
+
+    #[test]
+    fn test_rpc_error() {
+        let server = MockServer::spawn(|_| {
+            MockResponse::ok(r#"{"jsonrpc":"2.0","id":0,"error":{"code":-32000,"message":"boom"}}"#)
+        });
+        let provider = HttpProvider::new(server.url());
+        assert_eq!(
+            provider.get_block_with_txs(BlockId::Number(9)),
+            Err(Error::Rpc {
+                code: -32000,
+                message: "boom".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_rate_limited() {
+        let server =
+            MockServer::spawn(|_| MockResponse::status(429).with_header("Retry-After", "3"));
+        let provider = HttpProvider::new(server.url());
+        let err = provider.chain_id().unwrap_err();
+        assert_eq!(
+            err,
+            Error::RateLimited {
+                retry_after: Some(3)
+            }
+        );
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_http_status_error() {
+        let server = MockServer::spawn(|_| MockResponse::status(503));
+        let provider = HttpProvider::new(server.url());
+        let err = provider.chain_id().unwrap_err();
+        assert!(matches!(err, Error::Transport(_)));
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = std::format!("http://{}", listener.local_addr().unwrap());
+        let provider = HttpProvider::with_transport(
+            HttpTransport::new(url).with_timeout(Duration::from_millis(100)),
+        );
+        assert_eq!(provider.chain_id(), Err(Error::Timeout));
+        drop(listener);
+    }
+
+    #[test]
+    fn test_invalid_response() {
+        let server = MockServer::spawn(|_| rpc_result(r#"{"number":"not hex"}"#));
+        let provider = HttpProvider::new(server.url());
+        assert!(matches!(
+            provider.get_block_with_txs(BlockId::Number(9)),
+            Err(Error::Decode(_))
+        ));
+    }
+}
@@ -0,0 +1,239 @@
+//! IPC Provider
+//!
+//! The ipc provider is a blocking [Provider][crate::provider::Provider]
+//! that talks json-rpc to a co-located execution client over a Unix
+//! domain socket, such as the `geth.ipc` or `reth.ipc` endpoint. It
+//! requires the `std` feature and a Unix target.
+//!
+//! IPC messages are not length-prefixed, so responses are read until a
+//! complete json value has been received. The connection is opened on
+//! the first request and reused, and re-opened after a failure.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use axos_providers::ipc::IpcProvider;
+//! use axos_providers::provider::Provider;
+//! use axos_primitives::{BlockId, BlockKind};
+//!
+//! let provider = IpcProvider::new("/tmp/reth.ipc");
+//! let block = provider.get_block_with_txs(BlockId::Kind(BlockKind::Latest));
+//! ```
+
+use core::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::string::ToString;
+use std::time::Duration;
+use std::vec::Vec;
+
+use crate::provider::Error;
+use crate::rpc::JsonRpcProvider;
+use crate::transport::Transport;
+
+/// The url scheme of an IPC endpoint.
+pub const IPC_SCHEME: &str = "ipc://";
+
+/// An IPC json-rpc provider.
+pub type IpcProvider = JsonRpcProvider<IpcTransport>;
+
+impl IpcProvider {
+    /// Instantiates a new [IpcProvider] for the given socket path.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self::with_transport(IpcTransport::new(path))
+    }
+}
+
+/// A blocking Unix domain socket [Transport].
+#[derive(Debug)]
+pub struct IpcTransport {
+    /// The path of the socket.
+    path: PathBuf,
+    /// The read and write timeout.
+    timeout: Option<Duration>,
+    /// The open connection, if any.
+    stream: RefCell<Option<BufReader<UnixStream>>>,
+}
+
+impl IpcTransport {
+    /// Instantiates a new [IpcTransport] for the given socket path.
+    /// A leading `ipc://` is stripped from the path.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let path = path
+            .to_str()
+            .and_then(|p| p.strip_prefix(IPC_SCHEME))
+            .map(PathBuf::from)
+            .unwrap_or_else(|| path.to_path_buf());
+        Self {
+            path,
+            timeout: None,
+            stream: RefCell::new(None),
+        }
+    }
+
+    /// Sets the read and write timeout of each request.
+    /// Requests that exceed it fail with [Error::Timeout].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the path of the socket.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Opens a connection to the socket.
+    fn connect(&self) -> io::Result<BufReader<UnixStream>> {
+        let stream = UnixStream::connect(&self.path)?;
+        stream.set_read_timeout(self.timeout)?;
+        stream.set_write_timeout(self.timeout)?;
+        Ok(BufReader::new(stream))
+    }
+
+    /// Writes the request and reads the response over the open
+    /// connection, opening one if needed.
+    fn exchange(
+        &self,
+        stream: &mut Option<BufReader<UnixStream>>,
+        request: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        if stream.is_none() {
+            *stream = Some(self.connect()?);
+        }
+        let reader = stream.as_mut().expect("connection was just opened");
+        reader.get_mut().write_all(request)?;
+        reader.get_mut().flush()?;
+        read_json(reader)?.ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))
+    }
+}
+
+impl Transport for IpcTransport {
+    fn send(&self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut stream = self.stream.borrow_mut();
+        self.exchange(&mut stream, request).map_err(|e| {
+            tracing::warn!(target: "ipc_provider", "request to {:?} failed: {}", self.path, e);
+            *stream = None;
+            match e.kind() {
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => Error::Timeout,
+                _ => Error::Transport(e.to_string()),
+            }
+        })
+    }
+}
+
+/// Reads a single json object or array from the reader.
+///
+/// Returns [None] if the reader is closed before a message starts.
+/// Leading whitespace is skipped, and nothing past the end of the message
+/// is consumed.
+pub fn read_json(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut message = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            if message.is_empty() {
+                return Ok(None);
+            }
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let mut consumed = 0;
+        let mut complete = false;
+        for &byte in buf {
+            consumed += 1;
+            if message.is_empty() && byte.is_ascii_whitespace() {
+                continue;
+            }
+            message.push(byte);
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            if depth == 0 {
+                complete = true;
+                break;
+            }
+        }
+        reader.consume(consumed);
+        if complete {
+            return Ok(Some(message));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use crate::test_utils::rpc::{rpc_method, BLOCK};
+    use crate::test_utils::MockIpcServer;
+    use axos_primitives::{BlockId, U64};
+
+    fn block_response(_: &[u8]) -> Vec<u8> {
+        std::format!(r#"{{"jsonrpc":"2.0","id":0,"result":{}}}"#, BLOCK).into_bytes()
+    }
+
+    #[test]
+    fn test_read_json_framing() {
+        let mut reader = io::Cursor::new(br#" {"a":"}\"{"} [1,[2]]"#.to_vec());
+        assert_eq!(
+            read_json(&mut reader).unwrap().unwrap(),
+            br#"{"a":"}\"{"}"#.to_vec()
+        );
+        assert_eq!(
+            read_json(&mut reader).unwrap().unwrap(),
+            b"[1,[2]]".to_vec()
+        );
+        assert_eq!(read_json(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_ipc_get_block() {
+        let server = MockIpcServer::spawn(block_response);
+        let provider = IpcProvider::new(server.path());
+        for _ in 0..2 {
+            let block = provider
+                .get_block_with_txs(BlockId::Number(2))
+                .unwrap()
+                .unwrap();
+            assert_eq!(block.number, Some(U64::from(2)));
+        }
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(rpc_method(&requests[0]), "eth_getBlockByNumber");
+        assert_eq!(server.connections(), 1);
+    }
+
+    #[test]
+    fn test_ipc_url_scheme() {
+        let server =
+            MockIpcServer::spawn(|_| br#"{"jsonrpc":"2.0","id":0,"result":"0xa"}"#.to_vec());
+        let url = std::format!("{}{}", IPC_SCHEME, server.path().display());
+        assert_eq!(IpcProvider::new(url).chain_id(), Ok(10));
+    }
+
+    #[test]
+    fn test_ipc_missing_socket() {
+        let provider = IpcProvider::new("/nonexistent/axos.ipc");
+        assert!(matches!(
+            provider.get_block_with_txs(BlockId::Number(2)),
+            Err(Error::Transport(_))
+        ));
+    }
+}
@@ -15,7 +15,43 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+pub mod async_provider;
+#[cfg(feature = "beacon")]
+pub mod beacon;
+#[cfg(feature = "alloc")]
+pub mod cache;
+#[cfg(feature = "purple")]
+pub mod client;
+pub mod clock;
+#[cfg(feature = "era")]
+pub mod era;
+pub mod executor;
+#[cfg(feature = "std")]
+pub mod http;
+#[cfg(all(feature = "std", unix))]
+pub mod ipc;
 #[cfg(feature = "test-utils")]
 pub mod mock;
+#[cfg(feature = "alloc")]
+pub mod multi;
 pub mod provider;
+#[cfg(feature = "purple")]
 pub mod purple;
+#[cfg(feature = "alloc")]
+pub mod receipts;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "alloc")]
+pub mod retry;
+#[cfg(all(feature = "alloc", feature = "serde", feature = "serde_json"))]
+pub mod rpc;
+#[cfg(all(feature = "std", any(test, feature = "test-utils")))]
+pub mod test_utils;
+#[cfg(feature = "alloc")]
+pub mod transport;
+#[cfg(feature = "verify")]
+pub mod verify;
@@ -0,0 +1,686 @@
+//! Mock Chain
+//!
+//! A [MockChain] is a deterministic, scriptable chain of blocks. Blocks
+//! link to their parents by hash, carry the transactions scheduled for
+//! their height, and are answered by hash, number and [BlockKind] like a
+//! real node would. Reorgs and finality can be scheduled at chosen head
+//! heights, or triggered directly.
+//!
+//! An L2 chain can follow an L1 [MockChain], in which case every L2 block
+//! starts with the L1 info deposit of its epoch.
+
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    keccak256, AccountProof, Address, BlockId, BlockKind, BlockWithTransactions, StorageProof,
+    TransactionReceipt, B256, U256, U64,
+};
+
+use super::txs::{l1_info_tx, DEPOSIT_TX_TYPE};
+use super::MOCK_CHAIN_ID;
+use crate::provider::{Error, Provider};
+
+/// The gas used by every mock transaction.
+const MOCK_TX_GAS: u64 = 21_000;
+
+/// The base fee of every mock block.
+const MOCK_BASE_FEE: u64 = 1_000_000_000;
+
+/// An event scheduled at a head height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    /// Replace the given number of blocks at the tip.
+    Reorg(u64),
+    /// Finalize the given block number.
+    Finalize(u64),
+    /// Mark the given block number safe.
+    Safe(u64),
+}
+
+/// The L1 chain an L2 [MockChain] derives its epochs from.
+#[derive(Debug, Clone)]
+struct L1Origin {
+    /// The L1 chain.
+    chain: MockChain,
+    /// The number of L2 blocks in each epoch.
+    blocks_per_epoch: u64,
+    /// The batcher hash set by the L1 info deposits.
+    batcher_hash: B256,
+}
+
+/// The shared state of a [MockChain].
+#[derive(Debug)]
+struct ChainState {
+    /// The chain id.
+    chain_id: u64,
+    /// The timestamp of the genesis block.
+    genesis_timestamp: u64,
+    /// The seconds between blocks.
+    block_time: u64,
+    /// Every block ever built, including reorged ones, by hash.
+    blocks: BTreeMap<B256, BlockWithTransactions>,
+    /// The receipts of every block ever built, by block hash.
+    receipts: BTreeMap<B256, Vec<TransactionReceipt>>,
+    /// The canonical block hashes, indexed by number.
+    canonical: Vec<B256>,
+    /// The transactions scheduled for each height.
+    transactions: BTreeMap<u64, Vec<Transaction>>,
+    /// The events scheduled for each head height.
+    events: BTreeMap<u64, Vec<Event>>,
+    /// The L1 chain, for L2 chains.
+    l1: Option<L1Origin>,
+    /// The finalized block number.
+    finalized: u64,
+    /// The safe block number.
+    safe: u64,
+    /// The number of reorgs so far, mixed into block hashes.
+    forks: u64,
+    /// Advance the head on every `latest` query.
+    auto_advance: bool,
+}
+
+impl ChainState {
+    /// Returns the head block number.
+    fn head(&self) -> u64 {
+        self.canonical.len() as u64 - 1
+    }
+
+    /// Returns the canonical block at the given number.
+    fn block(&self, number: u64) -> Option<&BlockWithTransactions> {
+        let hash = self.canonical.get(number as usize)?;
+        self.blocks.get(hash)
+    }
+
+    /// Resolves a [BlockId] to a block hash.
+    fn resolve(&self, block_id: BlockId) -> Option<B256> {
+        let number = match block_id {
+            BlockId::Hash(hash) => return self.blocks.contains_key(&hash).then_some(hash),
+            BlockId::Number(number) => number,
+            BlockId::Kind(BlockKind::Earliest) => 0,
+            BlockId::Kind(BlockKind::Latest) => self.head(),
+            BlockId::Kind(BlockKind::Safe) => self.safe.max(self.finalized),
+            BlockId::Kind(BlockKind::Finalized) => self.finalized,
+        };
+        self.canonical.get(number as usize).copied()
+    }
+
+    /// Builds the next canonical block and fires the events scheduled at
+    /// its height.
+    fn push_block(&mut self) {
+        let number = self.canonical.len() as u64;
+        let parent_hash = self.canonical.last().copied().unwrap_or_default();
+        let timestamp = self.genesis_timestamp + number * self.block_time;
+
+        let mut transactions = Vec::new();
+        if let Some(l1) = &self.l1 {
+            let epoch = number / l1.blocks_per_epoch;
+            let l1_block = l1
+                .chain
+                .block(epoch.min(l1.chain.head()))
+                .expect("the l1 chain has a head block");
+            let sequence_number = number % l1.blocks_per_epoch;
+            transactions.push(l1_info_tx(&l1_block, sequence_number, l1.batcher_hash));
+        }
+        transactions.extend(self.transactions.get(&number).cloned().unwrap_or_default());
+
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(parent_hash.as_slice());
+        preimage.extend_from_slice(&number.to_be_bytes());
+        preimage.extend_from_slice(&timestamp.to_be_bytes());
+        preimage.extend_from_slice(&self.chain_id.to_be_bytes());
+        preimage.extend_from_slice(&self.forks.to_be_bytes());
+        for (index, tx) in transactions.iter_mut().enumerate() {
+            if tx.hash == B256::ZERO {
+                tx.hash = self.transaction_hash(number, index, tx);
+            }
+            preimage.extend_from_slice(tx.hash.as_slice());
+        }
+        let hash = keccak256(&preimage);
+
+        let mut receipts = Vec::with_capacity(transactions.len());
+        for (index, tx) in transactions.iter_mut().enumerate() {
+            tx.block_hash = Some(hash);
+            tx.block_number = Some(U256::from(number));
+            tx.transaction_index = Some(U256::from(index));
+            receipts.push(TransactionReceipt {
+                transaction_hash: tx.hash,
+                transaction_index: U64::from(index),
+                block_hash: Some(hash),
+                block_number: Some(U64::from(number)),
+                from: tx.from,
+                to: tx.to,
+                cumulative_gas_used: U256::from(MOCK_TX_GAS * (index as u64 + 1)),
+                gas_used: Some(U256::from(MOCK_TX_GAS)),
+                status: Some(U64::from(1)),
+                transaction_type: tx.transaction_type,
+                effective_gas_price: tx.gas_price,
+                deposit_nonce: (tx.transaction_type == Some(U64::from(DEPOSIT_TX_TYPE)))
+                    .then_some(U64::ZERO),
+                ..Default::default()
+            });
+        }
+
+        let block = BlockWithTransactions {
+            hash: Some(hash),
+            parent_hash,
+            number: Some(U64::from(number)),
+            timestamp: U256::from(timestamp),
+            gas_used: U256::from(MOCK_TX_GAS * transactions.len() as u64),
+            base_fee_per_gas: Some(U256::from(MOCK_BASE_FEE)),
+            transactions,
+            ..Default::default()
+        };
+        self.blocks.insert(hash, block);
+        self.receipts.insert(hash, receipts);
+        self.canonical.push(hash);
+
+        for event in self.events.get(&number).cloned().unwrap_or_default() {
+            match event {
+                Event::Reorg(depth) => self.reorg(depth),
+                Event::Finalize(number) => self.finalized = number.min(self.head()),
+                Event::Safe(number) => self.safe = number.min(self.head()),
+            }
+        }
+    }
+
+    /// Computes a stable hash for a transaction that was built without one.
+    ///
+    /// The hash does not depend on the block hash, so a transaction keeps
+    /// its hash when it is re-included after a reorg.
+    fn transaction_hash(&self, number: u64, index: usize, tx: &Transaction) -> B256 {
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(&self.chain_id.to_be_bytes());
+        preimage.extend_from_slice(&number.to_be_bytes());
+        preimage.extend_from_slice(&(index as u64).to_be_bytes());
+        preimage.extend_from_slice(tx.from.as_slice());
+        preimage.extend_from_slice(tx.to.unwrap_or_default().as_slice());
+        preimage.extend_from_slice(tx.source_hash.as_slice());
+        preimage.extend_from_slice(&tx.input);
+        keccak256(&preimage)
+    }
+
+    /// Replaces the top `depth` canonical blocks with new blocks at the
+    /// same heights. Finalized blocks are never replaced.
+    fn reorg(&mut self, depth: u64) {
+        let head = self.head();
+        let first = (head + 1).saturating_sub(depth).max(self.finalized + 1);
+        if first > head {
+            return;
+        }
+        self.forks += 1;
+        self.canonical.truncate(first as usize);
+        self.safe = self.safe.min(first - 1);
+        let events = core::mem::take(&mut self.events);
+        while self.head() < head {
+            self.push_block();
+        }
+        self.events = events;
+    }
+}
+
+/// A deterministic mock chain.
+///
+/// Clones share the same chain, so a test can keep a handle to drive
+/// the chain while a clone is boxed as a [Provider].
+///
+/// ## Example
+///
+/// ```rust
+/// use axos_providers::mock::MockChain;
+/// use axos_providers::provider::Provider;
+/// use axos_primitives::{BlockId, BlockKind};
+///
+/// let chain = MockChain::builder().blocks(10).finalize_at(8, 4).reorg_at(10, 2).build();
+/// let head = chain.get_block_with_txs(BlockId::Kind(BlockKind::Latest)).unwrap().unwrap();
+/// let parent = chain.get_block_with_txs(BlockId::Number(9)).unwrap().unwrap();
+/// assert_eq!(head.parent_hash, parent.hash.unwrap());
+/// assert_eq!(chain.finalized(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockChain {
+    /// The shared chain state.
+    state: Rc<RefCell<ChainState>>,
+}
+
+impl Default for MockChain {
+    fn default() -> Self {
+        MockChainBuilder::default().build()
+    }
+}
+
+impl MockChain {
+    /// Returns a [MockChainBuilder].
+    pub fn builder() -> MockChainBuilder {
+        MockChainBuilder::default()
+    }
+
+    /// Returns the chain id.
+    pub fn chain_id(&self) -> u64 {
+        self.state.borrow().chain_id
+    }
+
+    /// Returns the head block number.
+    pub fn head(&self) -> u64 {
+        self.state.borrow().head()
+    }
+
+    /// Returns the finalized block number.
+    pub fn finalized(&self) -> u64 {
+        self.state.borrow().finalized
+    }
+
+    /// Returns the safe block number.
+    pub fn safe(&self) -> u64 {
+        let state = self.state.borrow();
+        state.safe.max(state.finalized)
+    }
+
+    /// Returns the canonical block at the given number.
+    pub fn block(&self, number: u64) -> Option<BlockWithTransactions> {
+        self.state.borrow().block(number).cloned()
+    }
+
+    /// Returns the canonical block hash at the given number.
+    pub fn hash(&self, number: u64) -> Option<B256> {
+        self.state.borrow().canonical.get(number as usize).copied()
+    }
+
+    /// Builds the next block and returns it.
+    pub fn advance(&self) -> BlockWithTransactions {
+        let mut state = self.state.borrow_mut();
+        state.push_block();
+        let head = state.head();
+        state.block(head).cloned().expect("the head block exists")
+    }
+
+    /// Builds blocks until the head reaches the given number.
+    pub fn advance_to(&self, number: u64) {
+        let mut state = self.state.borrow_mut();
+        while state.head() < number {
+            state.push_block();
+        }
+    }
+
+    /// Replaces the top `depth` canonical blocks with new blocks at the
+    /// same heights. Finalized blocks are never replaced.
+    pub fn reorg(&self, depth: u64) {
+        self.state.borrow_mut().reorg(depth);
+    }
+
+    /// Finalizes the given block number, capped at the head.
+    pub fn set_finalized(&self, number: u64) {
+        let mut state = self.state.borrow_mut();
+        state.finalized = number.min(state.head());
+    }
+
+    /// Marks the given block number safe, capped at the head.
+    pub fn set_safe(&self, number: u64) {
+        let mut state = self.state.borrow_mut();
+        state.safe = number.min(state.head());
+    }
+}
+
+impl Provider for MockChain {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        tracing::debug!(target: "mock_chain", "get block with txs, block id: {:?}", block_id);
+        if block_id == BlockId::Kind(BlockKind::Latest) && self.state.borrow().auto_advance {
+            return Ok(Some(self.advance()));
+        }
+        let state = self.state.borrow();
+        Ok(state
+            .resolve(block_id)
+            .and_then(|hash| state.blocks.get(&hash).cloned()))
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        tracing::debug!(target: "mock_chain", "get receipts, block id: {:?}", block_id);
+        let state = self.state.borrow();
+        Ok(state
+            .resolve(block_id)
+            .and_then(|hash| state.receipts.get(&hash).cloned()))
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        tracing::debug!(target: "mock_chain", "get transaction, hash: {:?}", hash);
+        let state = self.state.borrow();
+        Ok(state
+            .canonical
+            .iter()
+            .filter_map(|block| state.blocks.get(block))
+            .flat_map(|block| block.transactions.iter())
+            .find(|tx| tx.hash == hash)
+            .cloned())
+    }
+
+    /// Returns an empty account with zeroed storage slots.
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        tracing::debug!(target: "mock_chain", "get proof, block id: {:?}", block_id);
+        if self.state.borrow().resolve(block_id).is_none() {
+            return Err(Error::BlockNotFound);
+        }
+        Ok(AccountProof {
+            address,
+            storage_proof: slots
+                .iter()
+                .map(|slot| StorageProof {
+                    key: U256::from_be_bytes(slot.0),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        Ok(self.chain_id())
+    }
+}
+
+/// A builder for a [MockChain].
+#[derive(Debug, Clone)]
+pub struct MockChainBuilder {
+    /// The chain id.
+    chain_id: u64,
+    /// The timestamp of the genesis block.
+    genesis_timestamp: u64,
+    /// The seconds between blocks.
+    block_time: u64,
+    /// The initial head block number.
+    head: u64,
+    /// The transactions scheduled for each height.
+    transactions: BTreeMap<u64, Vec<Transaction>>,
+    /// The events scheduled for each head height.
+    events: BTreeMap<u64, Vec<Event>>,
+    /// The L1 chain, for L2 chains.
+    l1: Option<L1Origin>,
+    /// Advance the head on every `latest` query.
+    auto_advance: bool,
+}
+
+impl Default for MockChainBuilder {
+    fn default() -> Self {
+        Self {
+            chain_id: MOCK_CHAIN_ID,
+            genesis_timestamp: 0,
+            block_time: 12,
+            head: 0,
+            transactions: BTreeMap::new(),
+            events: BTreeMap::new(),
+            l1: None,
+            auto_advance: false,
+        }
+    }
+}
+
+impl MockChainBuilder {
+    /// Sets the chain id.
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Sets the timestamp of the genesis block.
+    pub fn genesis_timestamp(mut self, timestamp: u64) -> Self {
+        self.genesis_timestamp = timestamp;
+        self
+    }
+
+    /// Sets the seconds between blocks.
+    pub fn block_time(mut self, block_time: u64) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
+    /// Builds blocks up to the given head number.
+    pub fn blocks(mut self, head: u64) -> Self {
+        self.head = head;
+        self
+    }
+
+    /// Includes a transaction in the block at the given number, after any
+    /// transactions already scheduled there.
+    ///
+    /// Transactions without a hash are given a deterministic one.
+    pub fn transaction(mut self, number: u64, tx: Transaction) -> Self {
+        self.transactions.entry(number).or_default().push(tx);
+        self
+    }
+
+    /// Derives the chain from an L1 chain, starting every block with the
+    /// L1 info deposit of its epoch. Each epoch spans `blocks_per_epoch`
+    /// blocks, and L2 block `n` belongs to L1 block `n / blocks_per_epoch`,
+    /// capped at the L1 head.
+    pub fn l1_origin(mut self, l1: &MockChain, blocks_per_epoch: u64, batcher_hash: B256) -> Self {
+        self.l1 = Some(L1Origin {
+            chain: l1.clone(),
+            blocks_per_epoch: blocks_per_epoch.max(1),
+            batcher_hash,
+        });
+        self
+    }
+
+    /// Replaces the top `depth` blocks once the head reaches `head`.
+    pub fn reorg_at(self, head: u64, depth: u64) -> Self {
+        self.event(head, Event::Reorg(depth))
+    }
+
+    /// Finalizes block `number` once the head reaches `head`.
+    pub fn finalize_at(self, head: u64, number: u64) -> Self {
+        self.event(head, Event::Finalize(number))
+    }
+
+    /// Marks block `number` safe once the head reaches `head`.
+    pub fn safe_at(self, head: u64, number: u64) -> Self {
+        self.event(head, Event::Safe(number))
+    }
+
+    /// Builds a new head block on every `latest` query.
+    pub fn auto_advance(mut self, auto_advance: bool) -> Self {
+        self.auto_advance = auto_advance;
+        self
+    }
+
+    /// Schedules an event at the given head height.
+    fn event(mut self, head: u64, event: Event) -> Self {
+        self.events.entry(head).or_default().push(event);
+        self
+    }
+
+    /// Builds the [MockChain], firing the events scheduled up to its head.
+    pub fn build(self) -> MockChain {
+        let mut state = ChainState {
+            chain_id: self.chain_id,
+            genesis_timestamp: self.genesis_timestamp,
+            block_time: self.block_time,
+            blocks: BTreeMap::new(),
+            receipts: BTreeMap::new(),
+            canonical: Vec::new(),
+            transactions: self.transactions,
+            events: self.events,
+            l1: self.l1,
+            finalized: 0,
+            safe: 0,
+            forks: 0,
+            auto_advance: self.auto_advance,
+        };
+        state.push_block();
+        while state.head() < self.head {
+            state.push_block();
+        }
+        MockChain {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::txs::{batcher_tx, user_deposit_tx};
+    use axos_primitives::SetL1BlockValuesCall;
+
+    fn get(chain: &MockChain, block_id: BlockId) -> BlockWithTransactions {
+        chain.get_block_with_txs(block_id).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_blocks_link_to_parents() {
+        let chain = MockChain::builder().blocks(5).build();
+        assert_eq!(chain.head(), 5);
+        for number in 1..=5 {
+            let block = get(&chain, BlockId::Number(number));
+            let parent = get(&chain, BlockId::Number(number - 1));
+            assert_eq!(block.parent_hash, parent.hash.unwrap());
+            assert_eq!(block.timestamp, parent.timestamp + U256::from(12));
+        }
+        assert_eq!(
+            get(&chain, BlockId::Kind(BlockKind::Earliest)).parent_hash,
+            B256::ZERO
+        );
+        assert_eq!(chain.get_block_with_txs(BlockId::Number(6)), Ok(None));
+    }
+
+    #[test]
+    fn test_lookup_by_hash_number_and_kind() {
+        let chain = MockChain::builder()
+            .blocks(4)
+            .safe_at(4, 3)
+            .finalize_at(4, 2)
+            .build();
+        let block = get(&chain, BlockId::Number(3));
+        assert_eq!(get(&chain, BlockId::Hash(block.hash.unwrap())), block);
+        assert_eq!(get(&chain, BlockId::Kind(BlockKind::Safe)), block);
+        assert_eq!(
+            get(&chain, BlockId::Kind(BlockKind::Latest)).number,
+            Some(U64::from(4))
+        );
+        assert_eq!(
+            get(&chain, BlockId::Kind(BlockKind::Finalized)).number,
+            Some(U64::from(2))
+        );
+        assert_eq!(
+            chain.get_block_with_txs(BlockId::Hash(B256::ZERO)),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_storage_and_chain_id() {
+        let chain = MockChain::builder().chain_id(10).blocks(2).build();
+        assert_eq!(Provider::chain_id(&chain), Ok(10));
+        assert_eq!(
+            chain.get_storage_at(Address::ZERO, B256::ZERO, BlockId::Number(1)),
+            Ok(B256::ZERO)
+        );
+        assert_eq!(
+            chain.get_storage_at(Address::ZERO, B256::ZERO, BlockId::Number(3)),
+            Err(Error::BlockNotFound)
+        );
+    }
+
+    #[test]
+    fn test_scheduled_reorg() {
+        let chain = MockChain::builder().blocks(6).reorg_at(6, 2).build();
+        let old = MockChain::builder().blocks(6).build();
+        assert_eq!(chain.hash(4), old.hash(4));
+        assert_ne!(chain.hash(5), old.hash(5));
+        assert_ne!(chain.hash(6), old.hash(6));
+        assert_eq!(
+            get(&chain, BlockId::Number(5)).parent_hash,
+            chain.hash(4).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reorg_keeps_orphans_and_finality() {
+        let chain = MockChain::builder().blocks(5).finalize_at(5, 4).build();
+        let finalized = chain.hash(4);
+        let orphan = chain.hash(5).unwrap();
+        chain.reorg(3);
+        assert_eq!(chain.hash(4), finalized);
+        assert_ne!(chain.hash(5), Some(orphan));
+        assert_eq!(
+            get(&chain, BlockId::Hash(orphan)).number,
+            Some(U64::from(5))
+        );
+        assert_eq!(chain.finalized(), 4);
+    }
+
+    #[test]
+    fn test_scheduled_finality_while_advancing() {
+        let chain = MockChain::builder().blocks(2).finalize_at(4, 3).build();
+        assert_eq!(chain.finalized(), 0);
+        chain.advance_to(4);
+        assert_eq!(chain.finalized(), 3);
+    }
+
+    #[test]
+    fn test_auto_advance() {
+        let chain = MockChain::builder().auto_advance(true).build();
+        let first = get(&chain, BlockId::Kind(BlockKind::Latest));
+        let second = get(&chain, BlockId::Kind(BlockKind::Latest));
+        assert_eq!(second.parent_hash, first.hash.unwrap());
+        assert_eq!(chain.head(), 2);
+    }
+
+    #[test]
+    fn test_transactions_and_receipts() {
+        let inbox = Address::with_last_byte(0xff);
+        let tx = batcher_tx(Address::with_last_byte(1), inbox, [0u8, 1, 2]);
+        let chain = MockChain::builder().blocks(3).transaction(2, tx).build();
+        let block = get(&chain, BlockId::Number(2));
+        assert_eq!(block.transactions.len(), 1);
+        let tx = &block.transactions[0];
+        assert_eq!(tx.block_hash, block.hash);
+        assert_eq!(tx.to, Some(inbox));
+        assert_eq!(chain.get_transaction(tx.hash), Ok(Some(tx.clone())));
+
+        let receipts = chain.get_receipts(BlockId::Number(2)).unwrap().unwrap();
+        assert_eq!(receipts.len(), 1);
+        assert!(receipts[0].is_success());
+        assert_eq!(receipts[0].transaction_hash, tx.hash);
+    }
+
+    #[test]
+    fn test_l2_chain_follows_l1() {
+        let l1 = MockChain::builder().blocks(3).build();
+        let deposit = user_deposit_tx(
+            l1.hash(1).unwrap(),
+            0,
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            U256::from(100),
+        );
+        let l2 = MockChain::builder()
+            .chain_id(10)
+            .block_time(2)
+            .l1_origin(&l1, 6, B256::with_last_byte(7))
+            .transaction(6, deposit)
+            .blocks(7)
+            .build();
+
+        let block = get(&l2, BlockId::Number(7));
+        let call = SetL1BlockValuesCall::try_from(block.transactions[0].input.clone()).unwrap();
+        assert_eq!(call.get_block_number().unwrap(), 1);
+        assert_eq!(call.get_block_hash().unwrap(), l1.hash(1).unwrap());
+        assert_eq!(call.get_sequence_number().unwrap(), 1);
+        assert_eq!(call.get_batcher_hash().unwrap(), B256::with_last_byte(7));
+
+        let epoch_start = get(&l2, BlockId::Number(6));
+        assert_eq!(epoch_start.transactions.len(), 2);
+        assert_eq!(epoch_start.transactions[1].mint, Some(U256::from(100)));
+        assert_eq!(l2.chain_id(), 10);
+    }
+}
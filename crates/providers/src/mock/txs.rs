@@ -0,0 +1,166 @@
+//! Mock Transactions
+//!
+//! Builders for the transactions a rollup node cares about: batcher
+//! transactions posted to the batch inbox on L1, and the user and L1
+//! info deposits at the start of each L2 block.
+
+use alloc::vec::Vec;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    keccak256, Address, BlockWithTransactions, Bytes, SystemAccounts, B256,
+    SET_L1_BLOCK_VALUES_SELECTOR, U256, U64,
+};
+
+/// The EIP-2718 type of a deposit transaction.
+pub const DEPOSIT_TX_TYPE: u8 = 0x7e;
+
+/// The L1 fee overhead set by mock L1 info deposits.
+pub const MOCK_L1_FEE_OVERHEAD: u64 = 2100;
+
+/// The L1 fee scalar set by mock L1 info deposits.
+pub const MOCK_L1_FEE_SCALAR: u64 = 1_000_000;
+
+/// Builds a legacy transaction from `from` to the batch `inbox` carrying `data`.
+pub fn batcher_tx(from: Address, inbox: Address, data: impl Into<Bytes>) -> Transaction {
+    Transaction {
+        from,
+        to: Some(inbox),
+        input: data.into(),
+        gas: U256::from(100_000),
+        gas_price: Some(U256::from(1_000_000_000)),
+        transaction_type: Some(U64::ZERO),
+        ..Default::default()
+    }
+}
+
+/// Builds a deposit transaction with the given source hash.
+pub fn deposit_tx(
+    source_hash: B256,
+    from: Address,
+    to: Option<Address>,
+    mint: U256,
+    data: impl Into<Bytes>,
+) -> Transaction {
+    Transaction {
+        source_hash,
+        from,
+        to,
+        mint: (mint > U256::ZERO).then_some(mint),
+        value: mint,
+        input: data.into(),
+        gas: U256::from(1_000_000),
+        transaction_type: Some(U64::from(DEPOSIT_TX_TYPE)),
+        ..Default::default()
+    }
+}
+
+/// Builds a user deposit emitted by the log at `log_index` of an L1 block.
+pub fn user_deposit_tx(
+    l1_block_hash: B256,
+    log_index: u64,
+    from: Address,
+    to: Address,
+    mint: U256,
+) -> Transaction {
+    let source_hash = user_deposit_source_hash(l1_block_hash, log_index);
+    deposit_tx(source_hash, from, Some(to), mint, Bytes::new())
+}
+
+/// Builds the L1 info deposit for the L2 block at `sequence_number` in the
+/// epoch of the given L1 block.
+pub fn l1_info_tx(
+    l1_block: &BlockWithTransactions,
+    sequence_number: u64,
+    batcher_hash: B256,
+) -> Transaction {
+    let l1_hash = l1_block.hash.unwrap_or_default();
+    let accounts = SystemAccounts::default();
+    let mut calldata = Vec::with_capacity(260);
+    calldata.extend_from_slice(SET_L1_BLOCK_VALUES_SELECTOR.as_slice());
+    calldata.extend_from_slice(&word(U256::from(l1_block.number.unwrap_or_default())));
+    calldata.extend_from_slice(&word(l1_block.timestamp));
+    calldata.extend_from_slice(&word(l1_block.base_fee_per_gas.unwrap_or_default()));
+    calldata.extend_from_slice(l1_hash.as_slice());
+    calldata.extend_from_slice(&word(U256::from(sequence_number)));
+    calldata.extend_from_slice(batcher_hash.as_slice());
+    calldata.extend_from_slice(&word(U256::from(MOCK_L1_FEE_OVERHEAD)));
+    calldata.extend_from_slice(&word(U256::from(MOCK_L1_FEE_SCALAR)));
+    deposit_tx(
+        l1_info_source_hash(l1_hash, sequence_number),
+        accounts.attributes_depositor,
+        Some(accounts.attributes_predeploy),
+        U256::ZERO,
+        calldata,
+    )
+}
+
+/// Computes the source hash of a user deposit.
+pub fn user_deposit_source_hash(l1_block_hash: B256, log_index: u64) -> B256 {
+    source_hash(0, l1_block_hash, log_index)
+}
+
+/// Computes the source hash of an L1 info deposit.
+pub fn l1_info_source_hash(l1_block_hash: B256, sequence_number: u64) -> B256 {
+    source_hash(1, l1_block_hash, sequence_number)
+}
+
+/// Computes `keccak256(domain ++ keccak256(l1_block_hash ++ index))`.
+fn source_hash(domain: u64, l1_block_hash: B256, index: u64) -> B256 {
+    let mut inner = [0u8; 64];
+    inner[..32].copy_from_slice(l1_block_hash.as_slice());
+    inner[32..].copy_from_slice(&word(U256::from(index)));
+    let mut outer = [0u8; 64];
+    outer[..32].copy_from_slice(&word(U256::from(domain)));
+    outer[32..].copy_from_slice(keccak256(inner).as_slice());
+    keccak256(outer)
+}
+
+/// Encodes a value as a big-endian abi word.
+fn word(value: U256) -> [u8; 32] {
+    value.to_be_bytes::<32>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_primitives::SetL1BlockValuesCall;
+
+    #[test]
+    fn test_l1_info_tx_calldata() {
+        let l1_block = BlockWithTransactions {
+            hash: Some(B256::with_last_byte(9)),
+            number: Some(U64::from(7)),
+            timestamp: U256::from(84),
+            base_fee_per_gas: Some(U256::from(10)),
+            ..Default::default()
+        };
+        let tx = l1_info_tx(&l1_block, 3, B256::with_last_byte(5));
+        assert_eq!(tx.transaction_type, Some(U64::from(DEPOSIT_TX_TYPE)));
+        assert_eq!(
+            tx.source_hash,
+            l1_info_source_hash(B256::with_last_byte(9), 3)
+        );
+
+        let call = SetL1BlockValuesCall::try_from(tx.input).unwrap();
+        assert_eq!(call.get_block_number().unwrap(), 7);
+        assert_eq!(call.get_block_timestamp().unwrap(), 84);
+        assert_eq!(call.get_basefee().unwrap(), U256::from(10));
+        assert_eq!(call.get_block_hash().unwrap(), B256::with_last_byte(9));
+        assert_eq!(call.get_sequence_number().unwrap(), 3);
+        assert_eq!(call.get_batcher_hash().unwrap(), B256::with_last_byte(5));
+    }
+
+    #[test]
+    fn test_source_hash_domains() {
+        let hash = B256::with_last_byte(1);
+        assert_ne!(
+            user_deposit_source_hash(hash, 0),
+            l1_info_source_hash(hash, 0)
+        );
+        assert_ne!(
+            user_deposit_source_hash(hash, 0),
+            user_deposit_source_hash(hash, 1)
+        );
+    }
+}
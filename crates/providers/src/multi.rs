@@ -0,0 +1,388 @@
+//! Multi Provider
+//!
+//! The [MultiProvider] spreads requests over several providers, such as
+//! independent L1 RPC endpoints. It runs in one of two [MultiMode]s:
+//!
+//! - [MultiMode::Failover] asks each provider in order and returns the
+//!   first successful response. A provider that errors is skipped.
+//! - [MultiMode::Quorum] asks providers for blocks until `n` of them
+//!   return the same block hash. If no block reaches the quorum, the
+//!   request fails with a [DisagreementError]. Other methods fail over.
+//!
+//! ## Example
+//!
+#![cfg_attr(
+    feature = "test-utils",
+    doc = r#"
+```rust
+use axos_providers::mock::MockChain;
+use axos_providers::multi::MultiProvider;
+
+let chain = MockChain::builder().blocks(10).build();
+let provider = MultiProvider::new()
+    .with_provider(chain.clone())
+    .with_provider(chain)
+    .with_quorum(2);
+assert_eq!(provider.len(), 2);
+```
+"#
+)]
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    AccountProof, Address, BlockId, BlockWithTransactions, Filter, Log, TransactionReceipt, B256,
+};
+
+use crate::provider::{Error, Provider};
+
+/// How a [MultiProvider] combines its providers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MultiMode {
+    /// Use the first provider that responds successfully.
+    #[default]
+    Failover,
+    /// Require this many providers to return the same block.
+    Quorum(u32),
+}
+
+/// Returned when no block reaches the quorum of a [MultiProvider].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisagreementError {
+    /// The number of matching responses required.
+    pub required: u32,
+    /// The number of providers behind the most common response.
+    pub best: u32,
+    /// The number of distinct responses.
+    pub distinct: u32,
+}
+
+impl core::fmt::Display for DisagreementError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "providers disagree: {} distinct responses, best has {} of {} required",
+            self.distinct, self.best, self.required
+        )
+    }
+}
+
+impl core::error::Error for DisagreementError {}
+
+impl From<DisagreementError> for Error {
+    fn from(e: DisagreementError) -> Self {
+        Error::other(e)
+    }
+}
+
+/// A [Provider] over several providers.
+#[derive(Default)]
+pub struct MultiProvider {
+    /// The providers, in priority order.
+    providers: Vec<Box<dyn Provider>>,
+    /// How responses are combined.
+    mode: MultiMode,
+}
+
+impl core::fmt::Debug for MultiProvider {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MultiProvider")
+            .field("providers", &self.providers.len())
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl From<Vec<Box<dyn Provider>>> for MultiProvider {
+    fn from(providers: Vec<Box<dyn Provider>>) -> Self {
+        Self {
+            providers,
+            ..Default::default()
+        }
+    }
+}
+
+impl MultiProvider {
+    /// Instantiates a new, empty [MultiProvider] in failover mode.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a provider with the lowest priority.
+    pub fn with_provider(mut self, provider: impl Provider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Sets the mode.
+    pub fn with_mode(mut self, mode: MultiMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Requires `n` providers to agree on every block.
+    pub fn with_quorum(self, n: u32) -> Self {
+        self.with_mode(MultiMode::Quorum(n))
+    }
+
+    /// Returns the number of providers.
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// Returns true if there are no providers.
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    /// Returns the mode.
+    pub fn mode(&self) -> MultiMode {
+        self.mode
+    }
+
+    /// Returns the first successful response, trying providers in order.
+    /// If every provider fails, the first error is returned.
+    fn failover<T>(&self, f: impl Fn(&dyn Provider) -> Result<T, Error>) -> Result<T, Error> {
+        let mut first = None;
+        for (i, provider) in self.providers.iter().enumerate() {
+            match f(provider.as_ref()) {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    tracing::warn!(target: "multi_provider", "provider {} failed: {}", i, e);
+                    first.get_or_insert(e);
+                }
+            }
+        }
+        Err(first.unwrap_or(Error::Unsupported))
+    }
+
+    /// Returns the block that `required` providers agree on.
+    fn quorum(
+        &self,
+        block_id: BlockId,
+        required: u32,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        let required = required.max(1);
+        // Distinct responses, keyed by block hash, with their vote counts.
+        let mut answers: Vec<(Option<Option<B256>>, Option<BlockWithTransactions>, u32)> =
+            Vec::new();
+        let mut first_err = None;
+        for (i, provider) in self.providers.iter().enumerate() {
+            let block = match provider.get_block_with_txs(block_id) {
+                Ok(block) => block,
+                Err(e) => {
+                    tracing::warn!(target: "multi_provider", "provider {} failed: {}", i, e);
+                    first_err.get_or_insert(e);
+                    continue;
+                }
+            };
+            let key = block.as_ref().map(|b| b.hash);
+            let votes = match answers.iter_mut().find(|(k, _, _)| *k == key) {
+                Some((_, _, votes)) => {
+                    *votes += 1;
+                    *votes
+                }
+                None => {
+                    if !answers.is_empty() {
+                        tracing::warn!(
+                            target: "multi_provider",
+                            "provider {} returned divergent block {:?} for {:?}",
+                            i,
+                            key,
+                            block_id
+                        );
+                    }
+                    answers.push((key, block, 1));
+                    1
+                }
+            };
+            if votes >= required {
+                let index = answers.iter().position(|(k, _, _)| *k == key);
+                return Ok(index.and_then(|i| answers.swap_remove(i).1));
+            }
+        }
+        match (answers.is_empty(), first_err) {
+            (true, Some(e)) => Err(e),
+            _ => Err(DisagreementError {
+                required,
+                best: answers.iter().map(|(_, _, v)| *v).max().unwrap_or(0),
+                distinct: answers.len() as u32,
+            }
+            .into()),
+        }
+    }
+}
+
+impl Provider for MultiProvider {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        match self.mode {
+            MultiMode::Failover => self.failover(|p| p.get_block_with_txs(block_id)),
+            MultiMode::Quorum(required) => self.quorum(block_id, required),
+        }
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        self.failover(|p| p.get_receipts(block_id))
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        self.failover(|p| p.get_logs(filter))
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        self.failover(|p| p.get_transaction(hash))
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.failover(|p| p.get_proof(address, slots, block_id))
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        self.failover(|p| p.get_storage_at(address, slot, block_id))
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.failover(|p| p.chain_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+
+    /// A provider returning a fixed response and counting its calls.
+    struct Fixed {
+        response: Result<Option<u8>, Error>,
+        calls: Rc<Cell<u32>>,
+    }
+
+    impl Fixed {
+        fn block(hash: u8) -> Self {
+            Self::new(Ok(Some(hash)))
+        }
+
+        fn new(response: Result<Option<u8>, Error>) -> Self {
+            Self {
+                response,
+                calls: Rc::new(Cell::new(0)),
+            }
+        }
+    }
+
+    impl Provider for Fixed {
+        fn get_block_with_txs(&self, _: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+            self.calls.set(self.calls.get() + 1);
+            self.response.clone().map(|hash| {
+                hash.map(|h| BlockWithTransactions {
+                    hash: Some(B256::with_last_byte(h)),
+                    ..Default::default()
+                })
+            })
+        }
+
+        fn chain_id(&self) -> Result<u64, Error> {
+            self.response.clone().map(|h| h.unwrap_or_default() as u64)
+        }
+    }
+
+    fn hash_of(block: Result<Option<BlockWithTransactions>, Error>) -> Option<B256> {
+        block.unwrap().and_then(|b| b.hash)
+    }
+
+    #[test]
+    fn test_failover() {
+        let provider = MultiProvider::new()
+            .with_provider(Fixed::new(Err(Error::Timeout)))
+            .with_provider(Fixed::block(2))
+            .with_provider(Fixed::block(3));
+        let block = provider.get_block_with_txs(BlockId::Number(1));
+        assert_eq!(hash_of(block), Some(B256::with_last_byte(2)));
+        assert_eq!(provider.chain_id(), Ok(2));
+    }
+
+    #[test]
+    fn test_failover_all_fail() {
+        let provider = MultiProvider::new()
+            .with_provider(Fixed::new(Err(Error::Timeout)))
+            .with_provider(Fixed::new(Err(Error::Unsupported)));
+        assert_eq!(
+            provider.get_block_with_txs(BlockId::Number(1)),
+            Err(Error::Timeout)
+        );
+        assert_eq!(MultiProvider::new().chain_id(), Err(Error::Unsupported));
+    }
+
+    #[test]
+    fn test_quorum_tolerates_divergent_provider() {
+        let last = Fixed::block(1);
+        let calls = Rc::clone(&last.calls);
+        let provider = MultiProvider::new()
+            .with_provider(Fixed::block(9))
+            .with_provider(Fixed::block(1))
+            .with_provider(Fixed::new(Err(Error::Timeout)))
+            .with_provider(Fixed::block(1))
+            .with_provider(last)
+            .with_quorum(2);
+        let block = provider.get_block_with_txs(BlockId::Number(1));
+        assert_eq!(hash_of(block), Some(B256::with_last_byte(1)));
+        // The quorum is reached before the last provider is asked.
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn test_quorum_disagreement() {
+        let provider = MultiProvider::new()
+            .with_provider(Fixed::block(1))
+            .with_provider(Fixed::block(2))
+            .with_provider(Fixed::new(Ok(None)))
+            .with_quorum(2);
+        let err = provider.get_block_with_txs(BlockId::Number(1)).unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&DisagreementError {
+                required: 2,
+                best: 1,
+                distinct: 3,
+            })
+        );
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_quorum_all_fail() {
+        let provider = MultiProvider::new()
+            .with_provider(Fixed::new(Err(Error::Timeout)))
+            .with_provider(Fixed::new(Err(Error::BlockNotFound)))
+            .with_quorum(2);
+        assert_eq!(
+            provider.get_block_with_txs(BlockId::Number(1)),
+            Err(Error::Timeout)
+        );
+    }
+
+    #[test]
+    fn test_quorum_agrees_on_missing_block() {
+        let provider = MultiProvider::new()
+            .with_provider(Fixed::new(Ok(None)))
+            .with_provider(Fixed::new(Ok(None)))
+            .with_quorum(2);
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(1)), Ok(None));
+    }
+}
@@ -2,9 +2,19 @@
 //!
 //! This module defines the `Provider` trait, which exposes a host of
 //! methods for fetching chain data.
-use axos_primitives::{BlockId, BlockWithTransactions};
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{AccountProof, Address, BlockId, BlockWithTransactions, GenericString, B256};
 
-#[cfg(feature = "serde")]
+#[cfg(feature = "alloc")]
+use alloc::string::ToString;
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use axos_primitives::{Filter, Log, TransactionReceipt};
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
 use serde::{Deserialize, Serialize};
 
 // todo(refcell): The provider should be removed and replaced with support
@@ -12,16 +22,350 @@ use serde::{Deserialize, Serialize};
 //                at https://github.com/alloy-rs/alloy.
 
 /// Provider Trait
+///
+/// Only [Provider::get_block_with_txs] is required. Methods without an
+/// implementation return [Error::Unsupported], except where they can be
+/// built from other methods.
 pub trait Provider {
     /// Fetch a block with transactions.
     fn get_block_with_txs(&self, block_id: BlockId)
         -> Result<Option<BlockWithTransactions>, Error>;
+
+    /// Fetch all transaction receipts for a block.
+    #[cfg(feature = "alloc")]
+    fn get_receipts(&self, _block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Fetch the logs matching a filter.
+    ///
+    /// By default, the logs are collected from the receipts of each block
+    /// the filter covers. A filter without a block hash must set both ends
+    /// of its block range.
+    #[cfg(feature = "alloc")]
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        let receipts = match (filter.block_hash, filter.from_block, filter.to_block) {
+            (Some(hash), _, _) => self.get_receipts(BlockId::Hash(hash))?.unwrap_or_default(),
+            (None, Some(from), Some(to)) => {
+                let mut receipts = Vec::new();
+                for number in from..=to {
+                    let block = self.get_receipts(BlockId::Number(number))?;
+                    receipts.extend(block.unwrap_or_default());
+                }
+                receipts
+            }
+            _ => return Err(Error::Unsupported),
+        };
+        Ok(receipts
+            .into_iter()
+            .flat_map(|r| r.logs)
+            .filter(|l| filter.matches(l))
+            .collect())
+    }
+
+    /// Fetch a transaction by hash.
+    fn get_transaction(&self, _hash: B256) -> Result<Option<Transaction>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Fetch the Merkle proof for an account and the given storage slots.
+    fn get_proof(
+        &self,
+        _address: Address,
+        _slots: &[B256],
+        _block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Fetch the value of a storage slot.
+    ///
+    /// By default, the value is read from a storage proof for the slot.
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        let proof = self.get_proof(address, &[slot], block_id)?;
+        let value = proof.storage_value(slot).unwrap_or_default();
+        Ok(B256::from(value.to_be_bytes::<32>()))
+    }
+
+    /// Fetch the chain id.
+    fn chain_id(&self) -> Result<u64, Error> {
+        Err(Error::Unsupported)
+    }
 }
 
+/// The json-rpc error code nodes return when a request is rate limited.
+pub const RATE_LIMITED_CODE: i64 = -32005;
+
 /// Provider Errors
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    all(feature = "serde", feature = "alloc"),
+    derive(Serialize, Deserialize)
+)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Error {
     /// Block not found.
     BlockNotFound,
+    /// The transport failed to send the request or receive the response.
+    Transport(GenericString),
+    /// The request timed out.
+    Timeout,
+    /// The node returned a json-rpc error.
+    Rpc {
+        /// The json-rpc error code.
+        code: i64,
+        /// The json-rpc error message.
+        message: GenericString,
+    },
+    /// The response could not be decoded.
+    Decode(GenericString),
+    /// The node is rate limiting requests.
+    RateLimited {
+        /// The number of seconds to wait before retrying, if the node sent one.
+        retry_after: Option<u64>,
+    },
+    /// The provider does not support the method.
+    Unsupported,
+    /// An error specific to one provider, defined in its module.
+    #[cfg(feature = "alloc")]
+    Other(OtherError),
+}
+
+impl Error {
+    /// Returns true if the request may succeed when retried.
+    ///
+    /// Transport failures, timeouts and rate limits are transient.
+    /// Json-rpc errors, decode failures, unsupported methods and
+    /// provider-specific errors will fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Error::Transport(_) | Error::Timeout | Error::RateLimited { .. }
+        )
+    }
+
+    /// Wraps an error specific to one provider.
+    #[cfg(feature = "alloc")]
+    pub fn other(error: impl core::error::Error + Send + Sync + 'static) -> Self {
+        Error::Other(OtherError(Arc::new(error)))
+    }
+
+    /// Returns the provider-specific error, if it is an `E`.
+    #[cfg(feature = "alloc")]
+    pub fn downcast_ref<E: core::error::Error + 'static>(&self) -> Option<&E> {
+        match self {
+            Error::Other(e) => e.0.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::BlockNotFound => write!(f, "block not found"),
+            Error::Transport(e) => write!(f, "transport error: {}", e),
+            Error::Timeout => write!(f, "request timed out"),
+            Error::Rpc { code, message } => write!(f, "rpc error {}: {}", code, message),
+            Error::Decode(e) => write!(f, "failed to decode response: {}", e),
+            Error::RateLimited {
+                retry_after: Some(secs),
+            } => write!(f, "rate limited, retry after {}s", secs),
+            Error::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            Error::Unsupported => write!(f, "unsupported method"),
+            #[cfg(feature = "alloc")]
+            Error::Other(e) => write!(f, "{}", e.0),
+        }
+    }
+}
+
+/// An error specific to one provider, wrapped in [Error::Other].
+///
+/// Use [Error::downcast_ref] to get the concrete error back. Two errors
+/// are equal if they are the same error or display the same. Errors are
+/// serialized as their message, so a deserialized error only keeps it.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct OtherError(Arc<dyn core::error::Error + Send + Sync>);
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl Serialize for OtherError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<'de> Deserialize<'de> for OtherError {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let message = alloc::string::String::deserialize(deserializer)?;
+        Ok(OtherError(Arc::new(OtherMessage(message))))
+    }
+}
+
+/// A provider-specific error restored from its message.
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[derive(Debug)]
+struct OtherMessage(alloc::string::String);
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl core::fmt::Display for OtherMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl core::error::Error for OtherMessage {}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for OtherError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq for OtherError {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0.to_string() == other.0.to_string()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Eq for OtherError {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use axos_primitives::{StorageProof, U256};
+
+    /// A provider serving two blocks of receipts and a single storage proof.
+    struct ReceiptsProvider;
+
+    fn log(address: u8) -> Log {
+        Log {
+            address: Address::with_last_byte(address),
+            ..Default::default()
+        }
+    }
+
+    impl Provider for ReceiptsProvider {
+        fn get_block_with_txs(&self, _: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+            Ok(None)
+        }
+
+        fn get_receipts(
+            &self,
+            block_id: BlockId,
+        ) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+            let logs = match block_id {
+                BlockId::Number(1) | BlockId::Hash(_) => vec![log(1), log(2)],
+                BlockId::Number(2) => vec![log(1)],
+                _ => return Ok(None),
+            };
+            Ok(Some(vec![TransactionReceipt {
+                logs,
+                ..Default::default()
+            }]))
+        }
+
+        fn get_proof(
+            &self,
+            address: Address,
+            slots: &[B256],
+            _: BlockId,
+        ) -> Result<AccountProof, Error> {
+            Ok(AccountProof {
+                address,
+                storage_proof: slots
+                    .iter()
+                    .map(|s| StorageProof {
+                        key: U256::from_be_bytes(s.0),
+                        value: U256::from(42),
+                        proof: vec![],
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_default_get_logs_by_range() {
+        let filter = Filter::new()
+            .from_block(1)
+            .to_block(3)
+            .address(Address::with_last_byte(1));
+        let logs = ReceiptsProvider.get_logs(&filter).unwrap();
+        assert_eq!(logs, vec![log(1), log(1)]);
+    }
+
+    #[test]
+    fn test_default_get_logs_by_hash() {
+        let filter = Filter::new().at_block_hash(B256::ZERO);
+        assert_eq!(ReceiptsProvider.get_logs(&filter).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_default_get_logs_open_range() {
+        let filter = Filter::new().from_block(1);
+        assert_eq!(ReceiptsProvider.get_logs(&filter), Err(Error::Unsupported));
+    }
+
+    #[test]
+    fn test_default_get_storage_at() {
+        let value = ReceiptsProvider
+            .get_storage_at(Address::ZERO, B256::with_last_byte(1), BlockId::Number(1))
+            .unwrap();
+        assert_eq!(value, B256::with_last_byte(42));
+    }
+
+    #[test]
+    fn test_default_unsupported() {
+        assert_eq!(ReceiptsProvider.chain_id(), Err(Error::Unsupported));
+        assert_eq!(
+            ReceiptsProvider.get_transaction(B256::ZERO),
+            Err(Error::Unsupported)
+        );
+    }
+
+    #[test]
+    fn test_error_retryable() {
+        assert!(Error::Timeout.is_retryable());
+        assert!(Error::Transport("connection reset".into()).is_retryable());
+        assert!(Error::RateLimited { retry_after: None }.is_retryable());
+        assert!(!Error::BlockNotFound.is_retryable());
+        assert!(!Error::Decode("invalid hex".into()).is_retryable());
+        assert!(!Error::Unsupported.is_retryable());
+        let rpc = Error::Rpc {
+            code: -32601,
+            message: "method not found".into(),
+        };
+        assert!(!rpc.is_retryable());
+        assert_eq!(rpc.to_string(), "rpc error -32601: method not found");
+    }
+
+    #[test]
+    fn test_other_error() {
+        let err = Error::other(core::fmt::Error);
+        assert!(!err.is_retryable());
+        assert!(err.downcast_ref::<core::fmt::Error>().is_some());
+        assert_eq!(err, Error::other(core::fmt::Error));
+        #[cfg(feature = "serde_json")]
+        {
+            let json = serde_json::to_string(&err).unwrap();
+            let restored: Error = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored, err);
+            assert!(restored.downcast_ref::<core::fmt::Error>().is_none());
+        }
+    }
 }
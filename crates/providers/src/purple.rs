@@ -2,73 +2,179 @@
 //!
 //! The purple provider is constructs purple, synchronous methods
 //! that internally use the runtime to execute asynchronous calls.
-#[cfg(feature = "alloc")]
+//!
+//! Requests are sent over HTTP with [reqwless], an async client built on
+//! the [embedded_nal_async] networking traits. The DNS resolver and TCP
+//! stack are pluggable, so the same provider runs on firmware with its
+//! own network driver and on hosts using the [std] adapters in
+//! [crate::client]. Each call is driven to completion with
+//! [block_on][crate::executor::block_on], keeping the [Provider] trait
+//! synchronous.
+//!
+//! ## Example
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = r#"
+```rust,no_run
+use axos_providers::client::{StdDns, StdTcp};
+use axos_providers::provider::Provider;
+use axos_providers::purple::PurpleProvider;
+use axos_primitives::{BlockId, BlockKind};
+
+let provider = PurpleProvider::new("http://localhost:8545", StdTcp, StdDns);
+let block = provider.get_block_with_txs(BlockId::Kind(BlockKind::Latest));
+```
+"#
+)]
+
+use alloc::format;
 use alloc::string::String;
-// use axos_primitives::{BlockHash, BlockId, BlockNumber};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use embedded_nal_async::{Dns, TcpConnect};
+use reqwless::client::HttpClient;
+use reqwless::headers::ContentType;
+use reqwless::request::{Method, RequestBuilder};
+
+use crate::executor::block_on;
+use crate::provider::Error;
+use crate::rpc::JsonRpcProvider;
+use crate::transport::Transport;
+
+/// The default size of the response buffer in bytes.
+pub const DEFAULT_RESPONSE_BUFFER_SIZE: usize = 1 << 20;
 
 /// An HTTP RPC provider with purple-colored functions.
-#[derive(Debug, Clone)]
-pub struct PurpleProvider {
-    /// The base URL for the provider.
-    #[cfg(feature = "alloc")]
+pub type PurpleProvider<T, D> = JsonRpcProvider<PurpleTransport<T, D>>;
+
+impl<T: TcpConnect, D: Dns> PurpleProvider<T, D> {
+    /// Instantiates a new [PurpleProvider] with the given network stack.
+    pub fn new(base: impl Into<String>, tcp: T, dns: D) -> Self {
+        Self::with_transport(PurpleTransport::new(base, tcp, dns))
+    }
+
+    /// Sets the size of the response buffer. Responses larger than
+    /// the buffer fail with [Error::Transport].
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.transport_mut().buffer_size = buffer_size;
+        self
+    }
+}
+
+/// An HTTP [Transport] over a pluggable network stack.
+pub struct PurpleTransport<T, D> {
+    /// The base URL for the transport.
     pub base: String,
-    #[cfg(not(feature = "alloc"))]
-    pub base: &'static str,
-    // The internal HTTP Client.
-    // inner: HttpClient<TokioTcp, StaticDns>,
+    /// The TCP stack used to open connections.
+    tcp: T,
+    /// The DNS resolver used to look up the base URL host.
+    dns: D,
+    /// The size of the response buffer in bytes.
+    buffer_size: usize,
 }
 
-// impl PurpleProvider {
-//     pub fn new(
-//         #[cfg(feature = "alloc")] base: String,
-//         #[cfg(not(feature = "alloc"))] base: &'static str,
-//     ) -> Self {
-//         let mut client = HttpClient::new(TokioTcp, StaticDns);
-//         client.set_timeout(Some(Duration::from_secs(5)));
-//         Self {
-//             base: base,
-//             inner: client
-//         }
-//     }
-// }
-//
-// pub struct Request {
-//     pub jsonrpc: String,
-//     pub method: String,
-//     pub params: Vec<String>,
-//     pub id: u64,
-// }
-//
-// impl Request {
-//     pub fn new(method: String, params: Vec<String>, id: u64) -> Self {
-//         Self {
-//             jsonrpc: "2.0".to_string(),
-//             method: method,
-//             params: params,
-//             id: id,
-//         }
-//     }
-// }
-//
-// impl PurpleProvider {
-//     /// Fetch the block with the given [BlockId].
-//     pub fn get_block_with_txs(&self, block_id: BlockId) -> anyhow::Result<Option<BlockWithTxs>> {
-//         let block_id = match block_id {
-//             BlockId::Hash(hash) => format!("0x{}", hex::encode(hash)),
-//             BlockId::Number(number) => format!("0x{:x}", number),
-//         };
-//         let mut rx_buf = [0; 4096];
-//         let response = self
-//             .inner
-//             .request(Method::POST, &self.base)
-//             .await
-//             .unwrap()
-//             .body(serde_json::to_string(&Request::new("eth_getBlockByHash".to_string(), vec![block_id], 83)).unwrap())
-//             .content_type(ContentType::TextJson)
-//             .send(&mut rx_buf)
-//             .await
-//             .unwrap();
-//         Ok(Some(BlockWithTxs::try_from(response)?))
-//     }
-// }
-//
+impl<T, D> core::fmt::Debug for PurpleTransport<T, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PurpleTransport")
+            .field("base", &self.base)
+            .field("buffer_size", &self.buffer_size)
+            .finish()
+    }
+}
+
+impl<T: TcpConnect, D: Dns> PurpleTransport<T, D> {
+    /// Instantiates a new [PurpleTransport] with the given network stack.
+    pub fn new(base: impl Into<String>, tcp: T, dns: D) -> Self {
+        Self {
+            base: base.into(),
+            tcp,
+            dns,
+            buffer_size: DEFAULT_RESPONSE_BUFFER_SIZE,
+        }
+    }
+
+    /// Posts a json body to the base URL and returns the response body.
+    async fn post(&self, body: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut client = HttpClient::new(&self.tcp, &self.dns);
+        let mut rx_buf = vec![0u8; self.buffer_size];
+        let mut request = client
+            .request(Method::POST, &self.base)
+            .await
+            .map_err(|e| {
+                tracing::warn!(target: "purple_provider", "failed to connect: {:?}", e);
+                Error::Transport(format!("failed to connect: {:?}", e))
+            })?
+            .body(body)
+            .content_type(ContentType::ApplicationJson);
+        let response = request.send(&mut rx_buf).await.map_err(|e| {
+            tracing::warn!(target: "purple_provider", "failed to send request: {:?}", e);
+            Error::Transport(format!("failed to send request: {:?}", e))
+        })?;
+        let body = response.body().read_to_end().await.map_err(|e| {
+            tracing::warn!(target: "purple_provider", "failed to read response: {:?}", e);
+            Error::Transport(format!("failed to read response: {:?}", e))
+        })?;
+        Ok(body.to_vec())
+    }
+}
+
+impl<T: TcpConnect, D: Dns> Transport for PurpleTransport<T, D> {
+    fn send(&self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        block_on(self.post(request))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::client::{StaticDns, StdTcp};
+    use crate::provider::Provider;
+    use crate::test_utils::rpc::{rpc_method, rpc_result, BLOCK};
+    use crate::test_utils::MockServer;
+    use axos_primitives::{BlockId, BlockKind, U64};
+
+    #[test]
+    fn test_purple_get_block() {
+        let server = MockServer::spawn(|_| rpc_result(BLOCK));
+        let provider = PurpleProvider::new(server.url(), StdTcp, StaticDns::loopback());
+        let block = provider
+            .get_block_with_txs(BlockId::Kind(BlockKind::Latest))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.number, Some(U64::from(2)));
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(
+            rpc_method(&server.requests()[0].body),
+            "eth_getBlockByNumber"
+        );
+    }
+
+    #[test]
+    fn test_purple_block_not_found() {
+        let server = MockServer::spawn(|_| rpc_result("null"));
+        let provider = PurpleProvider::new(server.url(), StdTcp, StaticDns::loopback());
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(3)), Ok(None));
+    }
+
+    #[test]
+    fn test_purple_response_too_large() {
+        let server = MockServer::spawn(|_| rpc_result(BLOCK));
+        let provider =
+            PurpleProvider::new(server.url(), StdTcp, StaticDns::loopback()).with_buffer_size(64);
+        assert!(matches!(
+            provider.get_block_with_txs(BlockId::Number(2)),
+            Err(Error::Transport(_))
+        ));
+    }
+
+    #[test]
+    fn test_purple_connection_refused() {
+        let provider = PurpleProvider::new("http://127.0.0.1:1", StdTcp, StaticDns::loopback());
+        assert!(matches!(
+            provider.get_block_with_txs(BlockId::Number(2)),
+            Err(Error::Transport(_))
+        ));
+    }
+}
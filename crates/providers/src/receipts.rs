@@ -0,0 +1,184 @@
+//! Receipt Decoding
+//!
+//! Decodes consensus-encoded receipts, as served by `debug_getRawReceipts`
+//! or stored in era1 archives, into [TransactionReceipt]s. The encoding
+//! carries no transaction context, so the hashes, senders and indices are
+//! filled in from the block the receipts belong to.
+
+use alloc::vec::Vec;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    Address, BlockWithTransactions, Bytes, Log, TransactionReceipt, B256, U256, U64,
+};
+use rlp::{DecoderError, Rlp};
+
+/// The type of a deposit transaction and its receipt.
+const DEPOSIT_TX_TYPE: u8 = 0x7e;
+
+/// Decodes the receipts of a block.
+///
+/// Each receipt is in its binary form: an rlp list for legacy receipts,
+/// or a type byte followed by an rlp list for typed receipts.
+pub fn decode_receipts<'a>(
+    receipts: impl ExactSizeIterator<Item = &'a [u8]>,
+    block: &BlockWithTransactions,
+) -> Result<Vec<TransactionReceipt>, DecoderError> {
+    if receipts.len() != block.transactions.len() {
+        return Err(DecoderError::Custom("receipt count does not match body"));
+    }
+    let mut decoded = Vec::with_capacity(block.transactions.len());
+    let mut log_index = 0u64;
+    let mut previous_gas = U256::ZERO;
+    for (index, (raw, tx)) in receipts.zip(&block.transactions).enumerate() {
+        let (tx_type, receipt) = match raw.first() {
+            Some(&first) if first < 0xc0 => (Some(first), Rlp::new(&raw[1..])),
+            _ => (None, Rlp::new(raw)),
+        };
+        let outcome = receipt.at(0)?.data()?;
+        let cumulative_gas_used = u256(&receipt, 1)?;
+        let logs = receipt
+            .at(3)?
+            .iter()
+            .map(|log| {
+                let decoded = Log {
+                    address: address(&log.at(0)?)?,
+                    topics: log
+                        .at(1)?
+                        .iter()
+                        .map(|t| t.data().and_then(to_b256))
+                        .collect::<Result<_, _>>()?,
+                    data: Bytes::copy_from_slice(log.at(2)?.data()?),
+                    block_hash: block.hash,
+                    block_number: block.number,
+                    transaction_hash: Some(tx.hash),
+                    transaction_index: Some(U64::from(index)),
+                    log_index: Some(U256::from(log_index)),
+                    removed: false,
+                };
+                log_index += 1;
+                Ok(decoded)
+            })
+            .collect::<Result<Vec<_>, DecoderError>>()?;
+        let deposit = tx_type == Some(DEPOSIT_TX_TYPE);
+        let optional = |index: usize| match deposit && receipt.item_count()? > index {
+            true => u256(&receipt, index).map(|n| Some(U64::from(n.saturating_to::<u64>()))),
+            false => Ok(None),
+        };
+        decoded.push(TransactionReceipt {
+            transaction_hash: tx.hash,
+            transaction_index: U64::from(index),
+            block_hash: block.hash,
+            block_number: block.number,
+            from: tx.from,
+            to: tx.to,
+            cumulative_gas_used,
+            gas_used: Some(cumulative_gas_used.saturating_sub(previous_gas)),
+            logs,
+            // Receipts before byzantium carry a state root instead of a status.
+            status: (outcome.len() <= 1).then(|| U64::from(outcome.first().copied().unwrap_or(0))),
+            logs_bloom: receipt
+                .at(2)?
+                .data()?
+                .try_into()
+                .map_err(|_| DecoderError::RlpInvalidLength)?,
+            transaction_type: tx.transaction_type,
+            effective_gas_price: effective_gas_price(tx, block.base_fee_per_gas),
+            deposit_nonce: optional(4)?,
+            deposit_receipt_version: optional(5)?,
+            ..Default::default()
+        });
+        previous_gas = cumulative_gas_used;
+    }
+    Ok(decoded)
+}
+
+/// Returns the gas price a transaction paid.
+fn effective_gas_price(tx: &Transaction, base_fee: Option<U256>) -> Option<U256> {
+    match (tx.max_fee_per_gas, tx.max_priority_fee_per_gas, base_fee) {
+        (Some(max_fee), Some(priority_fee), Some(base_fee)) => {
+            Some(max_fee.min(base_fee.saturating_add(priority_fee)))
+        }
+        _ => tx.gas_price,
+    }
+}
+
+/// Decodes a big-endian integer field.
+fn u256(rlp: &Rlp<'_>, index: usize) -> Result<U256, DecoderError> {
+    let data = rlp.at(index)?.data()?;
+    if data.len() > 32 {
+        return Err(DecoderError::RlpIsTooBig);
+    }
+    Ok(U256::from_be_slice(data))
+}
+
+/// Converts 32 bytes into a hash.
+fn to_b256(data: &[u8]) -> Result<B256, DecoderError> {
+    B256::try_from(data).map_err(|_| DecoderError::RlpInvalidLength)
+}
+
+/// Decodes an address.
+fn address(rlp: &Rlp<'_>) -> Result<Address, DecoderError> {
+    Address::try_from(rlp.data()?).map_err(|_| DecoderError::RlpInvalidLength)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use rlp::RlpStream;
+
+    /// Encodes a receipt with a single log in its binary form.
+    fn encode(tx_type: Option<u8>, status: u8, cumulative_gas: u64) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&status).append(&cumulative_gas);
+        stream.append(&[0u8; 256].as_slice());
+        stream.begin_list(1).begin_list(3);
+        stream.append(&Address::with_last_byte(1).as_slice());
+        stream
+            .begin_list(1)
+            .append(&B256::with_last_byte(2).as_slice());
+        stream.append(&[3u8].as_slice());
+        let mut raw = tx_type.map(|t| vec![t]).unwrap_or_default();
+        raw.extend_from_slice(&stream.out());
+        raw
+    }
+
+    fn block(count: usize) -> BlockWithTransactions {
+        BlockWithTransactions {
+            hash: Some(B256::with_last_byte(0xbb)),
+            number: Some(U64::from(9)),
+            transactions: (0..count)
+                .map(|i| Transaction {
+                    hash: B256::with_last_byte(i as u8),
+                    from: Address::with_last_byte(0xf0),
+                    gas_price: Some(U256::from(7)),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_receipts() {
+        let raw = [encode(None, 1, 21_000), encode(Some(2), 0, 50_000)];
+        let receipts = decode_receipts(raw.iter().map(|r| r.as_slice()), &block(2)).unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert!(receipts[0].is_success());
+        assert!(!receipts[1].is_success());
+        assert_eq!(receipts[1].gas_used, Some(U256::from(29_000)));
+        assert_eq!(receipts[1].transaction_hash, B256::with_last_byte(1));
+        assert_eq!(receipts[1].block_hash, Some(B256::with_last_byte(0xbb)));
+        assert_eq!(receipts[1].effective_gas_price, Some(U256::from(7)));
+        assert_eq!(receipts[1].logs[0].log_index, Some(U256::from(1)));
+        assert_eq!(receipts[1].logs[0].topics, vec![B256::with_last_byte(2)]);
+        assert_eq!(receipts[0].logs[0].address, Address::with_last_byte(1));
+    }
+
+    #[test]
+    fn test_decode_receipts_count_mismatch() {
+        let raw = [encode(None, 1, 21_000)];
+        assert!(decode_receipts(raw.iter().map(|r| r.as_slice()), &block(2)).is_err());
+    }
+}
@@ -0,0 +1,453 @@
+//! Record and Replay Providers
+//!
+//! The [RecordingProvider] wraps a [Provider] and records every call and
+//! its response into a [Fixture]. The [ReplayProvider] serves responses
+//! from a fixture without network access, so derivation can run offline
+//! against real chain data, for example in CI.
+//!
+//! Calls are matched by method name and json-encoded parameters. When the
+//! same call was recorded several times, the responses are replayed in
+//! the recorded order and the last one is repeated once they run out.
+//! Calls that were never recorded fail with an [UnrecordedError].
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use axos_providers::http::HttpProvider;
+//! use axos_providers::provider::Provider;
+//! use axos_providers::replay::{RecordingProvider, ReplayProvider};
+//! use axos_primitives::{BlockId, BlockKind};
+//!
+//! // Record a session against a live node.
+//! let recorder = RecordingProvider::new(HttpProvider::new("http://localhost:8545"), "fixture.json");
+//! let live = recorder.get_block_with_txs(BlockId::Kind(BlockKind::Finalized));
+//! recorder.flush().unwrap();
+//!
+//! // Replay it offline.
+//! let replay = ReplayProvider::open("fixture.json").unwrap();
+//! assert_eq!(replay.get_block_with_txs(BlockId::Kind(BlockKind::Finalized)), live);
+//! ```
+
+use core::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    AccountProof, Address, BlockId, BlockWithTransactions, Filter, Log, TransactionReceipt, B256,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::provider::{Error, Provider};
+
+/// Returned by a [ReplayProvider] for a call that was never recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnrecordedError(pub String);
+
+impl core::fmt::Display for UnrecordedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "no recorded response for {}", self.0)
+    }
+}
+
+impl std::error::Error for UnrecordedError {}
+
+impl From<UnrecordedError> for Error {
+    fn from(e: UnrecordedError) -> Self {
+        Error::other(e)
+    }
+}
+
+/// A recorded provider call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// The provider method.
+    pub method: String,
+    /// The json-encoded method parameters.
+    pub params: Value,
+    /// The json-encoded response.
+    pub response: Result<Value, Error>,
+}
+
+/// A sequence of recorded provider calls.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// The recorded calls, in order.
+    pub entries: Vec<Entry>,
+}
+
+impl Fixture {
+    /// Loads a fixture from a json file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        serde_json::from_slice(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Saves the fixture to a json file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let bytes = serde_json::to_vec_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, bytes)
+    }
+}
+
+/// A [Provider] that records every call to a [Fixture] file.
+///
+/// The fixture is written by [RecordingProvider::flush], and when the
+/// provider is dropped with calls that were not flushed yet. A provider
+/// dropped while the thread panics, or before recording anything, leaves
+/// the fixture file untouched.
+#[derive(Debug)]
+pub struct RecordingProvider<P> {
+    /// The inner provider.
+    inner: P,
+    /// The fixture file path.
+    path: PathBuf,
+    /// The calls recorded so far.
+    fixture: RefCell<Fixture>,
+    /// Whether calls were recorded since the last flush.
+    dirty: Cell<bool>,
+}
+
+impl<P> RecordingProvider<P> {
+    /// Instantiates a new [RecordingProvider] writing to the given path.
+    pub fn new(inner: P, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            fixture: RefCell::new(Fixture::default()),
+            dirty: Cell::new(false),
+        }
+    }
+
+    /// Returns the inner provider.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns a copy of the calls recorded so far.
+    pub fn fixture(&self) -> Fixture {
+        self.fixture.borrow().clone()
+    }
+
+    /// Writes the recorded calls to the fixture file.
+    pub fn flush(&self) -> io::Result<()> {
+        self.fixture.borrow().save(&self.path)?;
+        self.dirty.set(false);
+        Ok(())
+    }
+
+    /// Records a call and its response.
+    fn record<T: Serialize>(&self, method: &str, params: Value, response: &Result<T, Error>) {
+        let response = match response {
+            Ok(value) => match serde_json::to_value(value) {
+                Ok(value) => Ok(value),
+                Err(e) => {
+                    tracing::warn!(target: "replay", "failed to record {}: {}", method, e);
+                    return;
+                }
+            },
+            Err(e) => Err(e.clone()),
+        };
+        self.fixture.borrow_mut().entries.push(Entry {
+            method: method.to_string(),
+            params,
+            response,
+        });
+        self.dirty.set(true);
+    }
+
+    /// Forwards a call to the inner provider and records it.
+    fn call<T: Serialize>(
+        &self,
+        method: &str,
+        params: Value,
+        f: impl FnOnce(&P) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let response = f(&self.inner);
+        self.record(method, params, &response);
+        response
+    }
+}
+
+impl<P> Drop for RecordingProvider<P> {
+    fn drop(&mut self) {
+        if !self.dirty.get() || std::thread::panicking() {
+            return;
+        }
+        if let Err(e) = self.flush() {
+            tracing::error!(target: "replay", "failed to write fixture {:?}: {}", self.path, e);
+        }
+    }
+}
+
+impl<P: Provider> Provider for RecordingProvider<P> {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        self.call("get_block_with_txs", json!([block_id]), |p| {
+            p.get_block_with_txs(block_id)
+        })
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        self.call("get_receipts", json!([block_id]), |p| {
+            p.get_receipts(block_id)
+        })
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        self.call("get_logs", json!([filter]), |p| p.get_logs(filter))
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        self.call("get_transaction", json!([hash]), |p| {
+            p.get_transaction(hash)
+        })
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.call("get_proof", json!([address, slots, block_id]), |p| {
+            p.get_proof(address, slots, block_id)
+        })
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        self.call("get_storage_at", json!([address, slot, block_id]), |p| {
+            p.get_storage_at(address, slot, block_id)
+        })
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.call("chain_id", json!([]), |p| p.chain_id())
+    }
+}
+
+/// The recorded responses for a single call.
+#[derive(Debug, Clone, Default)]
+struct Responses {
+    /// The responses, in recorded order.
+    responses: Vec<Result<Value, Error>>,
+    /// The index of the next response to serve.
+    next: usize,
+}
+
+/// A [Provider] that serves recorded responses from a [Fixture].
+#[derive(Debug, Default)]
+pub struct ReplayProvider {
+    /// The recorded responses, keyed by method and encoded params.
+    calls: RefCell<BTreeMap<(String, String), Responses>>,
+}
+
+impl From<Fixture> for ReplayProvider {
+    fn from(fixture: Fixture) -> Self {
+        let mut calls: BTreeMap<(String, String), Responses> = BTreeMap::new();
+        for entry in fixture.entries {
+            calls
+                .entry((entry.method, entry.params.to_string()))
+                .or_default()
+                .responses
+                .push(entry.response);
+        }
+        Self {
+            calls: RefCell::new(calls),
+        }
+    }
+}
+
+impl ReplayProvider {
+    /// Loads a [ReplayProvider] from a fixture file.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Fixture::load(path).map(Self::from)
+    }
+
+    /// Serves the next recorded response for a call.
+    fn replay<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, Error> {
+        let params = params.to_string();
+        let mut calls = self.calls.borrow_mut();
+        let Some(call) = calls.get_mut(&(method.to_string(), params.clone())) else {
+            tracing::warn!(target: "replay", "no recorded response for {} {}", method, params);
+            return Err(UnrecordedError(std::format!("{} {}", method, params)).into());
+        };
+        let response = call.responses[call.next].clone();
+        if call.next + 1 < call.responses.len() {
+            call.next += 1;
+        }
+        serde_json::from_value(response?).map_err(|e| Error::Decode(e.to_string()))
+    }
+}
+
+impl Provider for ReplayProvider {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        self.replay("get_block_with_txs", json!([block_id]))
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        self.replay("get_receipts", json!([block_id]))
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        self.replay("get_logs", json!([filter]))
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        self.replay("get_transaction", json!([hash]))
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.replay("get_proof", json!([address, slots, block_id]))
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        self.replay("get_storage_at", json!([address, slot, block_id]))
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.replay("chain_id", json!([]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_primitives::{BlockKind, U64};
+
+    /// A provider whose latest block advances on every call.
+    #[derive(Default)]
+    struct Advancing(Cell<u64>);
+
+    impl Provider for Advancing {
+        fn get_block_with_txs(&self, id: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+            let number = match id {
+                BlockId::Number(n) if n > 100 => return Err(Error::Timeout),
+                BlockId::Number(n) => n,
+                _ => {
+                    self.0.set(self.0.get() + 1);
+                    self.0.get()
+                }
+            };
+            Ok(Some(BlockWithTransactions {
+                number: Some(U64::from(number)),
+                hash: Some(B256::with_last_byte(number as u8)),
+                ..Default::default()
+            }))
+        }
+
+        fn chain_id(&self) -> Result<u64, Error> {
+            Ok(10)
+        }
+    }
+
+    fn fixture_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(std::format!("axos-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let path = fixture_path("record");
+        let latest = BlockId::Kind(BlockKind::Latest);
+        let recorded = {
+            let recorder = RecordingProvider::new(Advancing::default(), &path);
+            let calls = [
+                recorder.get_block_with_txs(latest),
+                recorder.get_block_with_txs(latest),
+                recorder.get_block_with_txs(BlockId::Number(7)),
+                recorder.get_block_with_txs(BlockId::Number(101)),
+            ];
+            assert_eq!(recorder.chain_id(), Ok(10));
+            assert_eq!(recorder.fixture().entries.len(), 5);
+            calls
+        };
+
+        let replay = ReplayProvider::open(&path).unwrap();
+        assert_eq!(replay.chain_id(), Ok(10));
+        assert_eq!(replay.get_block_with_txs(latest), recorded[0]);
+        assert_eq!(replay.get_block_with_txs(latest), recorded[1]);
+        // The last response repeats once the recorded ones run out.
+        assert_eq!(replay.get_block_with_txs(latest), recorded[1]);
+        assert_eq!(replay.get_block_with_txs(BlockId::Number(7)), recorded[2]);
+        assert_eq!(
+            replay.get_block_with_txs(BlockId::Number(101)),
+            Err(Error::Timeout)
+        );
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_drop_keeps_fixture_without_new_calls() {
+        let path = fixture_path("keep");
+        {
+            let recorder = RecordingProvider::new(Advancing::default(), &path);
+            recorder.chain_id().unwrap();
+        }
+        let fixture = Fixture::load(&path).unwrap();
+        assert_eq!(fixture.entries.len(), 1);
+
+        // Neither a recorder without calls nor a flushed one rewrites it.
+        drop(RecordingProvider::new(Advancing::default(), &path));
+        let recorder = RecordingProvider::new(Advancing::default(), &path);
+        recorder.chain_id().unwrap();
+        recorder.flush().unwrap();
+        fs::write(&path, serde_json::to_vec(&fixture).unwrap()).unwrap();
+        drop(recorder);
+        assert_eq!(Fixture::load(&path).unwrap(), fixture);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_replay_unrecorded() {
+        let replay = ReplayProvider::from(Fixture::default());
+        let err = replay.get_block_with_txs(BlockId::Number(1)).unwrap_err();
+        assert!(err.downcast_ref::<UnrecordedError>().is_some());
+        assert!(!err.is_retryable());
+        let err = replay.get_transaction(B256::ZERO).unwrap_err();
+        assert!(err.downcast_ref::<UnrecordedError>().is_some());
+    }
+
+    #[test]
+    fn test_fixture_round_trip() {
+        let path = fixture_path("fixture");
+        let fixture = Fixture {
+            entries: std::vec![Entry {
+                method: "chain_id".to_string(),
+                params: json!([]),
+                response: Err(Error::RateLimited {
+                    retry_after: Some(1)
+                }),
+            }],
+        };
+        fixture.save(&path).unwrap();
+        assert_eq!(Fixture::load(&path).unwrap(), fixture);
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,486 @@
+//! Retry Provider
+//!
+//! The [RetryProvider] wraps a [Provider] and retries failed requests
+//! with exponential backoff and jitter, as configured by a [RetryPolicy].
+//! An optional [TokenBucket] spaces requests out to stay under a rate
+//! limit. All waiting goes through a [Clock], so tests can use a
+//! [ManualClock][crate::clock::ManualClock] instead of sleeping.
+//!
+//! When the node sends a rate limit with a `Retry-After` delay, the
+//! provider waits at least that long before retrying.
+
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::time::Duration;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    AccountProof, Address, BlockId, BlockWithTransactions, Filter, Log, TransactionReceipt, B256,
+};
+
+use crate::clock::Clock;
+use crate::provider::{Error, Provider};
+
+/// A retry policy with exponential backoff.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// The delay before the first retry.
+    pub initial_backoff: Duration,
+    /// The upper bound on the delay between attempts.
+    pub max_backoff: Duration,
+    /// The factor the delay grows by after each retry.
+    pub multiplier: u32,
+    /// The percentage of each delay, from 0 to 100, that is randomly
+    /// shaved off so clients do not retry in lockstep.
+    pub jitter: u8,
+    /// Returns true if an error should be retried.
+    pub retryable: fn(&Error) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2,
+            jitter: 20,
+            retryable: Error::is_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum number of attempts.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the initial and maximum backoff.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets the jitter percentage. Values above 100 are clamped.
+    pub fn with_jitter(mut self, jitter: u8) -> Self {
+        self.jitter = jitter.min(100);
+        self
+    }
+
+    /// Sets the check deciding which errors are retried.
+    pub fn with_retryable(mut self, retryable: fn(&Error) -> bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Returns the delay before retry number `retry`, starting at zero.
+    /// `random` is any uniformly distributed value used for jitter.
+    pub fn delay(&self, retry: u32, random: u64) -> Duration {
+        let factor = (self.multiplier.max(1) as u128).saturating_pow(retry);
+        let base = self
+            .initial_backoff
+            .as_nanos()
+            .saturating_mul(factor)
+            .min(self.max_backoff.as_nanos());
+        let spread = base * self.jitter.min(100) as u128 / 100;
+        let shaved = if spread == 0 {
+            0
+        } else {
+            random as u128 % (spread + 1)
+        };
+        duration_from_nanos(base - shaved)
+    }
+}
+
+/// Converts nanoseconds to a [Duration], saturating at [u64::MAX] nanoseconds.
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::from_nanos(nanos.min(u64::MAX as u128) as u64)
+}
+
+/// A token bucket rate limiter.
+///
+/// The bucket holds up to `capacity` tokens and gains one every
+/// `refill_interval`. Each request takes a token, waiting for one
+/// to be added if the bucket is empty.
+#[derive(Debug)]
+pub struct TokenBucket {
+    /// The maximum number of tokens.
+    capacity: u32,
+    /// The time it takes to add one token.
+    refill_interval: Duration,
+    /// The tokens currently available.
+    tokens: Cell<u32>,
+    /// The time of the last refill.
+    last_refill: Cell<Option<Duration>>,
+}
+
+impl TokenBucket {
+    /// Instantiates a full [TokenBucket].
+    pub fn new(capacity: u32, refill_interval: Duration) -> Self {
+        Self {
+            capacity,
+            refill_interval,
+            tokens: Cell::new(capacity),
+            last_refill: Cell::new(None),
+        }
+    }
+
+    /// Instantiates a [TokenBucket] allowing `rate` requests per second,
+    /// with bursts of up to `rate` requests.
+    pub fn per_second(rate: u32) -> Self {
+        let rate = rate.max(1);
+        Self::new(rate, Duration::from_secs(1) / rate)
+    }
+
+    /// Returns the tokens currently available.
+    pub fn available(&self) -> u32 {
+        self.tokens.get()
+    }
+
+    /// Adds the tokens earned since the last refill.
+    fn refill(&self, now: Duration) {
+        let Some(last) = self.last_refill.get() else {
+            self.last_refill.set(Some(now));
+            return;
+        };
+        let interval = self.refill_interval.as_nanos().max(1);
+        let earned = now.saturating_sub(last).as_nanos() / interval;
+        if earned == 0 {
+            return;
+        }
+        let tokens = (self.tokens.get() as u128 + earned).min(self.capacity as u128) as u32;
+        self.tokens.set(tokens);
+        if tokens == self.capacity {
+            self.last_refill.set(Some(now));
+        } else {
+            let spent = duration_from_nanos(earned * interval);
+            self.last_refill.set(Some(last + spent));
+        }
+    }
+
+    /// Takes a token, sleeping on the clock until one is available.
+    pub fn acquire<C: Clock + ?Sized>(&self, clock: &C) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.refill(clock.now());
+        while self.tokens.get() == 0 {
+            let last = self.last_refill.get().unwrap_or_default();
+            let ready = last + self.refill_interval;
+            clock.sleep(
+                ready
+                    .saturating_sub(clock.now())
+                    .max(Duration::from_nanos(1)),
+            );
+            self.refill(clock.now());
+        }
+        self.tokens.set(self.tokens.get() - 1);
+    }
+}
+
+/// A [Provider] that retries failed requests.
+#[derive(Debug)]
+pub struct RetryProvider<P, C> {
+    /// The inner provider.
+    inner: P,
+    /// The clock used to wait between attempts.
+    clock: C,
+    /// The retry policy.
+    policy: RetryPolicy,
+    /// The optional rate limiter.
+    bucket: Option<TokenBucket>,
+    /// The jitter random number generator state.
+    seed: Cell<u64>,
+}
+
+impl<P: Provider, C: Clock> RetryProvider<P, C> {
+    /// Instantiates a new [RetryProvider] with the default [RetryPolicy].
+    pub fn new(inner: P, clock: C) -> Self {
+        Self {
+            inner,
+            clock,
+            policy: RetryPolicy::default(),
+            bucket: None,
+            seed: Cell::new(0x9e37_79b9_7f4a_7c15),
+        }
+    }
+
+    /// Sets the retry policy.
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Limits the request rate with a token bucket.
+    pub fn with_rate_limit(mut self, bucket: TokenBucket) -> Self {
+        self.bucket = Some(bucket);
+        self
+    }
+
+    /// Seeds the jitter random number generator.
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.seed.set(seed.max(1));
+        self
+    }
+
+    /// Returns the inner provider.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the clock.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the retry policy.
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Returns the next xorshift random number.
+    fn next_random(&self) -> u64 {
+        let mut x = self.seed.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.seed.set(x);
+        x
+    }
+
+    /// Runs a request, retrying it according to the policy.
+    fn call<T>(&self, method: &str, f: impl Fn(&P) -> Result<T, Error>) -> Result<T, Error> {
+        let mut retry = 0;
+        loop {
+            if let Some(bucket) = &self.bucket {
+                bucket.acquire(&self.clock);
+            }
+            let err = match f(&self.inner) {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            if retry + 1 >= self.policy.max_attempts || !(self.policy.retryable)(&err) {
+                return Err(err);
+            }
+            let mut delay = self.policy.delay(retry, self.next_random());
+            if let Error::RateLimited {
+                retry_after: Some(secs),
+            } = err
+            {
+                delay = delay.max(Duration::from_secs(secs));
+            }
+            tracing::warn!(
+                target: "retry_provider",
+                "{} failed: {}. Retrying in {:?}",
+                method,
+                err,
+                delay
+            );
+            self.clock.sleep(delay);
+            retry += 1;
+        }
+    }
+}
+
+impl<P: Provider, C: Clock> Provider for RetryProvider<P, C> {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        self.call("get_block_with_txs", |p| p.get_block_with_txs(block_id))
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        self.call("get_receipts", |p| p.get_receipts(block_id))
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        self.call("get_logs", |p| p.get_logs(filter))
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        self.call("get_transaction", |p| p.get_transaction(hash))
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.call("get_proof", |p| p.get_proof(address, slots, block_id))
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        self.call("get_storage_at", |p| {
+            p.get_storage_at(address, slot, block_id)
+        })
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.call("chain_id", |p| p.chain_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use alloc::vec;
+
+    /// A provider that fails with the given error a number of times before succeeding.
+    struct FlakyProvider {
+        failures: Cell<u32>,
+        error: Error,
+        calls: Cell<u32>,
+    }
+
+    impl FlakyProvider {
+        fn new(failures: u32, error: Error) -> Self {
+            Self {
+                failures: Cell::new(failures),
+                error,
+                calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl Provider for FlakyProvider {
+        fn get_block_with_txs(&self, _: BlockId) -> Result<Option<BlockWithTransactions>, Error> {
+            self.calls.set(self.calls.get() + 1);
+            if self.failures.get() > 0 {
+                self.failures.set(self.failures.get() - 1);
+                return Err(self.error.clone());
+            }
+            Ok(None)
+        }
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(300))
+            .with_jitter(0)
+    }
+
+    #[test]
+    fn test_policy_delay() {
+        let policy = policy();
+        assert_eq!(policy.delay(0, 0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1, 0), Duration::from_millis(200));
+        assert_eq!(policy.delay(2, 0), Duration::from_millis(300));
+        assert_eq!(policy.delay(60, 0), Duration::from_millis(300));
+
+        let jittered = policy.with_jitter(50);
+        for random in [0, 7, u64::MAX] {
+            let delay = jittered.delay(1, random);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_retries_transient_errors() {
+        let clock = ManualClock::new();
+        let provider =
+            RetryProvider::new(FlakyProvider::new(3, Error::Timeout), &clock).with_policy(policy());
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(1)), Ok(None));
+        assert_eq!(provider.inner().calls.get(), 4);
+        assert_eq!(
+            clock.sleeps(),
+            vec![
+                Duration::from_millis(100),
+                Duration::from_millis(200),
+                Duration::from_millis(300)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gives_up_after_max_attempts() {
+        let clock = ManualClock::new();
+        let provider = RetryProvider::new(FlakyProvider::new(10, Error::Timeout), &clock)
+            .with_policy(policy().with_max_attempts(3));
+        assert_eq!(
+            provider.get_block_with_txs(BlockId::Number(1)),
+            Err(Error::Timeout)
+        );
+        assert_eq!(provider.inner().calls.get(), 3);
+    }
+
+    #[test]
+    fn test_fatal_errors_not_retried() {
+        let clock = ManualClock::new();
+        let error = Error::Decode("bad block".into());
+        let provider =
+            RetryProvider::new(FlakyProvider::new(1, error.clone()), &clock).with_policy(policy());
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(1)), Err(error));
+        assert_eq!(provider.inner().calls.get(), 1);
+        assert!(clock.sleeps().is_empty());
+    }
+
+    #[test]
+    fn test_custom_retryable_check() {
+        let clock = ManualClock::new();
+        let provider = RetryProvider::new(FlakyProvider::new(1, Error::BlockNotFound), &clock)
+            .with_policy(policy().with_retryable(|e| *e == Error::BlockNotFound));
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(1)), Ok(None));
+        assert_eq!(provider.inner().calls.get(), 2);
+    }
+
+    #[test]
+    fn test_honors_retry_after() {
+        let clock = ManualClock::new();
+        let error = Error::RateLimited {
+            retry_after: Some(2),
+        };
+        let provider =
+            RetryProvider::new(FlakyProvider::new(1, error), &clock).with_policy(policy());
+        assert_eq!(provider.get_block_with_txs(BlockId::Number(1)), Ok(None));
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(2)]);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let clock = ManualClock::new();
+        let bucket = TokenBucket::new(2, Duration::from_millis(500));
+        bucket.acquire(&clock);
+        bucket.acquire(&clock);
+        assert_eq!(bucket.available(), 0);
+        assert!(clock.sleeps().is_empty());
+
+        bucket.acquire(&clock);
+        assert_eq!(clock.now(), Duration::from_millis(500));
+
+        clock.advance(Duration::from_secs(10));
+        bucket.acquire(&clock);
+        assert_eq!(bucket.available(), 1);
+    }
+
+    #[test]
+    fn test_rate_limited_provider() {
+        let clock = ManualClock::new();
+        let provider = RetryProvider::new(FlakyProvider::new(0, Error::Timeout), &clock)
+            .with_rate_limit(TokenBucket::per_second(2));
+        for _ in 0..4 {
+            provider.get_block_with_txs(BlockId::Number(1)).unwrap();
+        }
+        assert_eq!(clock.now(), Duration::from_secs(1));
+    }
+}
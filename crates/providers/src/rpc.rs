@@ -0,0 +1,965 @@
+//! JSON-RPC Codec
+//!
+//! Request and response envelopes for the Ethereum json-rpc api, and the
+//! [JsonRpcProvider], which encodes [Provider] calls as json-rpc requests
+//! and sends them over any [Transport].
+//!
+//! Receipts are fetched with one of several methods, chosen by a
+//! [ReceiptStrategy], since each node and rpc vendor supports a different
+//! subset of them.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::Cell;
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    AccountProof, Address, BlockId, BlockWithTransactions, Bytes, Filter, Log, TransactionReceipt,
+    B256, U64,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::provider::{Error, Provider, RATE_LIMITED_CODE};
+use crate::transport::Transport;
+
+/// The json-rpc protocol version.
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// The json-rpc error code for a method the node does not implement.
+pub const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
+/// The [EIP-1474] error code for a method the node does not support.
+///
+/// [EIP-1474]: https://eips.ethereum.org/EIPS/eip-1474
+pub const METHOD_NOT_SUPPORTED_CODE: i64 = -32004;
+
+/// The json-rpc error code nodes use for generic server errors.
+pub const SERVER_ERROR_CODE: i64 = -32000;
+
+/// The default number of receipts requested in each json-rpc batch by
+/// [ReceiptStrategy::TransactionReceipts].
+pub const DEFAULT_RECEIPT_BATCH_SIZE: usize = 16;
+
+/// How a [JsonRpcProvider] fetches the receipts of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReceiptStrategy {
+    /// Probes the endpoint on first use, trying `eth_getBlockReceipts`,
+    /// then `debug_getRawReceipts`, then `eth_getTransactionReceipt`,
+    /// and keeps the first method it supports.
+    #[default]
+    Auto,
+    /// Fetches every receipt with a single `eth_getBlockReceipts` request.
+    BlockReceipts,
+    /// Fetches each receipt with `eth_getTransactionReceipt`, sending the
+    /// requests in json-rpc batches one after another. A batch the node
+    /// rejects is retried one request at a time.
+    TransactionReceipts {
+        /// The number of receipts requested in each json-rpc batch.
+        /// A batch size of one sends each request on its own.
+        batch_size: usize,
+    },
+    /// Fetches the consensus encoded receipts with `debug_getRawReceipts`
+    /// and decodes them against the block.
+    RawReceipts,
+}
+
+/// A json-rpc request.
+#[derive(Debug, Clone, Serialize)]
+pub struct Request<'a, P> {
+    /// The json-rpc protocol version.
+    pub jsonrpc: &'static str,
+    /// The method name.
+    pub method: &'a str,
+    /// The method params.
+    pub params: P,
+    /// The request id.
+    pub id: u64,
+}
+
+impl<'a, P> Request<'a, P> {
+    /// Instantiates a new [Request].
+    pub fn new(method: &'a str, params: P, id: u64) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            method,
+            params,
+            id,
+        }
+    }
+}
+
+/// A json-rpc response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Response<R> {
+    /// The request id.
+    pub id: Option<u64>,
+    /// The result, if the request succeeded.
+    pub result: Option<R>,
+    /// The error, if the request failed.
+    pub error: Option<RpcError>,
+}
+
+/// A json-rpc error object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcError {
+    /// The error code.
+    pub code: i64,
+    /// The error message.
+    pub message: String,
+}
+
+impl From<RpcError> for Error {
+    fn from(error: RpcError) -> Self {
+        match error.code {
+            RATE_LIMITED_CODE => Error::RateLimited { retry_after: None },
+            code => Error::Rpc {
+                code,
+                message: error.message,
+            },
+        }
+    }
+}
+
+/// Returns the method and params used to fetch a block with full
+/// transactions for the given [BlockId].
+pub fn block_with_txs_request(block_id: BlockId) -> (&'static str, Value) {
+    match block_id {
+        BlockId::Hash(hash) => ("eth_getBlockByHash", serde_json::json!([hash, true])),
+        BlockId::Number(number) => (
+            "eth_getBlockByNumber",
+            Value::Array(vec![
+                serde_json::json!(U64::from(number)),
+                Value::Bool(true),
+            ]),
+        ),
+        BlockId::Kind(kind) => (
+            "eth_getBlockByNumber",
+            Value::Array(vec![Value::from(kind.as_tag()), Value::Bool(true)]),
+        ),
+    }
+}
+
+/// Returns the json-rpc block parameter for the given [BlockId].
+/// Block hashes use the [EIP-1898] object form.
+///
+/// [EIP-1898]: https://eips.ethereum.org/EIPS/eip-1898
+pub fn block_param(block_id: BlockId) -> Value {
+    match block_id {
+        BlockId::Hash(hash) => serde_json::json!({ "blockHash": hash }),
+        BlockId::Number(number) => serde_json::json!(U64::from(number)),
+        BlockId::Kind(kind) => Value::from(kind.as_tag()),
+    }
+}
+
+/// Returns the json-rpc filter object for the given [Filter].
+pub fn filter_param(filter: &Filter) -> Value {
+    let mut param = serde_json::Map::new();
+    match filter.block_hash {
+        Some(hash) => {
+            param.insert("blockHash".into(), serde_json::json!(hash));
+        }
+        None => {
+            if let Some(from) = filter.from_block {
+                param.insert("fromBlock".into(), serde_json::json!(U64::from(from)));
+            }
+            if let Some(to) = filter.to_block {
+                param.insert("toBlock".into(), serde_json::json!(U64::from(to)));
+            }
+        }
+    }
+    if !filter.address.is_empty() {
+        param.insert("address".into(), serde_json::json!(filter.address));
+    }
+    if !filter.topics.is_empty() {
+        param.insert("topics".into(), serde_json::json!(filter.topics));
+    }
+    Value::Object(param)
+}
+
+/// Returns true if the error means the node does not serve the method.
+fn is_unsupported_method(error: &Error) -> bool {
+    match error {
+        Error::Unsupported => true,
+        Error::Rpc {
+            code: METHOD_NOT_FOUND_CODE | METHOD_NOT_SUPPORTED_CODE,
+            ..
+        } => true,
+        // Some nodes report a missing method as a generic server error, so
+        // only then fall back to the message.
+        Error::Rpc {
+            code: SERVER_ERROR_CODE,
+            message,
+        } => {
+            let message = message.to_lowercase();
+            message.contains("method")
+                && (message.contains("does not exist")
+                    || message.contains("not available")
+                    || message.contains("not supported")
+                    || message.contains("unsupported"))
+        }
+        _ => false,
+    }
+}
+
+/// A json-rpc [Provider] over a [Transport].
+#[derive(Debug)]
+pub struct JsonRpcProvider<T> {
+    /// The underlying transport.
+    transport: T,
+    /// The next request id.
+    id: Cell<u64>,
+    /// The configured receipt strategy.
+    receipts: ReceiptStrategy,
+    /// The receipt strategy detected by [ReceiptStrategy::Auto].
+    detected: Cell<Option<ReceiptStrategy>>,
+}
+
+impl<T: Clone> Clone for JsonRpcProvider<T> {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            id: Cell::new(self.id.get()),
+            receipts: self.receipts,
+            detected: Cell::new(self.detected.get()),
+        }
+    }
+}
+
+impl<T: Transport> From<T> for JsonRpcProvider<T> {
+    fn from(transport: T) -> Self {
+        Self::with_transport(transport)
+    }
+}
+
+impl<T: Transport> JsonRpcProvider<T> {
+    /// Instantiates a new [JsonRpcProvider] over the given transport.
+    pub fn with_transport(transport: T) -> Self {
+        Self {
+            transport,
+            id: Cell::new(0),
+            receipts: ReceiptStrategy::default(),
+            detected: Cell::new(None),
+        }
+    }
+
+    /// Sets how receipts are fetched.
+    pub fn with_receipt_strategy(mut self, strategy: ReceiptStrategy) -> Self {
+        self.receipts = strategy;
+        self.detected.set(None);
+        self
+    }
+
+    /// Returns the receipt strategy in use.
+    /// [ReceiptStrategy::Auto] is returned until a method has been detected.
+    pub fn receipt_strategy(&self) -> ReceiptStrategy {
+        self.detected.get().unwrap_or(self.receipts)
+    }
+
+    /// Returns a reference to the underlying transport.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Returns a mutable reference to the underlying transport.
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Returns the next request id.
+    fn next_id(&self) -> u64 {
+        let id = self.id.get();
+        self.id.set(id.wrapping_add(1));
+        id
+    }
+
+    /// Sends a json-rpc request and decodes its result.
+    /// A missing or `null` result is decoded from [Value::Null].
+    pub fn request<P: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<R, Error> {
+        let request = Request::new(method, params, self.next_id());
+        let body = serde_json::to_vec(&request).map_err(|e| Error::Decode(e.to_string()))?;
+        tracing::trace!(target: "rpc_provider", "sending {} request", method);
+        let response = self.transport.send(&body)?;
+        let response: Response<Value> = serde_json::from_slice(&response).map_err(|e| {
+            tracing::warn!(target: "rpc_provider", "invalid {} response: {}", method, e);
+            Error::Decode(e.to_string())
+        })?;
+        decode_result(method, response)
+    }
+
+    /// Sends a json-rpc batch calling the method once for each params and
+    /// decodes the results in the order of the params.
+    pub fn batch<P: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: impl IntoIterator<Item = P>,
+    ) -> Result<Vec<R>, Error> {
+        let requests = params
+            .into_iter()
+            .map(|params| Request::new(method, params, self.next_id()))
+            .collect::<Vec<_>>();
+        let body = serde_json::to_vec(&requests).map_err(|e| Error::Decode(e.to_string()))?;
+        tracing::trace!(
+            target: "rpc_provider",
+            "sending batch of {} {} requests",
+            requests.len(),
+            method
+        );
+        let response = self.transport.send(&body)?;
+        let mut responses: Vec<Response<Value>> = match serde_json::from_slice(&response) {
+            Ok(responses) => responses,
+            // Nodes that reject the whole batch answer with a single error,
+            // and a single result can't answer more than one request.
+            Err(e) => match serde_json::from_slice::<Response<Value>>(&response) {
+                Ok(response) => {
+                    decode_result::<Value>(method, response)?;
+                    tracing::warn!(
+                        target: "rpc_provider",
+                        "single response to a batch of {} {} requests",
+                        requests.len(),
+                        method
+                    );
+                    return Err(Error::Decode(alloc::format!(
+                        "expected {} responses, got a single response",
+                        requests.len()
+                    )));
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        target: "rpc_provider",
+                        "invalid {} batch response: {}",
+                        method,
+                        e
+                    );
+                    return Err(Error::Decode(e.to_string()));
+                }
+            },
+        };
+        requests
+            .iter()
+            .map(|request| {
+                let index = responses
+                    .iter()
+                    .position(|r| r.id == Some(request.id))
+                    .ok_or_else(|| {
+                        Error::Decode(alloc::format!("missing response {}", request.id))
+                    })?;
+                decode_result(method, responses.swap_remove(index))
+            })
+            .collect()
+    }
+
+    /// Fetches receipts with the given strategy.
+    fn receipts_with(
+        &self,
+        strategy: ReceiptStrategy,
+        block_id: BlockId,
+    ) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        match strategy {
+            ReceiptStrategy::Auto => self.detect_receipts(block_id),
+            ReceiptStrategy::BlockReceipts => {
+                self.request("eth_getBlockReceipts", [block_param(block_id)])
+            }
+            ReceiptStrategy::TransactionReceipts { batch_size } => {
+                self.transaction_receipts(block_id, batch_size)
+            }
+            ReceiptStrategy::RawReceipts => self.raw_receipts(block_id),
+        }
+    }
+
+    /// Tries each receipt method in turn and remembers the first one that
+    /// returns the receipts of a block.
+    fn detect_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        let candidates = [
+            ReceiptStrategy::BlockReceipts,
+            ReceiptStrategy::RawReceipts,
+            ReceiptStrategy::TransactionReceipts {
+                batch_size: DEFAULT_RECEIPT_BATCH_SIZE,
+            },
+        ];
+        for strategy in candidates {
+            match self.receipts_with(strategy, block_id) {
+                Err(e) if is_unsupported_method(&e) => {
+                    tracing::debug!(
+                        target: "rpc_provider",
+                        "{:?} is not supported: {}",
+                        strategy,
+                        e
+                    );
+                }
+                result => {
+                    if matches!(result, Ok(Some(_))) {
+                        tracing::debug!(
+                            target: "rpc_provider",
+                            "detected receipt strategy {:?}",
+                            strategy
+                        );
+                        self.detected.set(Some(strategy));
+                    }
+                    return result;
+                }
+            }
+        }
+        Err(Error::Unsupported)
+    }
+
+    /// Fetches the receipt of each transaction in the block, in sequential
+    /// batches. A batch that fails with a non-retryable error, such as a
+    /// node rejecting batches, is sent again one request at a time.
+    ///
+    /// Fails with [Error::BlockNotFound] if the block is reorged out
+    /// while its receipts are fetched.
+    fn transaction_receipts(
+        &self,
+        block_id: BlockId,
+        batch_size: usize,
+    ) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        let Some(block) = self.get_block_with_txs(block_id)? else {
+            return Ok(None);
+        };
+        let hashes = block
+            .transactions
+            .iter()
+            .map(|tx| tx.hash)
+            .collect::<Vec<_>>();
+        let mut receipts = Vec::with_capacity(hashes.len());
+        for chunk in hashes.chunks(batch_size.max(1)) {
+            let batch: Vec<Option<TransactionReceipt>> = match chunk {
+                [hash] => vec![self.request("eth_getTransactionReceipt", [hash])?],
+                _ => match self.batch("eth_getTransactionReceipt", chunk.iter().map(|hash| [hash]))
+                {
+                    Err(e) if !e.is_retryable() => {
+                        tracing::warn!(
+                            target: "rpc_provider",
+                            "receipt batch failed, sending each request: {}",
+                            e
+                        );
+                        chunk
+                            .iter()
+                            .map(|hash| self.request("eth_getTransactionReceipt", [hash]))
+                            .collect::<Result<_, _>>()?
+                    }
+                    result => result?,
+                },
+            };
+            for receipt in batch {
+                match receipt {
+                    Some(receipt) if receipt.block_hash == block.hash => receipts.push(receipt),
+                    _ => return Err(Error::BlockNotFound),
+                }
+            }
+        }
+        Ok(Some(receipts))
+    }
+
+    /// Fetches the consensus encoded receipts of the block and decodes them.
+    ///
+    /// Fails with [Error::BlockNotFound] if the block is reorged out
+    /// while its receipts are fetched.
+    fn raw_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        let Some(block) = self.get_block_with_txs(block_id)? else {
+            return Ok(None);
+        };
+        let hash = block.hash.ok_or(Error::BlockNotFound)?;
+        let raw: Option<Vec<Bytes>> = self.request("debug_getRawReceipts", [hash])?;
+        let raw = raw.ok_or(Error::BlockNotFound)?;
+        crate::receipts::decode_receipts(raw.iter().map(|r| r.as_ref()), &block)
+            .map(Some)
+            .map_err(|e| {
+                tracing::warn!(target: "rpc_provider", "failed to decode raw receipts: {}", e);
+                Error::Decode(e.to_string())
+            })
+    }
+}
+
+/// Decodes the result of a json-rpc response.
+/// A missing or `null` result is decoded from [Value::Null].
+fn decode_result<R: DeserializeOwned>(method: &str, response: Response<Value>) -> Result<R, Error> {
+    if let Some(error) = response.error {
+        tracing::warn!(
+            target: "rpc_provider",
+            "{} returned error {}: {}",
+            method,
+            error.code,
+            error.message
+        );
+        return Err(error.into());
+    }
+    serde_json::from_value(response.result.unwrap_or(Value::Null)).map_err(|e| {
+        tracing::warn!(target: "rpc_provider", "failed to decode {} result: {}", method, e);
+        Error::Decode(e.to_string())
+    })
+}
+
+impl<T: Transport> Provider for JsonRpcProvider<T> {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        let (method, params) = block_with_txs_request(block_id);
+        self.request(method, params)
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        self.receipts_with(self.receipt_strategy(), block_id)
+    }
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, Error> {
+        self.request("eth_getLogs", [filter_param(filter)])
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        self.request("eth_getTransactionByHash", [hash])
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.request("eth_getProof", (address, slots, block_param(block_id)))
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: B256,
+        block_id: BlockId,
+    ) -> Result<B256, Error> {
+        self.request("eth_getStorageAt", (address, slot, block_param(block_id)))
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        let chain_id: U64 = self.request("eth_chainId", [(); 0])?;
+        Ok(chain_id.to::<u64>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::FnTransport;
+    use axos_primitives::BlockKind;
+
+    /// Builds a provider whose transport answers every request with the given result.
+    fn scripted(result: &'static str) -> JsonRpcProvider<impl Transport> {
+        JsonRpcProvider::with_transport(FnTransport(move |_: &[u8]| {
+            Ok(alloc::format!(r#"{{"jsonrpc":"2.0","id":0,"result":{}}}"#, result).into_bytes())
+        }))
+    }
+
+    #[test]
+    fn test_provider_over_transport() {
+        let provider = scripted(r#""0x1""#);
+        assert_eq!(provider.chain_id(), Ok(1));
+        assert!(matches!(
+            provider.get_block_with_txs(BlockId::Number(1)),
+            Err(Error::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn test_provider_sends_request() {
+        let seen = core::cell::RefCell::new(Vec::new());
+        let provider = JsonRpcProvider::with_transport(FnTransport(|request: &[u8]| {
+            seen.borrow_mut().push(request.to_vec());
+            Ok(br#"{"jsonrpc":"2.0","id":0,"result":null}"#.to_vec())
+        }));
+        assert_eq!(provider.get_transaction(B256::ZERO), Ok(None));
+        assert_eq!(provider.get_transaction(B256::ZERO), Ok(None));
+        let requests = seen.borrow();
+        let first: Value = serde_json::from_slice(&requests[0]).unwrap();
+        let second: Value = serde_json::from_slice(&requests[1]).unwrap();
+        assert_eq!(first["method"], "eth_getTransactionByHash");
+        assert_eq!(first["id"], 0);
+        assert_eq!(second["id"], 1);
+    }
+
+    #[test]
+    fn test_provider_rpc_error() {
+        let provider = JsonRpcProvider::with_transport(FnTransport(|_: &[u8]| {
+            Ok(br#"{"jsonrpc":"2.0","id":0,"error":{"code":-32601,"message":"nope"}}"#.to_vec())
+        }));
+        assert_eq!(
+            provider.chain_id(),
+            Err(Error::Rpc {
+                code: -32601,
+                message: "nope".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_provider_rate_limited() {
+        let provider = JsonRpcProvider::with_transport(FnTransport(|_: &[u8]| {
+            Ok(
+                br#"{"jsonrpc":"2.0","id":0,"error":{"code":-32005,"message":"limit exceeded"}}"#
+                    .to_vec(),
+            )
+        }));
+        let err = provider.chain_id().unwrap_err();
+        assert_eq!(err, Error::RateLimited { retry_after: None });
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_provider_transport_error() {
+        let provider = JsonRpcProvider::with_transport(FnTransport(|_: &[u8]| Err(Error::Timeout)));
+        assert_eq!(provider.chain_id(), Err(Error::Timeout));
+    }
+
+    #[test]
+    fn test_serialize_request() {
+        let request = Request::new("eth_chainId", [0u8; 0], 7);
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":7}"#
+        );
+    }
+
+    #[test]
+    fn test_block_with_txs_request() {
+        let (method, params) = block_with_txs_request(BlockId::Number(16));
+        assert_eq!(method, "eth_getBlockByNumber");
+        assert_eq!(params, serde_json::json!(["0x10", true]));
+
+        let (method, params) = block_with_txs_request(BlockId::Kind(BlockKind::Safe));
+        assert_eq!(method, "eth_getBlockByNumber");
+        assert_eq!(params, serde_json::json!(["safe", true]));
+
+        let (method, params) = block_with_txs_request(BlockId::Hash(B256::ZERO));
+        assert_eq!(method, "eth_getBlockByHash");
+        assert_eq!(params, serde_json::json!([B256::ZERO, true]));
+    }
+
+    #[test]
+    fn test_block_param() {
+        assert_eq!(block_param(BlockId::Number(255)), serde_json::json!("0xff"));
+        assert_eq!(
+            block_param(BlockId::Kind(BlockKind::Finalized)),
+            serde_json::json!("finalized")
+        );
+        assert_eq!(
+            block_param(BlockId::Hash(B256::ZERO)),
+            serde_json::json!({ "blockHash": B256::ZERO })
+        );
+    }
+
+    #[test]
+    fn test_filter_param() {
+        use axos_primitives::Address;
+        let filter = Filter::new()
+            .from_block(1)
+            .to_block(2)
+            .address(Address::ZERO)
+            .topic(1, B256::ZERO);
+        assert_eq!(
+            filter_param(&filter),
+            serde_json::json!({
+                "fromBlock": "0x1",
+                "toBlock": "0x2",
+                "address": [Address::ZERO],
+                "topics": [null, [B256::ZERO]],
+            })
+        );
+        let filter = filter.at_block_hash(B256::ZERO);
+        assert_eq!(
+            filter_param(&filter)["blockHash"],
+            serde_json::json!(B256::ZERO)
+        );
+        assert!(filter_param(&filter).get("fromBlock").is_none());
+    }
+
+    /// Builds a provider that answers each request, batched or not, with
+    /// the result or error the handler returns for its method and params.
+    fn serving<F>(handler: F) -> JsonRpcProvider<impl Transport>
+    where
+        F: Fn(&str, &Value) -> Result<Value, i64>,
+    {
+        JsonRpcProvider::with_transport(FnTransport(move |request: &[u8]| {
+            let respond = |request: &Value| match handler(
+                request["method"].as_str().unwrap(),
+                &request["params"],
+            ) {
+                Ok(result) => {
+                    serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+                }
+                Err(code) => serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": { "code": code, "message": "the method does not exist" },
+                }),
+            };
+            let request: Value = serde_json::from_slice(request).unwrap();
+            let response = match request.as_array() {
+                // Answer out of order to exercise matching responses by id.
+                Some(batch) => Value::Array(batch.iter().rev().map(respond).collect()),
+                None => respond(&request),
+            };
+            Ok(serde_json::to_vec(&response).unwrap())
+        }))
+    }
+
+    /// A block with the given number of transactions.
+    fn receipts_block(count: u8) -> BlockWithTransactions {
+        BlockWithTransactions {
+            hash: Some(B256::with_last_byte(0xbb)),
+            number: Some(U64::from(3)),
+            transactions: (0..count)
+                .map(|i| Transaction {
+                    hash: B256::with_last_byte(i),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn receipt(hash: &Value, block_hash: B256) -> Value {
+        serde_json::json!(TransactionReceipt {
+            transaction_hash: serde_json::from_value(hash.clone()).unwrap(),
+            block_hash: Some(block_hash),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_auto_detects_block_receipts() {
+        let calls = core::cell::RefCell::new(Vec::new());
+        let provider = serving(|method, _| {
+            calls.borrow_mut().push(method.to_string());
+            Ok(serde_json::json!([]))
+        });
+        assert_eq!(provider.receipt_strategy(), ReceiptStrategy::Auto);
+        assert_eq!(provider.get_receipts(BlockId::Number(3)), Ok(Some(vec![])));
+        assert_eq!(provider.receipt_strategy(), ReceiptStrategy::BlockReceipts);
+        assert_eq!(*calls.borrow(), ["eth_getBlockReceipts"]);
+    }
+
+    #[test]
+    fn test_auto_falls_back_to_transaction_receipts() {
+        let calls = core::cell::RefCell::new(Vec::new());
+        let provider = serving(|method, params| {
+            calls.borrow_mut().push(method.to_string());
+            match method {
+                "eth_getBlockByNumber" => Ok(serde_json::json!(receipts_block(3))),
+                "eth_getTransactionReceipt" => Ok(receipt(&params[0], B256::with_last_byte(0xbb))),
+                _ => Err(METHOD_NOT_FOUND_CODE),
+            }
+        });
+        let receipts = provider.get_receipts(BlockId::Number(3)).unwrap().unwrap();
+        let hashes = receipts
+            .iter()
+            .map(|r| r.transaction_hash)
+            .collect::<Vec<_>>();
+        assert_eq!(hashes, [0, 1, 2].map(B256::with_last_byte));
+        assert_eq!(
+            provider.receipt_strategy(),
+            ReceiptStrategy::TransactionReceipts {
+                batch_size: DEFAULT_RECEIPT_BATCH_SIZE
+            }
+        );
+
+        calls.borrow_mut().clear();
+        provider.get_receipts(BlockId::Number(3)).unwrap();
+        assert_eq!(
+            *calls.borrow(),
+            [
+                "eth_getBlockByNumber",
+                "eth_getTransactionReceipt",
+                "eth_getTransactionReceipt",
+                "eth_getTransactionReceipt"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transaction_receipts_batch_size() {
+        let batches = core::cell::RefCell::new(Vec::new());
+        let provider = JsonRpcProvider::with_transport(FnTransport(|request: &[u8]| {
+            let request: Value = serde_json::from_slice(request).unwrap();
+            let response = match request.as_array() {
+                Some(batch) => {
+                    batches.borrow_mut().push(batch.len());
+                    Value::Array(
+                        batch
+                            .iter()
+                            .map(|r| {
+                                let result = receipt(&r["params"][0], B256::with_last_byte(0xbb));
+                                serde_json::json!({ "id": r["id"], "result": result })
+                            })
+                            .collect(),
+                    )
+                }
+                None => match request["method"].as_str().unwrap() {
+                    "eth_getTransactionReceipt" => {
+                        batches.borrow_mut().push(1);
+                        let result = receipt(&request["params"][0], B256::with_last_byte(0xbb));
+                        serde_json::json!({ "id": request["id"], "result": result })
+                    }
+                    _ => serde_json::json!({ "id": request["id"], "result": receipts_block(5) }),
+                },
+            };
+            Ok(serde_json::to_vec(&response).unwrap())
+        }))
+        .with_receipt_strategy(ReceiptStrategy::TransactionReceipts { batch_size: 2 });
+        let receipts = provider.get_receipts(BlockId::Number(3)).unwrap().unwrap();
+        assert_eq!(receipts.len(), 5);
+        assert_eq!(*batches.borrow(), [2, 2, 1]);
+    }
+
+    #[test]
+    fn test_transaction_receipts_batch_rejected() {
+        let requests = core::cell::RefCell::new(Vec::new());
+        let provider = JsonRpcProvider::with_transport(FnTransport(|request: &[u8]| {
+            let request: Value = serde_json::from_slice(request).unwrap();
+            if request.is_array() {
+                requests.borrow_mut().push("batch");
+                let error = serde_json::json!({ "code": -32600, "message": "batch disabled" });
+                return Ok(
+                    serde_json::to_vec(&serde_json::json!({ "id": null, "error": error })).unwrap(),
+                );
+            }
+            let result = match request["method"].as_str().unwrap() {
+                "eth_getTransactionReceipt" => {
+                    requests.borrow_mut().push("single");
+                    receipt(&request["params"][0], B256::with_last_byte(0xbb))
+                }
+                _ => serde_json::json!(receipts_block(3)),
+            };
+            Ok(
+                serde_json::to_vec(&serde_json::json!({ "id": request["id"], "result": result }))
+                    .unwrap(),
+            )
+        }))
+        .with_receipt_strategy(ReceiptStrategy::TransactionReceipts { batch_size: 4 });
+        let receipts = provider.get_receipts(BlockId::Number(3)).unwrap().unwrap();
+        let hashes = receipts
+            .iter()
+            .map(|r| r.transaction_hash)
+            .collect::<Vec<_>>();
+        assert_eq!(hashes, [0, 1, 2].map(B256::with_last_byte));
+        assert_eq!(*requests.borrow(), ["batch", "single", "single", "single"]);
+    }
+
+    #[test]
+    fn test_transaction_receipts_from_another_block() {
+        let provider = serving(|method, params| match method {
+            "eth_getBlockByNumber" => Ok(serde_json::json!(receipts_block(1))),
+            _ => Ok(receipt(&params[0], B256::with_last_byte(0xcc))),
+        })
+        .with_receipt_strategy(ReceiptStrategy::TransactionReceipts { batch_size: 4 });
+        assert_eq!(
+            provider.get_receipts(BlockId::Number(3)),
+            Err(Error::BlockNotFound)
+        );
+    }
+
+    #[test]
+    fn test_raw_receipts() {
+        let mut stream = rlp::RlpStream::new_list(4);
+        stream.append(&1u8).append(&21_000u64);
+        stream.append(&[0u8; 256].as_slice());
+        stream.begin_list(0);
+        let legacy = stream.out().to_vec();
+        let mut typed = vec![2u8];
+        typed.extend_from_slice(&legacy);
+        let raw = [Bytes::from(legacy), Bytes::from(typed)];
+
+        let provider = serving(|method, params| match method {
+            "eth_getBlockByNumber" => Ok(serde_json::json!(receipts_block(2))),
+            "debug_getRawReceipts" => {
+                assert_eq!(params[0], serde_json::json!(B256::with_last_byte(0xbb)));
+                Ok(serde_json::json!(raw))
+            }
+            _ => Err(METHOD_NOT_FOUND_CODE),
+        });
+        let receipts = provider.get_receipts(BlockId::Number(3)).unwrap().unwrap();
+        assert_eq!(provider.receipt_strategy(), ReceiptStrategy::RawReceipts);
+        assert_eq!(receipts.len(), 2);
+        assert!(receipts[1].is_success());
+        assert_eq!(receipts[1].transaction_hash, B256::with_last_byte(1));
+        assert_eq!(
+            receipts[1].cumulative_gas_used,
+            axos_primitives::U256::from(21_000)
+        );
+    }
+
+    #[test]
+    fn test_auto_without_receipt_methods() {
+        let provider = serving(|method, _| match method {
+            "eth_getBlockByNumber" => Ok(serde_json::json!(receipts_block(1))),
+            _ => Err(METHOD_NOT_FOUND_CODE),
+        });
+        assert_eq!(
+            provider.get_receipts(BlockId::Number(3)),
+            Err(Error::Unsupported)
+        );
+        assert_eq!(provider.receipt_strategy(), ReceiptStrategy::Auto);
+    }
+
+    #[test]
+    fn test_batch_rejected() {
+        let provider = JsonRpcProvider::with_transport(FnTransport(|_: &[u8]| {
+            Ok(br#"{"jsonrpc":"2.0","id":null,"error":{"code":-32600,"message":"batch disabled"}}"#.to_vec())
+        }));
+        let result: Result<Vec<U64>, _> = provider.batch("eth_chainId", [[(); 0], [(); 0]]);
+        assert_eq!(
+            result,
+            Err(Error::Rpc {
+                code: -32600,
+                message: "batch disabled".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_batch_single_result() {
+        let provider = JsonRpcProvider::with_transport(FnTransport(|_: &[u8]| {
+            Ok(br#"{"jsonrpc":"2.0","id":0,"result":"0x1"}"#.to_vec())
+        }));
+        let result: Result<Vec<U64>, _> = provider.batch("eth_chainId", [[(); 0], [(); 0]]);
+        assert_eq!(
+            result,
+            Err(Error::Decode(
+                "expected 2 responses, got a single response".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_is_unsupported_method() {
+        let rpc = |code, message: &str| Error::Rpc {
+            code,
+            message: message.to_string(),
+        };
+        assert!(is_unsupported_method(&rpc(METHOD_NOT_FOUND_CODE, "")));
+        assert!(is_unsupported_method(&rpc(
+            -32000,
+            "the method debug_getRawReceipts does not exist/is not available"
+        )));
+        assert!(!is_unsupported_method(&rpc(-32000, "header not found")));
+        assert!(!is_unsupported_method(&rpc(
+            -32602,
+            "invalid params: method argument not a hash"
+        )));
+        assert!(!is_unsupported_method(&Error::Timeout));
+    }
+
+    #[test]
+    fn test_deserialize_error_response() {
+        let json =
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"method not found"}}"#;
+        let response: Response<Value> = serde_json::from_str(json).unwrap();
+        assert!(response.result.is_none());
+        assert_eq!(response.error.unwrap().code, -32601);
+    }
+}
@@ -0,0 +1,17 @@
+//! Test Utilities
+//!
+//! Local mock servers for exercising providers without a live chain.
+//! This module is only available when the `std` and `test-utils`
+//! features are enabled.
+
+pub mod beacon;
+pub mod http;
+#[cfg(unix)]
+pub mod ipc;
+pub mod rpc;
+
+#[doc(inline)]
+pub use http::{MockRequest, MockResponse, MockServer};
+#[cfg(unix)]
+#[doc(inline)]
+pub use ipc::MockIpcServer;
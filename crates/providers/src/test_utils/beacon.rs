@@ -0,0 +1,76 @@
+//! Mock Beacon Node
+
+use std::collections::HashMap;
+use std::string::String;
+use std::vec::Vec;
+
+use axos_primitives::BlobSidecar;
+
+use super::http::{MockRequest, MockResponse, MockServer};
+
+/// A mock beacon node serving the genesis, spec and blob sidecar endpoints.
+#[derive(Debug, Clone, Default)]
+pub struct MockBeacon {
+    /// The beacon chain genesis time.
+    pub genesis_time: u64,
+    /// The number of seconds per slot.
+    pub seconds_per_slot: u64,
+    /// Blob sidecars keyed by slot.
+    pub sidecars: HashMap<u64, Vec<BlobSidecar>>,
+}
+
+impl MockBeacon {
+    /// Instantiates a new [MockBeacon].
+    pub fn new(genesis_time: u64, seconds_per_slot: u64) -> Self {
+        Self {
+            genesis_time,
+            seconds_per_slot,
+            sidecars: HashMap::new(),
+        }
+    }
+
+    /// Serves the given blob sidecars at the given slot.
+    pub fn with_sidecars(mut self, slot: u64, sidecars: Vec<BlobSidecar>) -> Self {
+        self.sidecars.insert(slot, sidecars);
+        self
+    }
+
+    /// Spawns a [MockServer] answering beacon api requests.
+    pub fn spawn(self) -> MockServer {
+        MockServer::spawn(move |req| self.handle(req))
+    }
+
+    /// Handles a single beacon api request.
+    fn handle(&self, req: &MockRequest) -> MockResponse {
+        let (path, query) = req.path.split_once('?').unwrap_or((&req.path, ""));
+        if path == "/eth/v1/beacon/genesis" {
+            return MockResponse::ok(std::format!(
+                r#"{{"data":{{"genesis_time":"{}"}}}}"#,
+                self.genesis_time
+            ));
+        }
+        if path == "/eth/v1/config/spec" {
+            return MockResponse::ok(std::format!(
+                r#"{{"data":{{"SECONDS_PER_SLOT":"{}"}}}}"#,
+                self.seconds_per_slot
+            ));
+        }
+        let Some(slot) = path.strip_prefix("/eth/v1/beacon/blob_sidecars/") else {
+            return MockResponse::not_found();
+        };
+        let Some(sidecars) = slot.parse::<u64>().ok().and_then(|s| self.sidecars.get(&s)) else {
+            return MockResponse::not_found();
+        };
+        let indices = query.strip_prefix("indices=").map(|i| {
+            i.split(',')
+                .filter_map(|i| i.parse::<u64>().ok())
+                .collect::<Vec<_>>()
+        });
+        let data = sidecars
+            .iter()
+            .filter(|s| indices.as_ref().is_none_or(|i| i.contains(&s.index)))
+            .collect::<Vec<_>>();
+        let data = serde_json::to_string(&data).unwrap_or_else(|_| String::from("[]"));
+        MockResponse::ok(std::format!(r#"{{"data":{}}}"#, data))
+    }
+}
@@ -0,0 +1,201 @@
+//! Mock HTTP Server
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::string::{String, ToString};
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+/// A request received by the [MockServer].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockRequest {
+    /// The request method.
+    pub method: String,
+    /// The request path, including any query string.
+    pub path: String,
+    /// The request body.
+    pub body: Vec<u8>,
+}
+
+/// A response returned by the [MockServer].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockResponse {
+    /// The response status code.
+    pub status: u16,
+    /// Extra response headers.
+    pub headers: Vec<(String, String)>,
+    /// The response body.
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    /// A `200 OK` response with the given body.
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: 200,
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+
+    /// A response with the given status code and an empty body.
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            ..Default::default()
+        }
+    }
+
+    /// A `404 Not Found` response with an empty body.
+    pub fn not_found() -> Self {
+        Self::status(404)
+    }
+
+    /// Adds a header to the response.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// A local HTTP/1.1 server that answers every request with a handler.
+///
+/// Connections are served one at a time on a background thread and
+/// closed after each response.
+#[derive(Debug, Clone)]
+pub struct MockServer {
+    /// The address the server is listening on.
+    addr: SocketAddr,
+    /// The requests received so far.
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    /// Spawns a new [MockServer] on a random local port.
+    pub fn spawn<F>(handler: F) -> Self
+    where
+        F: Fn(&MockRequest) -> MockResponse + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind mock server");
+        let addr = listener
+            .local_addr()
+            .expect("mock server has no local address");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let Some(request) = read_request(&mut stream) else {
+                    continue;
+                };
+                let response = handler(&request);
+                if let Ok(mut seen) = seen.lock() {
+                    seen.push(request);
+                }
+                let _ = write_response(&mut stream, &response);
+            }
+        });
+        Self { addr, requests }
+    }
+
+    /// Returns the base url of the server.
+    pub fn url(&self) -> String {
+        std::format!("http://{}", self.addr)
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the requests received by the server so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+}
+
+/// Reads a single HTTP request from the stream.
+fn read_request(stream: &mut TcpStream) -> Option<MockRequest> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or_default();
+
+    let mut body = buf[header_end..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+
+    Some(MockRequest { method, path, body })
+}
+
+/// Writes an HTTP response to the stream and closes the connection.
+fn write_response(stream: &mut TcpStream, response: &MockResponse) -> std::io::Result<()> {
+    let mut head = std::format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&std::format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(&response.body)?;
+    stream.flush()
+}
+
+/// Returns the reason phrase for the given status code.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_server_records_requests() {
+        let server = MockServer::spawn(|req| MockResponse::ok(req.path.clone().into_bytes()));
+        let body = ureq::get(&std::format!("{}/hello", server.url()))
+            .call()
+            .unwrap()
+            .into_string()
+            .unwrap();
+        assert_eq!(body, "/hello");
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(server.requests()[0].method, "GET");
+    }
+}
@@ -0,0 +1,83 @@
+//! Mock IPC Server
+
+use std::io::{BufReader, Write};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+use crate::ipc::read_json;
+
+/// Distinguishes the sockets of servers spawned by the same process.
+static NEXT_SOCKET: AtomicUsize = AtomicUsize::new(0);
+
+/// A local Unix domain socket server that answers every json-rpc
+/// message with a handler.
+///
+/// Connections are served one at a time on a background thread and
+/// kept open until the client closes them.
+#[derive(Debug, Clone)]
+pub struct MockIpcServer {
+    /// The path of the socket.
+    path: PathBuf,
+    /// The messages received so far.
+    requests: Arc<Mutex<Vec<Vec<u8>>>>,
+    /// The number of connections accepted so far.
+    connections: Arc<AtomicUsize>,
+}
+
+impl MockIpcServer {
+    /// Spawns a new [MockIpcServer] on a fresh socket in the temp directory.
+    pub fn spawn<F>(handler: F) -> Self
+    where
+        F: Fn(&[u8]) -> Vec<u8> + Send + 'static,
+    {
+        let path = std::env::temp_dir().join(std::format!(
+            "axos-{}-{}.ipc",
+            std::process::id(),
+            NEXT_SOCKET.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).expect("failed to bind mock ipc server");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let connections = Arc::new(AtomicUsize::new(0));
+        let (seen, accepted) = (Arc::clone(&requests), Arc::clone(&connections));
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                accepted.fetch_add(1, Ordering::Relaxed);
+                let mut reader = BufReader::new(stream);
+                while let Ok(Some(request)) = read_json(&mut reader) {
+                    let response = handler(&request);
+                    if let Ok(mut seen) = seen.lock() {
+                        seen.push(request);
+                    }
+                    if reader.get_mut().write_all(&response).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Self {
+            path,
+            requests,
+            connections,
+        }
+    }
+
+    /// Returns the path of the socket.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the messages received by the server so far.
+    pub fn requests(&self) -> Vec<Vec<u8>> {
+        self.requests.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    /// Returns the number of connections accepted so far.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,55 @@
+//! Mock JSON-RPC Helpers
+
+use std::string::{String, ToString};
+
+use serde_json::Value;
+
+use super::http::MockResponse;
+
+/// A canned `eth_getBlockByNumber` result for block `2` with a single deposit transaction.
+pub const BLOCK: &str = r#"{
+    "hash": "0x0000000000000000000000000000000000000000000000000000000000000002",
+    "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+    "miner": "0x4200000000000000000000000000000000000011",
+    "stateRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "transactionsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "receiptsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "number": "0x2",
+    "gasUsed": "0x5208",
+    "gasLimit": "0x1c9c380",
+    "extraData": "0x",
+    "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "timestamp": "0x64",
+    "baseFeePerGas": "0x7",
+    "transactions": [{
+        "hash": "0x00000000000000000000000000000000000000000000000000000000000000aa",
+        "nonce": "0x0",
+        "blockHash": "0x0000000000000000000000000000000000000000000000000000000000000002",
+        "blockNumber": "0x2",
+        "transactionIndex": "0x0",
+        "from": "0xdeaddeaddeaddeaddeaddeaddeaddeaddead0001",
+        "to": "0x4200000000000000000000000000000000000015",
+        "value": "0x0",
+        "gas": "0xf4240",
+        "gasPrice": "0x0",
+        "input": "0x015d8eb9",
+        "type": "0x7e",
+        "sourceHash": "0x00000000000000000000000000000000000000000000000000000000000000bb",
+        "mint": "0x0",
+        "isSystemTx": false
+    }]
+}"#;
+
+/// Wraps a json result in a json-rpc response.
+pub fn rpc_result(result: &str) -> MockResponse {
+    MockResponse::ok(std::format!(
+        r#"{{"jsonrpc":"2.0","id":0,"result":{}}}"#,
+        result
+    ))
+}
+
+/// Returns the method name of a json-rpc request body.
+pub fn rpc_method(body: &[u8]) -> String {
+    let request: Value = serde_json::from_slice(body).unwrap_or_default();
+    request["method"].as_str().unwrap_or_default().to_string()
+}
@@ -0,0 +1,58 @@
+//! Transport Trait
+//!
+//! A [Transport] moves serialized requests and responses between axos and
+//! a node. It knows nothing about the payload, so any byte channel works:
+//! HTTP, a UART, shared memory, or a host-call channel in a fault-proof
+//! program. The [JsonRpcProvider][crate::rpc::JsonRpcProvider] layers the
+//! json-rpc codec on top of a transport to implement the
+//! [Provider][crate::provider::Provider] trait.
+
+use alloc::vec::Vec;
+
+use crate::provider::Error;
+
+/// Transport Trait
+pub trait Transport {
+    /// Send a serialized request and return the serialized response.
+    fn send(&self, request: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    fn send(&self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        (**self).send(request)
+    }
+}
+
+/// A [Transport] backed by a closure.
+///
+/// Useful to plug in a channel that is a single function call, such as
+/// a host call, and to script responses in tests.
+#[derive(Clone, Copy)]
+pub struct FnTransport<F>(pub F);
+
+impl<F> core::fmt::Debug for FnTransport<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("FnTransport").finish()
+    }
+}
+
+impl<F> Transport for FnTransport<F>
+where
+    F: Fn(&[u8]) -> Result<Vec<u8>, Error>,
+{
+    fn send(&self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        (self.0)(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fn_transport_echo() {
+        let transport = FnTransport(|request: &[u8]| Ok(request.to_vec()));
+        assert_eq!(transport.send(b"ping"), Ok(b"ping".to_vec()));
+        assert_eq!(Transport::send(&&transport, b"pong"), Ok(b"pong".to_vec()));
+    }
+}
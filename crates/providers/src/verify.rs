@@ -0,0 +1,789 @@
+//! Verifying Provider
+//!
+//! The [VerifyingProvider] wraps an untrusted [Provider], such as a
+//! third-party L1 rpc endpoint, and checks everything it returns against
+//! a trusted checkpoint before handing it on:
+//!
+//! - Each header is re-encoded and hashed, and must match its reported
+//!   hash and the requested hash or number.
+//! - Each header must link to the checkpoint through parent hashes.
+//!   Descendants are linked by walking back to an already verified block,
+//!   and ancestors by walking down from the checkpoint. Missing links are
+//!   fetched by hash and verified the same way, up to a maximum gap.
+//! - Each transaction must hash to its hash, and the transactions must
+//!   match the transactions root of the header.
+//! - Receipts must match the receipts root of their verified header.
+//!
+//! Anything that fails is rejected with a [VerificationError], wrapped in
+//! [Error::Other].
+//! Transactions that are not yet mined cannot be verified and are not
+//! returned. Account proofs and the chain id are passed through unverified.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use axos_providers::provider::Provider;
+//! use axos_providers::verify::VerifyingProvider;
+//! use axos_primitives::{b256, BlockId};
+//!
+//! fn first_block(untrusted: impl Provider) {
+//!     let genesis = b256!("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3");
+//!     let provider = VerifyingProvider::from_checkpoint_hash(untrusted, genesis).unwrap();
+//!     let block = provider.get_block_with_txs(BlockId::Number(1)).unwrap();
+//! }
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+use axos_primitives::transactions::Transaction;
+use axos_primitives::{
+    keccak256, AccountProof, Address, BlockId, BlockInfo, BlockWithTransactions,
+    TransactionReceipt, B256,
+};
+
+use crate::provider::{Error, Provider};
+
+mod encode;
+mod trie;
+
+#[doc(inline)]
+pub use encode::*;
+#[doc(inline)]
+pub use trie::*;
+
+/// The default maximum number of blocks fetched to link a block to the
+/// verified chain.
+pub const DEFAULT_MAX_GAP: u64 = 256;
+
+/// Verification Errors
+///
+/// Returned when a block, transaction or receipt does not match the
+/// chain of headers it claims to belong to.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum VerificationError {
+    /// The header is missing a field needed to compute its hash.
+    IncompleteHeader {
+        /// The block number, if known.
+        number: Option<u64>,
+    },
+    /// The header does not hash to the expected hash.
+    HashMismatch {
+        /// The block number.
+        number: u64,
+        /// The expected block hash.
+        expected: B256,
+        /// The hash computed from the header.
+        computed: B256,
+    },
+    /// The provider returned a different block than requested.
+    UnexpectedNumber {
+        /// The requested block number.
+        expected: u64,
+        /// The number of the returned block.
+        found: u64,
+    },
+    /// The block does not link back to the trusted checkpoint.
+    Unlinked {
+        /// The block number.
+        number: u64,
+    },
+    /// A transaction could not be encoded or does not hash to its hash.
+    InvalidTransaction {
+        /// The block number.
+        number: u64,
+        /// The index of the transaction in the block.
+        index: u64,
+    },
+    /// The transactions do not match the transactions root.
+    TransactionsRoot {
+        /// The block number.
+        number: u64,
+        /// The transactions root in the header.
+        expected: B256,
+        /// The root computed from the transactions.
+        computed: B256,
+    },
+    /// A receipt could not be encoded.
+    InvalidReceipt {
+        /// The block number.
+        number: u64,
+        /// The index of the receipt in the block.
+        index: u64,
+    },
+    /// The receipts do not match the receipts root.
+    ReceiptsRoot {
+        /// The block number.
+        number: u64,
+        /// The receipts root in the header.
+        expected: B256,
+        /// The root computed from the receipts.
+        computed: B256,
+    },
+}
+
+impl core::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            VerificationError::IncompleteHeader { number: Some(n) } => {
+                write!(f, "header of block {} is incomplete", n)
+            }
+            VerificationError::IncompleteHeader { number: None } => {
+                write!(f, "header is incomplete")
+            }
+            VerificationError::HashMismatch {
+                number,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "block {} hashes to {}, expected {}",
+                number, computed, expected
+            ),
+            VerificationError::UnexpectedNumber { expected, found } => {
+                write!(f, "requested block {}, received block {}", expected, found)
+            }
+            VerificationError::Unlinked { number } => {
+                write!(f, "block {} does not link to the checkpoint", number)
+            }
+            VerificationError::InvalidTransaction { number, index } => {
+                write!(f, "transaction {} of block {} is invalid", index, number)
+            }
+            VerificationError::TransactionsRoot {
+                number,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "transactions of block {} have root {}, expected {}",
+                number, computed, expected
+            ),
+            VerificationError::InvalidReceipt { number, index } => {
+                write!(f, "receipt {} of block {} is invalid", index, number)
+            }
+            VerificationError::ReceiptsRoot {
+                number,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "receipts of block {} have root {}, expected {}",
+                number, computed, expected
+            ),
+        }
+    }
+}
+
+impl core::error::Error for VerificationError {}
+
+impl From<VerificationError> for Error {
+    fn from(e: VerificationError) -> Self {
+        Error::other(e)
+    }
+}
+
+/// A [Provider] that verifies the blocks of an inner provider.
+#[derive(Debug)]
+pub struct VerifyingProvider<P> {
+    /// The inner provider.
+    inner: P,
+    /// The trusted checkpoint.
+    checkpoint: BlockInfo,
+    /// The maximum number of blocks fetched to link a block.
+    max_gap: u64,
+    /// The number and parent hash of each verified block, by hash.
+    verified: RefCell<BTreeMap<B256, (u64, B256)>>,
+    /// The number and parent hash of the lowest verified checkpoint ancestor.
+    lowest: Cell<(u64, B256)>,
+}
+
+impl<P: Provider> VerifyingProvider<P> {
+    /// Instantiates a new [VerifyingProvider] anchored to a trusted checkpoint.
+    pub fn new(inner: P, checkpoint: BlockInfo) -> Self {
+        let mut verified = BTreeMap::new();
+        verified.insert(checkpoint.hash, (checkpoint.number, checkpoint.parent_hash));
+        Self {
+            inner,
+            checkpoint,
+            max_gap: DEFAULT_MAX_GAP,
+            verified: RefCell::new(verified),
+            lowest: Cell::new((checkpoint.number, checkpoint.parent_hash)),
+        }
+    }
+
+    /// Instantiates a new [VerifyingProvider] anchored to the block with
+    /// the given trusted hash, which is fetched from the inner provider.
+    pub fn from_checkpoint_hash(inner: P, hash: B256) -> Result<Self, Error> {
+        let block = inner
+            .get_block_with_txs(BlockId::Hash(hash))?
+            .ok_or(Error::BlockNotFound)?;
+        let (number, _) = verify_header(&block, Some(hash))?;
+        let checkpoint = BlockInfo::new(
+            hash,
+            number,
+            block.parent_hash,
+            block.timestamp.saturating_to(),
+        );
+        Ok(Self::new(inner, checkpoint))
+    }
+
+    /// Sets the maximum number of blocks fetched to link a block to the
+    /// verified chain. Blocks further away are rejected as unlinked.
+    pub fn with_max_gap(mut self, max_gap: u64) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    /// Returns the inner provider.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the trusted checkpoint.
+    pub fn checkpoint(&self) -> BlockInfo {
+        self.checkpoint
+    }
+
+    /// Returns true if the block with the given hash has been verified.
+    pub fn is_verified(&self, hash: B256) -> bool {
+        self.verified.borrow().contains_key(&hash)
+    }
+
+    /// Verifies a block and links it to the checkpoint.
+    pub fn verify(&self, block: &BlockWithTransactions) -> Result<(), Error> {
+        let (number, hash) = verify_header(block, None)?;
+        verify_transactions(block, number)?;
+        self.link(number, hash, block.parent_hash)
+    }
+
+    /// Links a block with a verified header to the checkpoint.
+    fn link(&self, number: u64, hash: B256, parent_hash: B256) -> Result<(), Error> {
+        if self.is_verified(hash) {
+            return Ok(());
+        }
+        if number > self.checkpoint.number {
+            self.link_descendant(number, hash, parent_hash)
+        } else {
+            self.link_ancestor(number, hash, parent_hash)
+        }
+    }
+
+    /// Links a block above the checkpoint by walking its parents back to
+    /// a verified block.
+    fn link_descendant(&self, number: u64, hash: B256, parent_hash: B256) -> Result<(), Error> {
+        let mut pending = Vec::from([(hash, number, parent_hash)]);
+        let (mut number, mut parent_hash) = (number, parent_hash);
+        loop {
+            if let Some(&(parent, _)) = self.verified.borrow().get(&parent_hash) {
+                if parent + 1 != number {
+                    return Err(VerificationError::Unlinked { number }.into());
+                }
+                break;
+            }
+            if number - 1 <= self.checkpoint.number || pending.len() as u64 > self.max_gap {
+                return Err(VerificationError::Unlinked {
+                    number: pending[0].1,
+                }
+                .into());
+            }
+            let parent = self.fetch_header(parent_hash, number - 1)?;
+            pending.push((parent_hash, number - 1, parent.parent_hash));
+            number -= 1;
+            parent_hash = parent.parent_hash;
+        }
+        let mut verified = self.verified.borrow_mut();
+        for (hash, number, parent_hash) in pending {
+            verified.insert(hash, (number, parent_hash));
+        }
+        Ok(())
+    }
+
+    /// Links a block at or below the checkpoint by walking down from the
+    /// checkpoint to the block.
+    fn link_ancestor(&self, number: u64, hash: B256, parent_hash: B256) -> Result<(), Error> {
+        let (mut lowest, mut expected) = self.lowest.get();
+        if number >= lowest {
+            // Every block from the lowest ancestor up to the checkpoint is
+            // verified, so this one is on another chain.
+            let expected = self
+                .verified
+                .borrow()
+                .iter()
+                .find(|(_, (n, _))| *n == number)
+                .map_or(self.checkpoint.hash, |(hash, _)| *hash);
+            return Err(VerificationError::HashMismatch {
+                number,
+                expected,
+                computed: hash,
+            }
+            .into());
+        }
+        if lowest - number > self.max_gap {
+            return Err(VerificationError::Unlinked { number }.into());
+        }
+        while lowest > number + 1 {
+            let ancestor = self.fetch_header(expected, lowest - 1)?;
+            self.verified
+                .borrow_mut()
+                .insert(expected, (lowest - 1, ancestor.parent_hash));
+            lowest -= 1;
+            expected = ancestor.parent_hash;
+            self.lowest.set((lowest, expected));
+        }
+        if hash != expected {
+            return Err(VerificationError::HashMismatch {
+                number,
+                expected,
+                computed: hash,
+            }
+            .into());
+        }
+        self.verified
+            .borrow_mut()
+            .insert(hash, (number, parent_hash));
+        self.lowest.set((number, parent_hash));
+        Ok(())
+    }
+
+    /// Fetches the block with the given hash and verifies its header.
+    fn fetch_header(&self, hash: B256, number: u64) -> Result<BlockWithTransactions, Error> {
+        let block = self
+            .inner
+            .get_block_with_txs(BlockId::Hash(hash))?
+            .ok_or(VerificationError::Unlinked { number })?;
+        let (found, _) = verify_header(&block, Some(hash))?;
+        if found != number {
+            return Err(VerificationError::UnexpectedNumber {
+                expected: number,
+                found,
+            }
+            .into());
+        }
+        Ok(block)
+    }
+}
+
+impl<P: Provider> Provider for VerifyingProvider<P> {
+    fn get_block_with_txs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockWithTransactions>, Error> {
+        let Some(mut block) = self.inner.get_block_with_txs(block_id)? else {
+            return Ok(None);
+        };
+        let expected = match block_id {
+            BlockId::Hash(hash) => Some(hash),
+            _ => None,
+        };
+        let (number, hash) = verify_header(&block, expected)?;
+        if let BlockId::Number(expected) = block_id {
+            if expected != number {
+                return Err(VerificationError::UnexpectedNumber {
+                    expected,
+                    found: number,
+                }
+                .into());
+            }
+        }
+        verify_transactions(&block, number)?;
+        self.link(number, hash, block.parent_hash)?;
+        block.hash = Some(hash);
+        Ok(Some(block))
+    }
+
+    fn get_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+        // Receipts are fetched by hash, so that they belong to the block
+        // that was verified even if the chain moves in between.
+        let Some(block) = self.get_block_with_txs(block_id)? else {
+            return Ok(None);
+        };
+        let hash = block.hash.unwrap_or_default();
+        let number = block.number.unwrap_or_default().to::<u64>();
+        let Some(receipts) = self.inner.get_receipts(BlockId::Hash(hash))? else {
+            return Ok(None);
+        };
+        let encoded = receipts
+            .iter()
+            .enumerate()
+            .map(|(index, receipt)| {
+                encode_receipt(receipt).ok_or(VerificationError::InvalidReceipt {
+                    number,
+                    index: index as u64,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let computed = ordered_trie_root(&encoded);
+        if computed != block.receipts_root {
+            return Err(VerificationError::ReceiptsRoot {
+                number,
+                expected: block.receipts_root,
+                computed,
+            }
+            .into());
+        }
+        Ok(Some(receipts))
+    }
+
+    fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+        let Some(tx) = self.inner.get_transaction(hash)? else {
+            return Ok(None);
+        };
+        // Transactions are served from their verified block.
+        let Some(block_hash) = tx.block_hash else {
+            return Ok(None);
+        };
+        let block = self
+            .get_block_with_txs(BlockId::Hash(block_hash))?
+            .ok_or(Error::BlockNotFound)?;
+        Ok(block.transactions.into_iter().find(|t| t.hash == hash))
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        slots: &[B256],
+        block_id: BlockId,
+    ) -> Result<AccountProof, Error> {
+        self.inner.get_proof(address, slots, block_id)
+    }
+
+    fn chain_id(&self) -> Result<u64, Error> {
+        self.inner.chain_id()
+    }
+}
+
+/// Checks that a header hashes to its reported hash and to the expected
+/// hash, if any, and returns its number and hash.
+fn verify_header(
+    block: &BlockWithTransactions,
+    expected: Option<B256>,
+) -> Result<(u64, B256), Error> {
+    let number = block.number.map(|n| n.to::<u64>());
+    let computed = header_hash(block).ok_or(VerificationError::IncompleteHeader { number })?;
+    let number = number.unwrap_or_default();
+    for expected in [block.hash, expected].into_iter().flatten() {
+        if expected != computed {
+            return Err(VerificationError::HashMismatch {
+                number,
+                expected,
+                computed,
+            }
+            .into());
+        }
+    }
+    Ok((number, computed))
+}
+
+/// Checks that each transaction hashes to its hash and that together
+/// they match the transactions root.
+fn verify_transactions(block: &BlockWithTransactions, number: u64) -> Result<(), Error> {
+    let encoded = block
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| match encode_transaction(tx) {
+            Some(encoded) if keccak256(&encoded) == tx.hash => Ok(encoded),
+            _ => Err(VerificationError::InvalidTransaction {
+                number,
+                index: index as u64,
+            }),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let computed = ordered_trie_root(&encoded);
+    if computed != block.transactions_root {
+        return Err(VerificationError::TransactionsRoot {
+            number,
+            expected: block.transactions_root,
+            computed,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use axos_primitives::{BlockKind, FixedBytes, U256, U64};
+
+    /// Returns the verification error a request failed with.
+    fn verification_error<T: core::fmt::Debug>(result: Result<T, Error>) -> VerificationError {
+        *result
+            .unwrap_err()
+            .downcast_ref::<VerificationError>()
+            .expect("verification error")
+    }
+
+    /// An untrusted provider serving a fixed set of blocks and receipts.
+    #[derive(Debug, Default)]
+    struct Chain {
+        blocks: Vec<BlockWithTransactions>,
+        receipts: BTreeMap<B256, Vec<TransactionReceipt>>,
+    }
+
+    impl Chain {
+        /// Builds a chain of blocks with one transaction each.
+        fn new(len: u64) -> Self {
+            let mut chain = Self::default();
+            let mut parent_hash = B256::ZERO;
+            for number in 0..len {
+                let block = chain.push(number, parent_hash, 0);
+                parent_hash = block;
+            }
+            chain
+        }
+
+        /// Adds a block and its receipts, and returns its hash.
+        fn push(&mut self, number: u64, parent_hash: B256, salt: u64) -> B256 {
+            let mut tx = Transaction {
+                nonce: U256::from(number),
+                gas_price: Some(U256::from(7)),
+                gas: U256::from(21_000),
+                to: Some(Address::with_last_byte(1)),
+                value: U256::from(salt),
+                v: U64::from(27),
+                r: U256::from(1),
+                s: U256::from(1),
+                ..Default::default()
+            };
+            tx.hash = keccak256(encode_transaction(&tx).unwrap());
+            let receipt = TransactionReceipt {
+                transaction_hash: tx.hash,
+                status: Some(U64::from(1)),
+                cumulative_gas_used: U256::from(21_000),
+                ..Default::default()
+            };
+            let mut block = BlockWithTransactions {
+                parent_hash,
+                uncles_hash: Some(B256::ZERO),
+                author: Some(Address::ZERO),
+                transactions_root: ordered_trie_root(&[encode_transaction(&tx).unwrap()]),
+                receipts_root: ordered_trie_root(&[encode_receipt(&receipt).unwrap()]),
+                logs_bloom: Some(Default::default()),
+                difficulty: Some(U256::ZERO),
+                number: Some(U64::from(number)),
+                gas_limit: Some(U256::from(30_000_000)),
+                gas_used: U256::from(21_000),
+                timestamp: U256::from(number * 12),
+                mix_hash: Some(B256::ZERO),
+                nonce: Some(FixedBytes::ZERO),
+                base_fee_per_gas: Some(U256::from(7)),
+                transactions: vec![tx],
+                ..Default::default()
+            };
+            let hash = header_hash(&block).unwrap();
+            block.hash = Some(hash);
+            block.transactions[0].block_hash = Some(hash);
+            self.blocks.push(block);
+            self.receipts.insert(hash, vec![receipt]);
+            hash
+        }
+
+        /// Returns the canonical block with the given number.
+        fn block(&self, number: u64) -> &BlockWithTransactions {
+            self.blocks
+                .iter()
+                .find(|b| b.number == Some(U64::from(number)))
+                .unwrap()
+        }
+
+        /// Returns a checkpoint at the given canonical block.
+        fn checkpoint(&self, number: u64) -> BlockInfo {
+            let block = self.block(number);
+            BlockInfo::new(block.hash.unwrap(), number, block.parent_hash, number * 12)
+        }
+    }
+
+    impl Provider for Chain {
+        fn get_block_with_txs(
+            &self,
+            block_id: BlockId,
+        ) -> Result<Option<BlockWithTransactions>, Error> {
+            Ok(self
+                .blocks
+                .iter()
+                .find(|b| match block_id {
+                    BlockId::Hash(hash) => b.hash == Some(hash),
+                    BlockId::Number(number) => b.number == Some(U64::from(number)),
+                    BlockId::Kind(_) => b.number == Some(U64::from(self.blocks.len() - 1)),
+                })
+                .cloned())
+        }
+
+        fn get_receipts(
+            &self,
+            block_id: BlockId,
+        ) -> Result<Option<Vec<TransactionReceipt>>, Error> {
+            match block_id {
+                BlockId::Hash(hash) => Ok(self.receipts.get(&hash).cloned()),
+                _ => Err(Error::Unsupported),
+            }
+        }
+
+        fn get_transaction(&self, hash: B256) -> Result<Option<Transaction>, Error> {
+            Ok(self
+                .blocks
+                .iter()
+                .flat_map(|b| &b.transactions)
+                .find(|t| t.hash == hash)
+                .cloned())
+        }
+    }
+
+    #[test]
+    fn test_verifies_descendants() {
+        let chain = Chain::new(8);
+        let checkpoint = chain.checkpoint(2);
+        let expected = chain.block(5).clone();
+        let provider = VerifyingProvider::new(chain, checkpoint);
+
+        let block = provider.get_block_with_txs(BlockId::Number(5)).unwrap();
+        assert_eq!(block, Some(expected));
+        // The gap to the checkpoint was linked through fetched parents.
+        for number in 3..=5 {
+            assert!(provider.is_verified(provider.inner().block(number).hash.unwrap()));
+        }
+        let latest = provider.get_block_with_txs(BlockId::Kind(BlockKind::Latest));
+        assert_eq!(latest.unwrap().unwrap().number, Some(U64::from(7)));
+
+        let receipts = provider.get_receipts(BlockId::Number(6)).unwrap().unwrap();
+        assert_eq!(receipts.len(), 1);
+        let tx = provider.inner().block(4).transactions[0].clone();
+        assert_eq!(provider.get_transaction(tx.hash), Ok(Some(tx)));
+        assert_eq!(provider.get_transaction(B256::ZERO), Ok(None));
+    }
+
+    #[test]
+    fn test_verifies_ancestors() {
+        let chain = Chain::new(6);
+        let checkpoint = chain.checkpoint(4);
+        let provider = VerifyingProvider::new(chain, checkpoint);
+        let block = provider
+            .get_block_with_txs(BlockId::Number(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.number, Some(U64::from(1)));
+        assert!(provider.is_verified(provider.inner().block(3).hash.unwrap()));
+        assert!(provider.get_block_with_txs(BlockId::Number(4)).is_ok());
+    }
+
+    #[test]
+    fn test_from_checkpoint_hash() {
+        let chain = Chain::new(3);
+        let hash = chain.block(1).hash.unwrap();
+        let provider = VerifyingProvider::from_checkpoint_hash(chain, hash).unwrap();
+        assert_eq!(provider.checkpoint().number, 1);
+        assert!(provider.get_block_with_txs(BlockId::Number(2)).is_ok());
+
+        let chain = Chain::new(3);
+        let err = VerifyingProvider::from_checkpoint_hash(chain, B256::ZERO).unwrap_err();
+        assert_eq!(err, Error::BlockNotFound);
+    }
+
+    #[test]
+    fn test_rejects_tampered_header() {
+        let mut chain = Chain::new(4);
+        let checkpoint = chain.checkpoint(0);
+        chain.blocks[2].state_root = B256::with_last_byte(1);
+        let provider = VerifyingProvider::new(chain, checkpoint);
+        assert!(matches!(
+            verification_error(provider.get_block_with_txs(BlockId::Number(2))),
+            VerificationError::HashMismatch { number: 2, .. }
+        ));
+        // Block 3 links through the tampered parent, so it is rejected too.
+        assert!(matches!(
+            verification_error(provider.get_block_with_txs(BlockId::Number(3))),
+            VerificationError::HashMismatch { number: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_rejects_wrong_block() {
+        let chain = Chain::new(3);
+        let checkpoint = chain.checkpoint(0);
+        let provider = VerifyingProvider::new(chain, checkpoint);
+        let hash = provider.inner().block(1).hash.unwrap();
+        assert!(provider.get_block_with_txs(BlockId::Hash(hash)).is_ok());
+        assert!(matches!(
+            provider.get_block_with_txs(BlockId::Kind(BlockKind::Earliest)),
+            Ok(Some(_))
+        ));
+
+        let mut chain = Chain::new(3);
+        let checkpoint = chain.checkpoint(0);
+        chain.blocks[1].number = Some(U64::from(9));
+        let provider = VerifyingProvider::new(chain, checkpoint);
+        assert!(matches!(
+            verification_error(provider.get_block_with_txs(BlockId::Hash(hash))),
+            VerificationError::HashMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_rejects_fork_of_checkpoint() {
+        let mut chain = Chain::new(3);
+        let checkpoint = chain.checkpoint(2);
+        // A block 3 built on a sibling of the checkpoint.
+        let sibling = chain.push(2, chain.block(1).hash.unwrap(), 1);
+        chain.push(3, sibling, 0);
+        let provider = VerifyingProvider::new(chain, checkpoint).with_max_gap(4);
+        assert_eq!(
+            verification_error(provider.get_block_with_txs(BlockId::Number(3))),
+            VerificationError::Unlinked { number: 3 }
+        );
+        assert!(!provider.is_verified(sibling));
+    }
+
+    #[test]
+    fn test_rejects_large_gap() {
+        let chain = Chain::new(10);
+        let checkpoint = chain.checkpoint(0);
+        let provider = VerifyingProvider::new(chain, checkpoint).with_max_gap(3);
+        assert_eq!(
+            verification_error(provider.get_block_with_txs(BlockId::Number(9))),
+            VerificationError::Unlinked { number: 9 }
+        );
+        assert!(provider.get_block_with_txs(BlockId::Number(3)).is_ok());
+        assert!(provider.get_block_with_txs(BlockId::Number(6)).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_tampered_transactions() {
+        let mut chain = Chain::new(3);
+        let checkpoint = chain.checkpoint(0);
+        chain.blocks[1].transactions[0].value = U256::from(1);
+        chain.blocks[2].transactions.clear();
+        let provider = VerifyingProvider::new(chain, checkpoint);
+        assert_eq!(
+            verification_error(provider.get_block_with_txs(BlockId::Number(1))),
+            VerificationError::InvalidTransaction {
+                number: 1,
+                index: 0
+            }
+        );
+        assert!(matches!(
+            verification_error(provider.get_block_with_txs(BlockId::Number(2))),
+            VerificationError::TransactionsRoot { number: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_rejects_tampered_receipts() {
+        let mut chain = Chain::new(3);
+        let checkpoint = chain.checkpoint(0);
+        let hash = chain.block(1).hash.unwrap();
+        chain.receipts.get_mut(&hash).unwrap()[0].status = Some(U64::ZERO);
+        let provider = VerifyingProvider::new(chain, checkpoint);
+        assert!(matches!(
+            verification_error(provider.get_receipts(BlockId::Number(1))),
+            VerificationError::ReceiptsRoot { number: 1, .. }
+        ));
+        assert!(provider.get_receipts(BlockId::Number(2)).unwrap().is_some());
+    }
+}
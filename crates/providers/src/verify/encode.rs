@@ -0,0 +1,343 @@
+//! Consensus Encoding
+//!
+//! Re-encodes the json-rpc shaped headers, transactions and receipts the
+//! [Provider][crate::provider::Provider] trait returns into their
+//! consensus rlp encoding, so that their hashes and roots can be checked.
+//! Each function returns [None] if a field the encoding needs is missing.
+
+use alloc::vec::Vec;
+
+use axos_primitives::transactions::{AccessListItem, Transaction};
+use axos_primitives::{keccak256, BlockWithTransactions, TransactionReceipt, B256, U256};
+use rlp::RlpStream;
+
+/// The type of an access list transaction.
+pub const ACCESS_LIST_TX_TYPE: u64 = 0x01;
+/// The type of a dynamic fee transaction.
+pub const DYNAMIC_FEE_TX_TYPE: u64 = 0x02;
+/// The type of a blob transaction.
+pub const BLOB_TX_TYPE: u64 = 0x03;
+/// The type of a set code transaction.
+pub const SET_CODE_TX_TYPE: u64 = 0x04;
+/// The type of a deposit transaction.
+pub const DEPOSIT_TX_TYPE: u64 = 0x7e;
+
+/// Returns the hash of a block header.
+pub fn header_hash(block: &BlockWithTransactions) -> Option<B256> {
+    header_rlp(block).map(keccak256)
+}
+
+/// Encodes a block header.
+///
+/// Fields added by forks are appended in order while they are set, and
+/// a header that sets a field without the ones before it is rejected.
+pub fn header_rlp(block: &BlockWithTransactions) -> Option<Vec<u8>> {
+    let forks = [
+        block.base_fee_per_gas.map(Field::Int),
+        block.withdrawals_root.map(Field::Hash),
+        block.blob_gas_used.map(|g| Field::Int(U256::from(g))),
+        block.excess_blob_gas.map(|g| Field::Int(U256::from(g))),
+        block.parent_beacon_block_root.map(Field::Hash),
+        block.requests_hash.map(Field::Hash),
+    ];
+    let present = forks.iter().take_while(|f| f.is_some()).count();
+    if forks[present..].iter().any(Option::is_some) {
+        return None;
+    }
+
+    let mut stream = RlpStream::new_list(15 + present);
+    stream
+        .append(&block.parent_hash.as_slice())
+        .append(&block.uncles_hash?.as_slice())
+        .append(&block.author?.as_slice())
+        .append(&block.state_root.as_slice())
+        .append(&block.transactions_root.as_slice())
+        .append(&block.receipts_root.as_slice())
+        .append(&block.logs_bloom?.as_slice());
+    append_int(&mut stream, block.difficulty?);
+    append_int(&mut stream, U256::from(block.number?));
+    append_int(&mut stream, block.gas_limit?);
+    append_int(&mut stream, block.gas_used);
+    append_int(&mut stream, block.timestamp);
+    stream
+        .append(&block.extra_data.as_ref())
+        .append(&block.mix_hash?.as_slice())
+        .append(&block.nonce?.as_slice());
+    for field in forks.into_iter().flatten() {
+        match field {
+            Field::Int(value) => append_int(&mut stream, value),
+            Field::Hash(hash) => {
+                stream.append(&hash.as_slice());
+            }
+        }
+    }
+    Some(stream.out().to_vec())
+}
+
+/// An optional header field.
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    /// An integer field.
+    Int(U256),
+    /// A hash field.
+    Hash(B256),
+}
+
+/// Encodes a transaction, prefixed with its type if it is typed.
+pub fn encode_transaction(tx: &Transaction) -> Option<Vec<u8>> {
+    let kind = tx.transaction_type.map(|t| t.to::<u64>()).unwrap_or(0);
+    let mut stream = match kind {
+        0 => {
+            let mut stream = RlpStream::new_list(9);
+            append_int(&mut stream, tx.nonce);
+            append_int(&mut stream, tx.gas_price?);
+            append_call(&mut stream, tx);
+            append_signature(&mut stream, tx);
+            return Some(stream.out().to_vec());
+        }
+        ACCESS_LIST_TX_TYPE => {
+            let mut stream = RlpStream::new_list(11);
+            append_int(&mut stream, tx.chain_id?);
+            append_int(&mut stream, tx.nonce);
+            append_int(&mut stream, tx.gas_price?);
+            append_call(&mut stream, tx);
+            append_access_list(&mut stream, tx.access_list.as_deref().unwrap_or_default());
+            stream
+        }
+        DYNAMIC_FEE_TX_TYPE | BLOB_TX_TYPE | SET_CODE_TX_TYPE => {
+            let fields = match kind {
+                BLOB_TX_TYPE => 14,
+                SET_CODE_TX_TYPE => 13,
+                _ => 12,
+            };
+            let mut stream = RlpStream::new_list(fields);
+            append_int(&mut stream, tx.chain_id?);
+            append_int(&mut stream, tx.nonce);
+            append_int(&mut stream, tx.max_priority_fee_per_gas?);
+            append_int(&mut stream, tx.max_fee_per_gas?);
+            append_call(&mut stream, tx);
+            append_access_list(&mut stream, tx.access_list.as_deref().unwrap_or_default());
+            if kind == BLOB_TX_TYPE {
+                append_int(&mut stream, tx.max_fee_per_blob_gas?);
+                let hashes = tx.blob_versioned_hashes.as_deref()?;
+                stream.begin_list(hashes.len());
+                for hash in hashes {
+                    stream.append(&hash.as_slice());
+                }
+            }
+            if kind == SET_CODE_TX_TYPE {
+                let authorizations = tx.authorization_list.as_deref()?;
+                stream.begin_list(authorizations.len());
+                for auth in authorizations {
+                    stream.begin_list(6);
+                    append_int(&mut stream, auth.chain_id);
+                    stream.append(&auth.address.as_slice());
+                    append_int(&mut stream, U256::from(auth.nonce));
+                    append_int(&mut stream, U256::from(auth.y_parity));
+                    append_int(&mut stream, auth.r);
+                    append_int(&mut stream, auth.s);
+                }
+            }
+            stream
+        }
+        DEPOSIT_TX_TYPE => {
+            let mut stream = RlpStream::new_list(8);
+            stream
+                .append(&tx.source_hash.as_slice())
+                .append(&tx.from.as_slice())
+                .append(&tx.to.as_ref().map_or(&[][..], |to| to.as_slice()));
+            append_int(&mut stream, tx.mint.unwrap_or_default());
+            append_int(&mut stream, tx.value);
+            append_int(&mut stream, tx.gas);
+            stream.append(&tx.is_system_tx).append(&tx.input.as_ref());
+            return Some(typed(kind, stream));
+        }
+        _ => return None,
+    };
+    append_signature(&mut stream, tx);
+    Some(typed(kind, stream))
+}
+
+/// Encodes a receipt, prefixed with its type if it is typed.
+///
+/// Receipts from before byzantium commit to a state root instead of a
+/// status, which json-rpc receipts do not carry, so they cannot be encoded.
+pub fn encode_receipt(receipt: &TransactionReceipt) -> Option<Vec<u8>> {
+    let kind = receipt.transaction_type.map(|t| t.to::<u64>()).unwrap_or(0);
+    let deposit = match kind {
+        DEPOSIT_TX_TYPE => [receipt.deposit_nonce, receipt.deposit_receipt_version],
+        _ => [None, None],
+    };
+    let mut stream = RlpStream::new_list(4 + deposit.iter().flatten().count());
+    append_int(&mut stream, U256::from(receipt.status?));
+    append_int(&mut stream, receipt.cumulative_gas_used);
+    stream
+        .append(&receipt.logs_bloom.as_slice())
+        .begin_list(receipt.logs.len());
+    for log in &receipt.logs {
+        stream.begin_list(3).append(&log.address.as_slice());
+        stream.begin_list(log.topics.len());
+        for topic in &log.topics {
+            stream.append(&topic.as_slice());
+        }
+        stream.append(&log.data.as_ref());
+    }
+    for value in deposit.into_iter().flatten() {
+        append_int(&mut stream, U256::from(value));
+    }
+    match kind {
+        0 => Some(stream.out().to_vec()),
+        _ => Some(typed(kind, stream)),
+    }
+}
+
+/// Prefixes an encoded payload with its type.
+fn typed(kind: u64, stream: RlpStream) -> Vec<u8> {
+    let mut encoded = Vec::from([kind as u8]);
+    encoded.extend_from_slice(&stream.out());
+    encoded
+}
+
+/// Appends the gas limit, recipient, value and input shared by every
+/// transaction type.
+fn append_call(stream: &mut RlpStream, tx: &Transaction) {
+    append_int(stream, tx.gas);
+    stream.append(&tx.to.as_ref().map_or(&[][..], |to| to.as_slice()));
+    append_int(stream, tx.value);
+    stream.append(&tx.input.as_ref());
+}
+
+/// Appends an access list.
+fn append_access_list(stream: &mut RlpStream, access_list: &[AccessListItem]) {
+    stream.begin_list(access_list.len());
+    for item in access_list {
+        stream.begin_list(2).append(&item.address.as_slice());
+        stream.begin_list(item.storage_keys.len());
+        for key in &item.storage_keys {
+            stream.append(&key.as_slice());
+        }
+    }
+}
+
+/// Appends the signature values, where `v` is the parity for typed
+/// transactions.
+fn append_signature(stream: &mut RlpStream, tx: &Transaction) {
+    append_int(stream, U256::from(tx.v));
+    append_int(stream, tx.r);
+    append_int(stream, tx.s);
+}
+
+/// Appends an integer in its minimal big-endian form.
+fn append_int(stream: &mut RlpStream, value: U256) {
+    stream.append(&value.to_be_bytes_trimmed_vec());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axos_primitives::{address, b256, Address, Bytes, FixedBytes, U64};
+
+    #[test]
+    fn test_mainnet_genesis_hash() {
+        let genesis = BlockWithTransactions {
+            uncles_hash: Some(b256!(
+                "1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+            )),
+            author: Some(Address::ZERO),
+            state_root: b256!("d7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544"),
+            transactions_root: b256!(
+                "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+            ),
+            receipts_root: b256!(
+                "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+            ),
+            logs_bloom: Some(Default::default()),
+            difficulty: Some(U256::from(0x400000000u64)),
+            number: Some(U64::ZERO),
+            gas_limit: Some(U256::from(5000)),
+            extra_data: Bytes::from_static(&[
+                0x11, 0xbb, 0xe8, 0xdb, 0x4e, 0x34, 0x7b, 0x4e, 0x8c, 0x93, 0x7c, 0x1c, 0x83, 0x70,
+                0xe4, 0xb5, 0xed, 0x33, 0xad, 0xb3, 0xdb, 0x69, 0xcb, 0xdb, 0x7a, 0x38, 0xe1, 0xe5,
+                0x0b, 0x1b, 0x82, 0xfa,
+            ]),
+            mix_hash: Some(B256::ZERO),
+            nonce: Some(FixedBytes::with_last_byte(0x42)),
+            ..Default::default()
+        };
+        assert_eq!(
+            header_hash(&genesis),
+            Some(b256!(
+                "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_header_fields_must_be_contiguous() {
+        let block = BlockWithTransactions {
+            uncles_hash: Some(B256::ZERO),
+            author: Some(Address::ZERO),
+            logs_bloom: Some(Default::default()),
+            difficulty: Some(U256::ZERO),
+            number: Some(U64::from(1)),
+            gas_limit: Some(U256::ZERO),
+            mix_hash: Some(B256::ZERO),
+            nonce: Some(FixedBytes::ZERO),
+            ..Default::default()
+        };
+        assert!(header_hash(&block).is_some());
+        let skipped = BlockWithTransactions {
+            withdrawals_root: Some(B256::ZERO),
+            ..block.clone()
+        };
+        assert_eq!(header_hash(&skipped), None);
+        let incomplete = BlockWithTransactions {
+            nonce: None,
+            ..block
+        };
+        assert_eq!(header_hash(&incomplete), None);
+    }
+
+    #[test]
+    fn test_eip155_transaction() {
+        // The example transaction from EIP-155.
+        let tx = Transaction {
+            nonce: U256::from(9),
+            gas_price: Some(U256::from(20_000_000_000u64)),
+            gas: U256::from(21_000),
+            to: Some(address!("3535353535353535353535353535353535353535")),
+            value: U256::from(1_000_000_000_000_000_000u64),
+            v: U64::from(37),
+            r: U256::from_be_bytes(
+                b256!("28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276").0,
+            ),
+            s: U256::from_be_bytes(
+                b256!("67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83").0,
+            ),
+            ..Default::default()
+        };
+        assert_eq!(
+            encode_transaction(&tx).map(Bytes::from),
+            Some(Bytes::from_static(&[
+                0xf8, 0x6c, 0x09, 0x85, 0x04, 0xa8, 0x17, 0xc8, 0x00, 0x82, 0x52, 0x08, 0x94, 0x35,
+                0x35, 0x35, 0x35, 0x35, 0x35, 0x35, 0x35, 0x35, 0x35, 0x35, 0x35, 0x35, 0x35, 0x35,
+                0x35, 0x35, 0x35, 0x35, 0x35, 0x88, 0x0d, 0xe0, 0xb6, 0xb3, 0xa7, 0x64, 0x00, 0x00,
+                0x80, 0x25, 0xa0, 0x28, 0xef, 0x61, 0x34, 0x0b, 0xd9, 0x39, 0xbc, 0x21, 0x95, 0xfe,
+                0x53, 0x75, 0x67, 0x86, 0x60, 0x03, 0xe1, 0xa1, 0x5d, 0x3c, 0x71, 0xff, 0x63, 0xe1,
+                0x59, 0x06, 0x20, 0xaa, 0x63, 0x62, 0x76, 0xa0, 0x67, 0xcb, 0xe9, 0xd8, 0x99, 0x7f,
+                0x76, 0x1a, 0xec, 0xb7, 0x03, 0x30, 0x4b, 0x38, 0x00, 0xcc, 0xf5, 0x55, 0xc9, 0xf3,
+                0xdc, 0x64, 0x21, 0x4b, 0x29, 0x7f, 0xb1, 0x96, 0x6a, 0x3b, 0x6d, 0x83,
+            ]))
+        );
+        let typed = Transaction {
+            transaction_type: Some(U64::from(DYNAMIC_FEE_TX_TYPE)),
+            ..tx.clone()
+        };
+        assert_eq!(encode_transaction(&typed), None);
+        let unknown = Transaction {
+            transaction_type: Some(U64::from(0x05)),
+            ..tx
+        };
+        assert_eq!(encode_transaction(&unknown), None);
+    }
+}
@@ -0,0 +1,158 @@
+//! Trie Roots
+//!
+//! Computes the root of a Merkle Patricia trie from its leaves, as used
+//! for the transactions and receipts roots of a block header.
+
+use alloc::vec::Vec;
+
+use axos_primitives::{keccak256, B256};
+use rlp::RlpStream;
+
+/// Returns the root of a trie keyed by the rlp-encoded index of each value.
+pub fn ordered_trie_root(values: &[Vec<u8>]) -> B256 {
+    trie_root(
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (rlp::encode(&index).to_vec(), value.as_slice())),
+    )
+}
+
+/// Returns the root of a trie holding the given keys and values.
+pub fn trie_root<'a>(entries: impl IntoIterator<Item = (Vec<u8>, &'a [u8])>) -> B256 {
+    let mut leaves = entries
+        .into_iter()
+        .map(|(key, value)| (nibbles(&key), value))
+        .collect::<Vec<_>>();
+    leaves.sort_by(|a, b| a.0.cmp(&b.0));
+    leaves.dedup_by(|a, b| a.0 == b.0);
+    keccak256(encode_node(&leaves, 0))
+}
+
+/// Encodes the node holding the given sorted leaves, whose keys all share
+/// their first `depth` nibbles.
+fn encode_node(leaves: &[(Vec<u8>, &[u8])], depth: usize) -> Vec<u8> {
+    let stream = match leaves {
+        [] => return rlp::NULL_RLP.to_vec(),
+        [(key, value)] => {
+            let mut stream = RlpStream::new_list(2);
+            stream
+                .append(&hex_prefix(&key[depth..], true))
+                .append(value);
+            stream
+        }
+        _ => {
+            let shared = shared_prefix(leaves, depth);
+            if shared > 0 {
+                let mut stream = RlpStream::new_list(2);
+                stream.append(&hex_prefix(&leaves[0].0[depth..depth + shared], false));
+                append_child(&mut stream, encode_node(leaves, depth + shared));
+                stream
+            } else {
+                let mut stream = RlpStream::new_list(17);
+                // A key that ends here sorts first and holds the branch value.
+                let (value, mut rest) = match leaves[0].0.len() == depth {
+                    true => (Some(leaves[0].1), &leaves[1..]),
+                    false => (None, leaves),
+                };
+                for nibble in 0..16u8 {
+                    let end = rest
+                        .iter()
+                        .position(|(key, _)| key[depth] != nibble)
+                        .unwrap_or(rest.len());
+                    match end {
+                        0 => stream.append_empty_data(),
+                        _ => append_child(&mut stream, encode_node(&rest[..end], depth + 1)),
+                    };
+                    rest = &rest[end..];
+                }
+                match value {
+                    Some(value) => stream.append(&value),
+                    None => stream.append_empty_data(),
+                };
+                stream
+            }
+        }
+    };
+    stream.out().to_vec()
+}
+
+/// Appends a child node, inline if shorter than a hash.
+fn append_child(stream: &mut RlpStream, node: Vec<u8>) -> &mut RlpStream {
+    match node.len() < 32 {
+        true => stream.append_raw(&node, 1),
+        false => stream.append(&keccak256(node).as_slice()),
+    }
+}
+
+/// Returns the number of nibbles after `depth` that all keys share.
+fn shared_prefix(leaves: &[(Vec<u8>, &[u8])], depth: usize) -> usize {
+    let first = &leaves[0].0[depth..];
+    leaves[1..].iter().fold(first.len(), |shared, (key, _)| {
+        first
+            .iter()
+            .zip(&key[depth..])
+            .take(shared)
+            .take_while(|(a, b)| a == b)
+            .count()
+    })
+}
+
+/// Splits bytes into nibbles.
+fn nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Compacts nibbles into their hex-prefix encoding.
+fn hex_prefix(nibbles: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 0x20 } else { 0x00 };
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = match nibbles.len() % 2 {
+        1 => {
+            encoded.push(flag | 0x10 | nibbles[0]);
+            &nibbles[1..]
+        }
+        _ => {
+            encoded.push(flag);
+            nibbles
+        }
+    };
+    encoded.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use axos_primitives::b256;
+
+    #[test]
+    fn test_empty_root() {
+        assert_eq!(
+            ordered_trie_root(&[]),
+            b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+        );
+    }
+
+    #[test]
+    fn test_trie_root_with_branch_value() {
+        let entries: [(&[u8], &[u8]); 3] = [
+            (b"doe", b"reindeer"),
+            (b"dog", b"puppy"),
+            (b"dogglesworth", b"cat"),
+        ];
+        let root = trie_root(entries.iter().map(|(k, v)| (k.to_vec(), *v)));
+        assert_eq!(
+            root,
+            b256!("8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3")
+        );
+    }
+
+    #[test]
+    fn test_ordered_root_is_order_sensitive() {
+        let values = vec![vec![1u8; 40], vec![2u8; 40]];
+        let swapped = vec![values[1].clone(), values[0].clone()];
+        assert_ne!(ordered_trie_root(&values), ordered_trie_root(&swapped));
+    }
+}